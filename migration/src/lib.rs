@@ -1,6 +1,16 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20261019_000001_create_rate_limit_table;
+mod m20261019_000002_add_post_author;
+mod m20261019_000003_add_post_version;
+mod m20261019_000004_create_blob_table;
+mod m20261019_000005_create_match_tables;
+mod m20261019_000006_create_world_snapshot_table;
+mod m20261019_000007_create_cron_lease_table;
+mod m20261019_000008_create_cron_runs_table;
+mod m20261019_000009_create_cron_job_state_table;
+mod m20261019_000010_create_webhook_delivery_table;
 
 pub struct Migrator;
 
@@ -9,6 +19,16 @@ impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
         vec![
             Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20261019_000001_create_rate_limit_table::Migration),
+            Box::new(m20261019_000002_add_post_author::Migration),
+            Box::new(m20261019_000003_add_post_version::Migration),
+            Box::new(m20261019_000004_create_blob_table::Migration),
+            Box::new(m20261019_000005_create_match_tables::Migration),
+            Box::new(m20261019_000006_create_world_snapshot_table::Migration),
+            Box::new(m20261019_000007_create_cron_lease_table::Migration),
+            Box::new(m20261019_000008_create_cron_runs_table::Migration),
+            Box::new(m20261019_000009_create_cron_job_state_table::Migration),
+            Box::new(m20261019_000010_create_webhook_delivery_table::Migration),
         ]
     }
 }
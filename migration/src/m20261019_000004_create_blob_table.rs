@@ -0,0 +1,54 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Blob::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Blob::Id))
+                    .col(integer(Blob::OwnerId))
+                    .col(string(Blob::FileName))
+                    .col(string(Blob::ContentType))
+                    .col(big_integer(Blob::Size))
+                    .col(char_len(Blob::Sha256, 64))
+                    .col(
+                        timestamp_with_time_zone(Blob::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // По хешу ищем уже загруженное содержимое для дедупликации
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_blob_sha256")
+                    .table(Blob::Table)
+                    .col(Blob::Sha256)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(Blob::Table).to_owned()).await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Blob {
+    Table,
+    Id,
+    OwnerId,
+    FileName,
+    ContentType,
+    Size,
+    Sha256,
+    CreatedAt,
+}
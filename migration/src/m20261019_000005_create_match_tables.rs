@@ -0,0 +1,118 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PlayerRating::Table)
+                    .if_not_exists()
+                    .col(integer(PlayerRating::UserId).primary_key())
+                    .col(integer(PlayerRating::Rating))
+                    .col(integer(PlayerRating::MatchesPlayed).default(0))
+                    .col(
+                        timestamp_with_time_zone(PlayerRating::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(GameMatch::Table)
+                    .if_not_exists()
+                    .col(pk_auto(GameMatch::Id))
+                    .col(big_integer(GameMatch::RoomId))
+                    .col(string(GameMatch::Status))
+                    .col(char_len(GameMatch::Seed, 16))
+                    .col(timestamp_with_time_zone(GameMatch::StartedAt))
+                    .col(timestamp_with_time_zone(GameMatch::FinishedAt))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(MatchPlayer::Table)
+                    .if_not_exists()
+                    .col(integer(MatchPlayer::MatchId))
+                    .col(integer(MatchPlayer::UserId))
+                    .col(big_integer(MatchPlayer::Score))
+                    .col(string(MatchPlayer::Outcome))
+                    .col(integer(MatchPlayer::RatingBefore))
+                    .col(integer(MatchPlayer::RatingAfter))
+                    .primary_key(
+                        Index::create()
+                            .col(MatchPlayer::MatchId)
+                            .col(MatchPlayer::UserId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_match_player_match")
+                            .from(MatchPlayer::Table, MatchPlayer::MatchId)
+                            .to(GameMatch::Table, GameMatch::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // История матчей игрока
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_match_player_user_id")
+                    .table(MatchPlayer::Table)
+                    .col(MatchPlayer::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MatchPlayer::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(GameMatch::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(PlayerRating::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PlayerRating {
+    Table,
+    UserId,
+    Rating,
+    MatchesPlayed,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum GameMatch {
+    Table,
+    Id,
+    RoomId,
+    Status,
+    Seed,
+    StartedAt,
+    FinishedAt,
+}
+
+#[derive(DeriveIden)]
+enum MatchPlayer {
+    Table,
+    MatchId,
+    UserId,
+    Score,
+    Outcome,
+    RatingBefore,
+    RatingAfter,
+}
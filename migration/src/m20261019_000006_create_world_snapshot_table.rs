@@ -0,0 +1,46 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WorldSnapshot::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WorldSnapshot::Id))
+                    .col(big_integer(WorldSnapshot::Tick))
+                    .col(integer(WorldSnapshot::Players))
+                    .col(string(WorldSnapshot::Encoding))
+                    .col(char_len(WorldSnapshot::Checksum, 16))
+                    .col(blob(WorldSnapshot::Data))
+                    .col(
+                        timestamp_with_time_zone(WorldSnapshot::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WorldSnapshot::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WorldSnapshot {
+    Table,
+    Id,
+    Tick,
+    Players,
+    Encoding,
+    Checksum,
+    Data,
+    CreatedAt,
+}
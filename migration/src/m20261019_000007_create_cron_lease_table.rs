@@ -0,0 +1,39 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CronLease::Table)
+                    .if_not_exists()
+                    .col(string(CronLease::JobName).primary_key())
+                    .col(string(CronLease::Holder))
+                    .col(timestamp_with_time_zone(CronLease::ScheduledAt))
+                    .col(timestamp_with_time_zone(CronLease::AcquiredAt))
+                    .col(timestamp_with_time_zone(CronLease::ExpiresAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CronLease::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CronLease {
+    Table,
+    JobName,
+    Holder,
+    ScheduledAt,
+    AcquiredAt,
+    ExpiresAt,
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CronRuns::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CronRuns::Id))
+                    .col(string(CronRuns::JobName))
+                    .col(timestamp_with_time_zone(CronRuns::ScheduledAt))
+                    .col(string(CronRuns::Instance))
+                    .col(timestamp_with_time_zone(CronRuns::StartedAt))
+                    .col(timestamp_with_time_zone_null(CronRuns::FinishedAt))
+                    .col(string(CronRuns::Outcome))
+                    .col(integer(CronRuns::Attempts).default(0))
+                    .col(text_null(CronRuns::Error))
+                    .to_owned(),
+            )
+            .await?;
+        // Последние запуски задачи и пропущенные срабатывания
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_cron_runs_job_scheduled")
+                    .table(CronRuns::Table)
+                    .col(CronRuns::JobName)
+                    .col(CronRuns::ScheduledAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CronRuns::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CronRuns {
+    Table,
+    Id,
+    JobName,
+    ScheduledAt,
+    Instance,
+    StartedAt,
+    FinishedAt,
+    Outcome,
+    Attempts,
+    Error,
+}
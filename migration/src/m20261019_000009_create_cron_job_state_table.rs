@@ -0,0 +1,39 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CronJobState::Table)
+                    .if_not_exists()
+                    .col(string(CronJobState::JobName).primary_key())
+                    .col(boolean(CronJobState::Paused).default(false))
+                    .col(string_null(CronJobState::Schedule))
+                    .col(string_null(CronJobState::Timezone))
+                    .col(timestamp_with_time_zone(CronJobState::UpdatedAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CronJobState::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CronJobState {
+    Table,
+    JobName,
+    Paused,
+    Schedule,
+    Timezone,
+    UpdatedAt,
+}
@@ -0,0 +1,236 @@
+use crate::api::errors::ApiError;
+use crate::api::negotiation::BodyFormat;
+use axum::extract::FromRequestParts;
+use axum::http::header::{
+    CACHE_CONTROL, CONTENT_LOCATION, CONTENT_TYPE, ETAG, EXPIRES, IF_MATCH,
+    IF_NONE_MATCH, VARY,
+};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+
+/// Значение ETag: сильный, если не указано иное.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ETag {
+    tag: String,
+    weak: bool,
+}
+
+impl ETag {
+    /// ETag ресурса с колонкой версии: `"v3"`.
+    pub fn from_version(version: i32) -> Self {
+        Self { tag: format!("v{version}"), weak: false }
+    }
+
+    /// ETag по хешу сериализованного содержимого, для ресурсов без версии.
+    pub fn from_content<T: Serialize>(value: &T) -> Self {
+        let bytes = serde_json::to_vec(value).unwrap_or_default();
+        let digest = Sha256::digest(&bytes);
+        Self { tag: hex::encode(&digest[..16]), weak: false }
+    }
+
+    /// Версия, если ETag был выдан через `from_version`, в любом
+    /// представлении.
+    pub fn version(&self) -> Option<i32> {
+        if self.weak {
+            return None;
+        }
+        let tag = self.tag.split('+').next()?;
+        tag.strip_prefix('v')?.parse().ok()
+    }
+
+    /// ETag конкретного представления: у JSON, MessagePack и CBOR разные
+    /// байты, поэтому сильный ETag у них тоже должен различаться —
+    /// `"v3"`, `"v3+msgpack"`, `"v3+cbor"`.
+    fn for_content_type(self, content_type: Option<&HeaderValue>) -> Self {
+        let format = content_type
+            .and_then(|v| v.to_str().ok())
+            .and_then(BodyFormat::parse);
+        let suffix = match format {
+            Some(BodyFormat::MessagePack) => "+msgpack",
+            Some(BodyFormat::Cbor) => "+cbor",
+            Some(BodyFormat::Json) | None => return self,
+        };
+        Self { tag: format!("{}{suffix}", self.tag), weak: self.weak }
+    }
+
+    fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        let (weak, raw) = match raw.strip_prefix("W/") {
+            Some(rest) => (true, rest),
+            None => (false, raw),
+        };
+        let tag = raw.strip_prefix('"')?.strip_suffix('"')?;
+        Some(Self { tag: tag.to_string(), weak })
+    }
+
+    fn weak_eq(&self, other: &ETag) -> bool {
+        self.tag == other.tag
+    }
+
+    fn header_value(&self) -> HeaderValue {
+        let value = if self.weak {
+            format!("W/\"{}\"", self.tag)
+        } else {
+            format!("\"{}\"", self.tag)
+        };
+        HeaderValue::from_str(&value).unwrap_or(HeaderValue::from_static(""))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TagMatch {
+    Any,
+    Tags(Vec<ETag>),
+}
+
+impl TagMatch {
+    fn from_headers(
+        headers: &HeaderMap,
+        name: impl AsRef<str>,
+    ) -> Option<Self> {
+        let values: Vec<&str> = headers
+            .get_all(name.as_ref())
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        if values.iter().any(|v| v.trim() == "*") {
+            return Some(TagMatch::Any);
+        }
+        Some(TagMatch::Tags(
+            values
+                .iter()
+                .flat_map(|v| v.split(','))
+                .filter_map(ETag::parse)
+                .collect(),
+        ))
+    }
+}
+
+/// Заголовки `If-Match` / `If-None-Match` запроса.
+#[derive(Debug, Clone, Default)]
+pub struct Preconditions {
+    if_match: Option<TagMatch>,
+    if_none_match: Option<TagMatch>,
+}
+
+impl<S> FromRequestParts<S> for Preconditions
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self {
+            if_match: TagMatch::from_headers(&parts.headers, IF_MATCH),
+            if_none_match: TagMatch::from_headers(
+                &parts.headers,
+                IF_NONE_MATCH,
+            ),
+        })
+    }
+}
+
+impl Preconditions {
+    /// `If-None-Match` совпал (слабое сравнение): клиенту можно ответить 304.
+    pub fn is_not_modified(&self, current: &ETag) -> bool {
+        match &self.if_none_match {
+            Some(TagMatch::Any) => true,
+            Some(TagMatch::Tags(tags)) => {
+                tags.iter().any(|t| t.weak_eq(current))
+            }
+            None => false,
+        }
+    }
+
+    /// Версия из `If-Match` для оптимистической блокировки в репозитории.
+    ///
+    /// `None` — условия нет (или `*`), запись безусловная. ETag, из которого
+    /// нельзя получить версию, не может совпасть ни с одной — сразу 412.
+    /// Если в заголовке несколько версий, подходит любая: тогда читается
+    /// `current` и берётся та, что с ней совпала.
+    pub async fn expected_version(
+        &self,
+        current: impl Future<Output = Result<i32, ApiError>>,
+    ) -> Result<Option<i32>, ApiError> {
+        let failed = || {
+            ApiError::PreconditionFailed(
+                "If-Match does not match current version".to_string(),
+            )
+        };
+        let versions: Vec<i32> = match &self.if_match {
+            None | Some(TagMatch::Any) => return Ok(None),
+            Some(TagMatch::Tags(tags)) => {
+                tags.iter().filter_map(ETag::version).collect()
+            }
+        };
+        match versions.as_slice() {
+            [] => Err(failed()),
+            [version] => Ok(Some(*version)),
+            _ => {
+                let current = current.await?;
+                versions
+                    .contains(&current)
+                    .then_some(Some(current))
+                    .ok_or_else(failed)
+            }
+        }
+    }
+
+    /// Ответ на GET: 304 без тела, если копия клиента актуальна,
+    /// иначе `body` с заголовком ETag. С `If-None-Match` сравнивается ETag
+    /// выбранного представления. В 304 те же заголовки кеширования, что
+    /// были бы в 200, включая `Vary`.
+    pub fn respond<T: IntoResponse>(&self, etag: ETag, body: T) -> Response {
+        let full = WithETag(etag, body).into_response();
+        let not_modified = full
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .and_then(ETag::parse)
+            .is_some_and(|etag| self.is_not_modified(&etag));
+        if !not_modified {
+            return full;
+        }
+        let mut response = StatusCode::NOT_MODIFIED.into_response();
+        for name in [ETAG, VARY, CACHE_CONTROL, CONTENT_LOCATION, EXPIRES] {
+            for value in full.headers().get_all(&name) {
+                response.headers_mut().append(&name, value.clone());
+            }
+        }
+        response
+    }
+}
+
+/// Ответ с заголовком ETag. Для MessagePack и CBOR к тегу добавляется
+/// суффикс формата; раз ETag зависит от `Accept`, он есть и в `Vary`.
+pub struct WithETag<T>(pub ETag, pub T);
+
+impl<T: IntoResponse> IntoResponse for WithETag<T> {
+    fn into_response(self) -> Response {
+        let mut response = self.1.into_response();
+        let etag =
+            self.0.for_content_type(response.headers().get(CONTENT_TYPE));
+        let headers = response.headers_mut();
+        headers.insert(ETAG, etag.header_value());
+        let varies_on_accept = headers
+            .get_all(VARY)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .any(|v| v.trim().eq_ignore_ascii_case("accept"));
+        if !varies_on_accept {
+            headers.append(VARY, HeaderValue::from_static("accept"));
+        }
+        response
+    }
+}
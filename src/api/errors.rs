@@ -0,0 +1,378 @@
+use crate::api::extractors::extractors::AuthError;
+use crate::api::extractors::webhook::SignatureError;
+use crate::core::errors::DomainError;
+use crate::infra::storage::blobs::BlobError;
+use crate::mediator::errors::{DispatchError, MediatorError};
+use axum::extract::Request;
+use axum::extract::multipart::{MultipartError, MultipartRejection};
+use axum::extract::rejection::{
+    BytesRejection, JsonRejection, PathRejection, QueryRejection,
+};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use sea_orm::DbErr;
+use serde::Serialize;
+use thiserror::Error;
+use tracing::error;
+use utoipa::ToSchema;
+
+pub const PROBLEM_JSON: &str = "application/problem+json";
+const PROBLEM_TYPE_BASE: &str = "/problems/";
+
+/// Тело ошибки по RFC 7807.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    #[schema(example = "/problems/not-found")]
+    pub type_: String,
+    #[schema(example = "Not Found")]
+    pub title: String,
+    #[schema(example = 404)]
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Post 13 not found")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "/api/v2/posts/13")]
+    pub instance: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "6f1c2b8e-1d3a-4c5f-9a7e-2b4d6f8a0c1e")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FieldError {
+    #[schema(example = "title")]
+    pub field: String,
+    #[schema(example = "must not be empty")]
+    pub message: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("Request validation failed")]
+    Validation(Vec<FieldError>),
+
+    #[error("{0}")]
+    Unauthorized(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    NotAcceptable(String),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("{0}")]
+    PreconditionFailed(String),
+
+    #[error("{0}")]
+    PayloadTooLarge(String),
+
+    #[error("{0}")]
+    UnsupportedMediaType(String),
+
+    #[error("{0}")]
+    RangeNotSatisfiable(String),
+
+    #[error("{0}")]
+    FailedDependency(String),
+
+    #[error("{0}")]
+    TooManyRequests(String),
+
+    #[error("{0}")]
+    Internal(String),
+
+    #[error("{0}")]
+    ServiceUnavailable(String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::NotAcceptable(_) => StatusCode::NOT_ACCEPTABLE,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::UnsupportedMediaType(_) => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            ApiError::RangeNotSatisfiable(_) => {
+                StatusCode::RANGE_NOT_SATISFIABLE
+            }
+            ApiError::FailedDependency(_) => StatusCode::FAILED_DEPENDENCY,
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    fn slug(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad-request",
+            ApiError::Validation(_) => "validation-error",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::NotFound(_) => "not-found",
+            ApiError::NotAcceptable(_) => "not-acceptable",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::PreconditionFailed(_) => "precondition-failed",
+            ApiError::PayloadTooLarge(_) => "payload-too-large",
+            ApiError::UnsupportedMediaType(_) => "unsupported-media-type",
+            ApiError::RangeNotSatisfiable(_) => "range-not-satisfiable",
+            ApiError::FailedDependency(_) => "failed-dependency",
+            ApiError::TooManyRequests(_) => "too-many-requests",
+            ApiError::Internal(_) => "internal-error",
+            ApiError::ServiceUnavailable(_) => "service-unavailable",
+        }
+    }
+
+    pub fn problem(&self) -> ProblemDetails {
+        let status = self.status();
+        let errors = match self {
+            ApiError::Validation(errors) => errors.clone(),
+            _ => Vec::new(),
+        };
+        ProblemDetails {
+            type_: format!("{PROBLEM_TYPE_BASE}{}", self.slug()),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: Some(self.to_string()),
+            instance: None,
+            request_id: None,
+            errors,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let problem = self.problem();
+        let mut response = problem_response(&problem);
+        // instance и request_id дописывает middleware `problem_details`
+        response.extensions_mut().insert(problem);
+        response
+    }
+}
+
+fn problem_response(problem: &ProblemDetails) -> Response {
+    let status = StatusCode::from_u16(problem.status)
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let body = serde_json::to_vec(problem).unwrap_or_default();
+    (status, [(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON))], body)
+        .into_response()
+}
+
+/// Дополняет ошибки полями, которые известны только на уровне запроса.
+pub async fn problem_details(req: Request, next: Next) -> Response {
+    let instance = req.uri().path().to_string();
+    let request_id = req
+        .headers()
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let mut response = next.run(req).await;
+    let Some(mut problem) =
+        response.extensions_mut().remove::<ProblemDetails>()
+    else {
+        return response;
+    };
+    problem.instance = Some(instance);
+    problem.request_id = request_id;
+
+    let mut patched = problem_response(&problem);
+    for (name, value) in response.headers() {
+        if name != CONTENT_TYPE && name != "content-length" {
+            // append: у Set-Cookie и подобных может быть несколько значений
+            patched.headers_mut().append(name, value.clone());
+        }
+    }
+    patched
+}
+
+impl From<MediatorError> for ApiError {
+    fn from(e: MediatorError) -> Self {
+        // Имена типов в ответ не попадают, только в лог
+        error!("Mediator error: {e}");
+        match e {
+            MediatorError::CommandNotFound(_)
+            | MediatorError::QueryNotFound(_) => ApiError::NotFound(
+                "No handler registered for this request".to_string(),
+            ),
+            MediatorError::CommandTypeMismatch(_)
+            | MediatorError::QueryTypeMismatch(_) => {
+                ApiError::Validation(vec![FieldError {
+                    field: "body".to_string(),
+                    message: "does not match the handler input".to_string(),
+                }])
+            }
+            MediatorError::CommandResultMismatch(_)
+            | MediatorError::QueryResultMismatch(_) => ApiError::Internal(
+                "Request could not be dispatched".to_string(),
+            ),
+        }
+    }
+}
+
+impl From<DispatchError> for ApiError {
+    fn from(e: DispatchError) -> Self {
+        match e {
+            DispatchError::UnknownCommand(_) => {
+                ApiError::BadRequest(e.to_string())
+            }
+            DispatchError::InvalidPayload { message, .. } => {
+                ApiError::Validation(vec![FieldError {
+                    field: "payload".to_string(),
+                    message,
+                }])
+            }
+            DispatchError::Domain(e) => e.into(),
+            DispatchError::Mediator(e) => e.into(),
+        }
+    }
+}
+
+impl From<DbErr> for ApiError {
+    fn from(e: DbErr) -> Self {
+        match e {
+            DbErr::RecordNotFound(msg) => ApiError::NotFound(msg),
+            e => {
+                // Детали ошибок БД наружу не отдаём
+                error!("Database error: {e}");
+                ApiError::Internal("Database error".to_string())
+            }
+        }
+    }
+}
+
+impl From<DomainError> for ApiError {
+    fn from(e: DomainError) -> Self {
+        match e {
+            DomainError::NotFound(msg) => ApiError::NotFound(msg),
+            DomainError::Forbidden(msg) => ApiError::Forbidden(msg),
+            DomainError::PreconditionFailed(msg) => {
+                ApiError::PreconditionFailed(msg)
+            }
+            DomainError::Conflict(msg) => ApiError::Conflict(msg),
+            DomainError::Unavailable(msg) => ApiError::ServiceUnavailable(msg),
+            DomainError::Validation(violations) => ApiError::Validation(
+                violations
+                    .into_iter()
+                    .map(|v| FieldError { field: v.field, message: v.message })
+                    .collect(),
+            ),
+            DomainError::Storage(e) => e.into(),
+            DomainError::Blob(BlobError::NotFound(key)) => {
+                // Метаданные есть, а содержимого нет — это ошибка хранилища
+                error!("Blob {key} is missing from storage");
+                ApiError::Internal("File content is missing".to_string())
+            }
+            DomainError::Blob(e) => {
+                error!("Blob storage error: {e}");
+                ApiError::Internal("File storage error".to_string())
+            }
+        }
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(e: AuthError) -> Self {
+        ApiError::Unauthorized(e.to_string())
+    }
+}
+
+impl From<SignatureError> for ApiError {
+    fn from(e: SignatureError) -> Self {
+        match e {
+            SignatureError::InvalidPayload(_) => {
+                ApiError::BadRequest(e.to_string())
+            }
+            SignatureError::BodyTooLarge => {
+                ApiError::PayloadTooLarge(e.to_string())
+            }
+            SignatureError::Replayed => ApiError::Conflict(e.to_string()),
+            e => ApiError::Unauthorized(e.to_string()),
+        }
+    }
+}
+
+impl From<BytesRejection> for ApiError {
+    fn from(e: BytesRejection) -> Self {
+        if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::PayloadTooLarge(e.body_text())
+        } else {
+            ApiError::BadRequest(e.body_text())
+        }
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(e: JsonRejection) -> Self {
+        match e {
+            JsonRejection::JsonDataError(e) => {
+                ApiError::Validation(vec![FieldError {
+                    field: "body".to_string(),
+                    message: e.body_text(),
+                }])
+            }
+            JsonRejection::MissingJsonContentType(e) => {
+                ApiError::UnsupportedMediaType(e.body_text())
+            }
+            e => ApiError::BadRequest(e.body_text()),
+        }
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(e: QueryRejection) -> Self {
+        ApiError::Validation(vec![FieldError {
+            field: "query".to_string(),
+            message: e.body_text(),
+        }])
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(e: PathRejection) -> Self {
+        ApiError::BadRequest(e.body_text())
+    }
+}
+
+impl From<MultipartRejection> for ApiError {
+    fn from(e: MultipartRejection) -> Self {
+        if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::PayloadTooLarge(e.body_text())
+        } else {
+            ApiError::BadRequest(e.body_text())
+        }
+    }
+}
+
+impl From<MultipartError> for ApiError {
+    fn from(e: MultipartError) -> Self {
+        if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            ApiError::PayloadTooLarge(e.body_text())
+        } else {
+            ApiError::BadRequest(e.body_text())
+        }
+    }
+}
@@ -1,38 +1,86 @@
-use crate::core::models::{AuthResult, AuthenticatedUser};
+use crate::api::errors::ApiError;
+use crate::configs::Config;
+use crate::core::models::{AdminUser, AuthResult, AuthenticatedUser};
 use crate::state::AppState;
 use axum::extract::{FromRef, FromRequestParts};
-use axum::http::StatusCode;
+use axum::http::HeaderMap;
 use axum::http::request::Parts;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("Missing token")]
+    MissingToken,
+
+    #[error("Invalid token format")]
+    InvalidTokenFormat,
+
+    #[error("Invalid token")]
+    InvalidToken,
+}
 
 impl<S> FromRequestParts<S> for AuthenticatedUser
 where
     S: Send + Sync,
     AppState: FromRef<S>,
 {
-    type Rejection = (StatusCode, &'static str);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
-        _state: &S,
+        state: &S,
     ) -> Result<Self, Self::Rejection> {
-        let app_state = AppState::from_ref(_state);
-
-        // Берём заголовок Authorization
-        let auth_header = parts
-            .headers
-            .get("Authorization")
-            .ok_or((StatusCode::UNAUTHORIZED, "Missing token"))?
-            .to_str()
-            .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token"))?;
-
-        let token = auth_header
-            .strip_prefix("Bearer ")
-            .ok_or((StatusCode::UNAUTHORIZED, "Invalid token format"))?;
-        // Проверка токена
-        if token != app_state.cfg.server_address {
-            return Err((StatusCode::UNAUTHORIZED, "Invalid token"));
-        }
+        let app_state = AppState::from_ref(state);
+        Ok(bearer_user(&app_state.cfg, &parts.headers)?)
+    }
+}
 
-        Ok(AuthenticatedUser(AuthResult { user_id: 42 }))
+impl<S> FromRequestParts<S> for AdminUser
+where
+    S: Send + Sync,
+    AppState: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let app_state = AppState::from_ref(state);
+        let AuthenticatedUser(user) =
+            bearer_user(&app_state.cfg, &parts.headers)?;
+        if !app_state.cfg.admin_user_ids.contains(&user.user_id) {
+            return Err(ApiError::Forbidden(
+                "Administrator role required".to_string(),
+            ));
+        }
+        Ok(AdminUser(user))
     }
 }
+
+/// Пользователь из `Authorization: Bearer`, для handler-ов, где
+/// авторизация необязательна или есть альтернатива.
+pub fn bearer_user(
+    cfg: &Config,
+    headers: &HeaderMap,
+) -> Result<AuthenticatedUser, AuthError> {
+    // Берём заголовок Authorization
+    let auth_header = headers
+        .get("Authorization")
+        .ok_or(AuthError::MissingToken)?
+        .to_str()
+        .map_err(|_| AuthError::InvalidToken)?;
+
+    let token = auth_header
+        .strip_prefix("Bearer ")
+        .ok_or(AuthError::InvalidTokenFormat)?;
+    // Проверка токена
+    authenticate(cfg, token)
+        .map(AuthenticatedUser)
+        .ok_or(AuthError::InvalidToken)
+}
+
+/// Проверка bearer-токена, общая для экстрактора и middleware.
+pub fn authenticate(cfg: &Config, token: &str) -> Option<AuthResult> {
+    (token == cfg.server_address).then_some(AuthResult { user_id: 42 })
+}
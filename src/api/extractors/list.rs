@@ -0,0 +1,229 @@
+use crate::api::errors::{ApiError, FieldError};
+use crate::core::listing::{
+    Cursor, FieldKind, Filter, FilterOp, FilterValue, ListRequest, Listable,
+    Pagination, SortDirection, SortField, list_scope,
+};
+use axum::extract::{FromRequestParts, Query};
+use axum::http::request::Parts;
+use std::marker::PhantomData;
+use utoipa::IntoParams;
+use utoipa::openapi::path::{Parameter, ParameterBuilder, ParameterIn};
+use utoipa::openapi::{ObjectBuilder, Required, Type};
+
+pub const DEFAULT_PAGE_SIZE: u64 = 20;
+pub const MAX_PAGE_SIZE: u64 = 100;
+const MAX_IN_VALUES: usize = 50;
+/// Дальше `(page - 1) * size` не помещается в OFFSET (bigint).
+const MAX_PAGE: u64 = i64::MAX as u64 / MAX_PAGE_SIZE;
+
+/// Параметры списка: `cursor` или `page`, `size`, `sort=title,-id` и
+/// фильтры `filter[field][op]=value` по полям из `R::FIELDS`.
+#[derive(Debug)]
+pub struct ListParams<R>(pub ListRequest, pub PhantomData<fn() -> R>);
+
+impl<S, R> FromRequestParts<S> for ListParams<R>
+where
+    S: Send + Sync,
+    R: Listable,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let Query(pairs) =
+            Query::<Vec<(String, String)>>::from_request_parts(parts, state)
+                .await?;
+        parse::<R>(pairs).map(|request| ListParams(request, PhantomData))
+    }
+}
+
+fn parse<R: Listable>(
+    pairs: Vec<(String, String)>,
+) -> Result<ListRequest, ApiError> {
+    let mut errors = Vec::new();
+    let mut cursor = None;
+    let mut page = None;
+    let mut size = DEFAULT_PAGE_SIZE;
+    let mut sort = Vec::new();
+    let mut filters = Vec::new();
+
+    for (key, value) in pairs {
+        match key.as_str() {
+            "cursor" => match Cursor::decode(&value) {
+                Some(c) => cursor = Some(c),
+                None => errors.push(field_error("cursor", "invalid cursor")),
+            },
+            "page" => match value.parse::<u64>() {
+                Ok(p) if (1..=MAX_PAGE).contains(&p) => page = Some(p),
+                _ => errors.push(field_error(
+                    "page",
+                    &format!("must be between 1 and {MAX_PAGE}"),
+                )),
+            },
+            "size" => match value.parse::<u64>() {
+                Ok(s) if (1..=MAX_PAGE_SIZE).contains(&s) => size = s,
+                _ => errors.push(field_error(
+                    "size",
+                    &format!("must be between 1 and {MAX_PAGE_SIZE}"),
+                )),
+            },
+            "sort" => {
+                for item in value.split(',').filter(|s| !s.is_empty()) {
+                    let (name, direction) = match item.strip_prefix('-') {
+                        Some(name) => (name, SortDirection::Desc),
+                        None => (item, SortDirection::Asc),
+                    };
+                    match R::field(name).filter(|f| f.sortable) {
+                        Some(field) => {
+                            sort.push(SortField { field, direction })
+                        }
+                        None => errors.push(field_error(
+                            "sort",
+                            &format!("cannot sort by {name}"),
+                        )),
+                    }
+                }
+            }
+            key if key.starts_with("filter[") => {
+                match parse_filter::<R>(key, &value) {
+                    Ok(filter) => filters.push(filter),
+                    Err(e) => errors.push(e),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Tie-breaker в конце делает порядок строгим, а курсоры стабильными
+    if !sort.iter().any(|s| s.field.name == R::TIE_BREAKER)
+        && let Some(field) = R::field(R::TIE_BREAKER)
+    {
+        sort.push(SortField { field, direction: SortDirection::Asc });
+    }
+
+    if cursor.is_some() && page.is_some() {
+        errors.push(field_error("cursor", "cannot be combined with page"));
+    }
+    if let Some(c) = &cursor
+        && (!c.fits(&sort) || c.scope != list_scope(&sort, &filters))
+    {
+        errors.push(field_error("cursor", "does not match sort or filters"));
+    }
+    if !errors.is_empty() {
+        return Err(ApiError::Validation(errors));
+    }
+
+    let pagination = match page {
+        Some(page) => Pagination::Page(page),
+        None => Pagination::Cursor(cursor),
+    };
+    Ok(ListRequest { pagination, size, sort, filters })
+}
+
+/// `filter[field]=v` или `filter[field][op]=v`.
+fn parse_filter<R: Listable>(
+    key: &str,
+    value: &str,
+) -> Result<Filter, FieldError> {
+    let inner = key
+        .strip_prefix("filter[")
+        .and_then(|k| k.strip_suffix(']'))
+        .ok_or_else(|| field_error(key, "invalid filter syntax"))?;
+    let (name, op) = match inner.split_once("][") {
+        Some((name, op)) => (name, op),
+        None => (inner, "eq"),
+    };
+    let field = R::field(name)
+        .filter(|f| f.filterable)
+        .ok_or_else(|| field_error(key, &format!("cannot filter by {name}")))?;
+    let op = FilterOp::parse(op)
+        .ok_or_else(|| field_error(key, &format!("unknown operator {op}")))?;
+    if op == FilterOp::Like && field.kind != FieldKind::Text {
+        return Err(field_error(key, "like is only allowed on text fields"));
+    }
+
+    let raw: Vec<&str> = match op {
+        FilterOp::In => value.split(',').collect(),
+        _ => vec![value],
+    };
+    if raw.len() > MAX_IN_VALUES {
+        return Err(field_error(
+            key,
+            &format!("at most {MAX_IN_VALUES} values allowed"),
+        ));
+    }
+    let values = raw
+        .into_iter()
+        .map(|v| match field.kind {
+            FieldKind::Int => v.trim().parse().map(FilterValue::Int),
+            FieldKind::Text => Ok(FilterValue::Text(v.to_string())),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| field_error(key, "must be an integer"))?;
+
+    Ok(Filter { field, op, values })
+}
+
+fn field_error(field: &str, message: &str) -> FieldError {
+    FieldError { field: field.to_string(), message: message.to_string() }
+}
+
+impl<R: Listable> IntoParams for ListParams<R> {
+    fn into_params(
+        _parameter_in_provider: impl Fn() -> Option<ParameterIn>,
+    ) -> Vec<Parameter> {
+        let sortable: Vec<&str> =
+            R::FIELDS.iter().filter(|f| f.sortable).map(|f| f.name).collect();
+        let mut params = vec![
+            query_param(
+                "cursor",
+                Type::String,
+                "Курсор из next_cursor/prev_cursor предыдущей страницы",
+            ),
+            query_param(
+                "page",
+                Type::Integer,
+                "Номер страницы с 1 (вместо cursor)",
+            ),
+            query_param(
+                "size",
+                Type::Integer,
+                &format!(
+                    "Размер страницы, по умолчанию {DEFAULT_PAGE_SIZE}, максимум {MAX_PAGE_SIZE}"
+                ),
+            ),
+            query_param(
+                "sort",
+                Type::String,
+                &format!(
+                    "Поля через запятую, `-` для убывания: {}",
+                    sortable.join(", ")
+                ),
+            ),
+        ];
+        for field in R::FIELDS.iter().filter(|f| f.filterable) {
+            let ops = match field.kind {
+                FieldKind::Int => "eq, ne, gt, gte, lt, lte, in",
+                FieldKind::Text => "eq, ne, gt, gte, lt, lte, in, like",
+            };
+            params.push(query_param(
+                &format!("filter[{}][op]", field.name),
+                Type::String,
+                &format!("Фильтр по {}, операторы: {ops}", field.name),
+            ));
+        }
+        params
+    }
+}
+
+fn query_param(name: &str, ty: Type, description: &str) -> Parameter {
+    ParameterBuilder::new()
+        .name(name)
+        .parameter_in(ParameterIn::Query)
+        .required(Required::False)
+        .description(Some(description))
+        .schema(Some(ObjectBuilder::new().schema_type(ty)))
+        .build()
+}
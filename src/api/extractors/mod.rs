@@ -1 +1,6 @@
+#[allow(clippy::module_inception)]
 pub mod extractors;
+pub mod json;
+pub mod list;
+pub mod patch;
+pub mod webhook;
@@ -0,0 +1,88 @@
+use crate::api::errors::{ApiError, FieldError};
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::header::CONTENT_TYPE;
+use json_patch::Patch;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+pub const MERGE_PATCH_JSON: &str = "application/merge-patch+json";
+pub const JSON_PATCH_JSON: &str = "application/json-patch+json";
+
+/// Тело PATCH-запроса: RFC 7396 Merge Patch или RFC 6902 JSON Patch,
+/// формат выбирается по `Content-Type`.
+#[derive(Debug)]
+pub enum PatchDocument {
+    Merge(Value),
+    Json(Patch),
+}
+
+impl<S> FromRequest<S> for PatchDocument
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(
+        req: Request,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let content_type = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map(|v| v.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        let body = Bytes::from_request(req, state).await?;
+
+        match content_type.as_str() {
+            MERGE_PATCH_JSON => {
+                let patch: Value = serde_json::from_slice(&body)
+                    .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+                if !patch.is_object() {
+                    return Err(ApiError::BadRequest(
+                        "Merge patch must be a JSON object".to_string(),
+                    ));
+                }
+                Ok(PatchDocument::Merge(patch))
+            }
+            JSON_PATCH_JSON => serde_json::from_slice(&body)
+                .map(PatchDocument::Json)
+                .map_err(|e| ApiError::BadRequest(e.to_string())),
+            _ => Err(ApiError::UnsupportedMediaType(format!(
+                "Expected {MERGE_PATCH_JSON} or {JSON_PATCH_JSON}"
+            ))),
+        }
+    }
+}
+
+impl PatchDocument {
+    /// Применяет патч к текущему состоянию и заново разбирает результат
+    /// в `T`, так что удаление обязательного поля или неверный тип
+    /// значения дают 422, а не молча проходят дальше.
+    pub fn apply<T>(&self, current: &T) -> Result<T, ApiError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let mut doc = serde_json::to_value(current)
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+        match self {
+            PatchDocument::Merge(patch) => json_patch::merge(&mut doc, patch),
+            PatchDocument::Json(patch) => json_patch::patch(&mut doc, patch)
+                .map_err(|e| {
+                    ApiError::Validation(vec![FieldError {
+                        field: format!("patch[{}]", e.operation),
+                        message: e.to_string(),
+                    }])
+                })?,
+        }
+        serde_json::from_value(doc).map_err(|e| {
+            ApiError::Validation(vec![FieldError {
+                field: "body".to_string(),
+                message: e.to_string(),
+            }])
+        })
+    }
+}
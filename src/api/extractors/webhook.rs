@@ -0,0 +1,174 @@
+use crate::api::errors::ApiError;
+use crate::infra::storage::webhook_deliveries::WebhookDeliveryRepository;
+use crate::state::AppState;
+use axum::body::Bytes;
+use axum::extract::{FromRef, FromRequest, Request};
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Signature-Timestamp";
+
+/// JSON-тело запроса, подписанное партнёром общим секретом.
+///
+/// Подпись: `X-Signature: sha256=<hex>` от строки `{timestamp}.{body}`,
+/// где `timestamp` (unix-секунды) передаётся в `X-Signature-Timestamp`.
+/// Допускается несколько подписей через запятую, а проверка идёт по всем
+/// активным секретам из `Config::webhook_secrets`, чтобы секреты можно
+/// было ротировать без простоя.
+///
+/// Подписанное содержимое принимается один раз: повтор той же доставки,
+/// пока её подпись не устарела, отклоняется, даже если пришёл на другой
+/// инстанс.
+#[derive(Debug)]
+pub struct SignedJson<T>(pub T);
+
+#[derive(Debug, Error)]
+pub enum SignatureError {
+    #[error("Missing signature timestamp")]
+    MissingTimestamp,
+
+    #[error("Invalid signature timestamp")]
+    InvalidTimestamp,
+
+    #[error("Signature expired")]
+    Expired,
+
+    #[error("Missing signature")]
+    MissingSignature,
+
+    #[error("Invalid signature format")]
+    InvalidFormat,
+
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    #[error("Unreadable body")]
+    UnreadableBody,
+
+    #[error("Body is too large")]
+    BodyTooLarge,
+
+    #[error("Webhook already delivered")]
+    Replayed,
+
+    #[error("Invalid JSON payload: {0}")]
+    InvalidPayload(String),
+}
+
+impl<S, T> FromRequest<S> for SignedJson<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+    AppState: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(
+        req: Request,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let app_state = AppState::from_ref(state);
+
+        let timestamp = header_str(&req, TIMESTAMP_HEADER)
+            .ok_or(SignatureError::MissingTimestamp)?
+            .to_string();
+        let signatures: Vec<Vec<u8>> = header_str(&req, SIGNATURE_HEADER)
+            .ok_or(SignatureError::MissingSignature)?
+            .split(',')
+            .filter_map(|s| s.trim().strip_prefix("sha256="))
+            .filter_map(|s| hex::decode(s).ok())
+            .collect();
+        if signatures.is_empty() {
+            return Err(SignatureError::InvalidFormat.into());
+        }
+
+        // Защита от повторной отправки перехваченного запроса
+        let sent_at: u64 =
+            timestamp.parse().map_err(|_| SignatureError::InvalidTimestamp)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        if now.abs_diff(sent_at) > app_state.cfg.webhook_tolerance_secs {
+            return Err(SignatureError::Expired.into());
+        }
+
+        let body = Bytes::from_request(req, state).await.map_err(|e| {
+            if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+                SignatureError::BodyTooLarge
+            } else {
+                SignatureError::UnreadableBody
+            }
+        })?;
+
+        if !verify(
+            &app_state.cfg.webhook_secrets,
+            &timestamp,
+            &body,
+            &signatures,
+        ) {
+            return Err(SignatureError::InvalidSignature.into());
+        }
+
+        // Невалидное тело не запоминаем: партнёр может повторить ту же
+        // доставку, и её нельзя отклонить как повтор
+        let payload = serde_json::from_slice(&body)
+            .map_err(|e| SignatureError::InvalidPayload(e.to_string()))?;
+
+        // Запоминаем до конца окна, в котором подпись ещё принимается
+        let expires_at = DateTime::<Utc>::from_timestamp(
+            (sent_at + app_state.cfg.webhook_tolerance_secs) as i64,
+            0,
+        )
+        .unwrap_or_default();
+        let deliveries = WebhookDeliveryRepository::new(app_state.db.clone());
+        if !deliveries.remember(&digest(&timestamp, &body), expires_at).await? {
+            return Err(SignatureError::Replayed.into());
+        }
+
+        Ok(SignedJson(payload))
+    }
+}
+
+fn header_str<'a>(req: &'a Request, name: &str) -> Option<&'a str> {
+    req.headers().get(name)?.to_str().ok()
+}
+
+/// Отпечаток подписанного содержимого: не зависит от того, какими
+/// секретами и в каком порядке его подписали.
+fn digest(timestamp: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(timestamp.as_bytes());
+    hasher.update(b".");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn verify(
+    secrets: &[String],
+    timestamp: &str,
+    body: &[u8],
+    signatures: &[Vec<u8>],
+) -> bool {
+    secrets.iter().any(|secret| {
+        signatures.iter().any(|signature| {
+            let Ok(mut mac) = HmacSha256::new_from_slice(secret.as_bytes())
+            else {
+                return false;
+            };
+            mac.update(timestamp.as_bytes());
+            mac.update(b".");
+            mac.update(body);
+            // verify_slice сравнивает за постоянное время
+            mac.verify_slice(signature).is_ok()
+        })
+    })
+}
@@ -0,0 +1,200 @@
+use crate::api::errors::ApiError;
+use crate::api::extractors::extractors::authenticate;
+use crate::configs::{Config, RateLimitStoreKind, RateQuota};
+use crate::core::rate_limit::{Decision, RateLimitStore};
+use crate::infra::storage::rate_limit::{
+    MemoryRateLimitStore, PostgresRateLimitStore,
+};
+use crate::state::AppState;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use sea_orm::DatabaseConnection;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::{MissedTickBehavior, interval};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+fn apply_headers(decision: &Decision, headers: &mut HeaderMap) {
+    headers.insert("RateLimit-Limit", HeaderValue::from(decision.limit));
+    headers
+        .insert("RateLimit-Remaining", HeaderValue::from(decision.remaining));
+    headers.insert(
+        "RateLimit-Reset",
+        HeaderValue::from(ceil_secs(decision.reset_ms)),
+    );
+    if !decision.allowed {
+        headers.insert(
+            "Retry-After",
+            HeaderValue::from(ceil_secs(decision.retry_after_ms)),
+        );
+    }
+}
+
+fn ceil_secs(ms: i64) -> i64 {
+    (ms.max(0) + 999) / 1000
+}
+
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    default: RateQuota,
+    overrides: Vec<(String, RateQuota)>,
+    trusted_proxies: Vec<IpAddr>,
+    /// SHA-256 известных API-ключей
+    api_keys: HashSet<String>,
+    purge_every: Duration,
+}
+
+impl RateLimiter {
+    pub fn new(cfg: &Config, db: DatabaseConnection) -> Self {
+        let store: Arc<dyn RateLimitStore> = match cfg.rate_limit_store {
+            RateLimitStoreKind::Memory => {
+                Arc::new(MemoryRateLimitStore::default())
+            }
+            RateLimitStoreKind::Postgres => {
+                Arc::new(PostgresRateLimitStore::new(db))
+            }
+        };
+        let mut overrides = cfg.rate_limit_overrides.clone();
+        // Самый длинный префикс проверяем первым
+        overrides.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        Self {
+            store,
+            default: cfg.rate_limit_default,
+            overrides,
+            trusted_proxies: cfg.trusted_proxies.clone(),
+            api_keys: cfg.api_keys.iter().map(key_digest).collect(),
+            purge_every: Duration::from_secs(cfg.rate_limit_purge_secs),
+        }
+    }
+
+    /// Периодически удаляет из хранилища ключи с истёкшим TAT, иначе
+    /// в нём остаётся каждый клиент, когда-либо пришедший в сервис.
+    pub async fn run_purge(self: Arc<Self>, shutdown: CancellationToken) {
+        let mut ticker = interval(self.purge_every);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            match self.store.purge(now_ms()).await {
+                Ok(0) => {}
+                Ok(purged) => debug!("🧹 Purged {purged} rate limit keys"),
+                Err(e) => warn!("Rate limit purge failed: {e:?}"),
+            }
+        }
+        info!("🛑 Rate limit purge stopped gracefully");
+    }
+
+    fn quota_for(&self, path: &str) -> (&str, RateQuota) {
+        self.overrides
+            .iter()
+            .find(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .map(|(prefix, quota)| (prefix.as_str(), *quota))
+            .unwrap_or(("*", self.default))
+    }
+
+    /// Ключ клиента: пользователь, затем известный API-ключ, затем IP.
+    /// Неизвестный ключ идёт в корзину IP, иначе новый ключ на каждый
+    /// запрос обходил бы лимит.
+    fn client_key(&self, cfg: &Config, req: &Request) -> String {
+        let headers = req.headers();
+        let bearer = headers
+            .get("Authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if let Some(user) = bearer.and_then(|t| authenticate(cfg, t)) {
+            return format!("user:{}", user.user_id);
+        }
+        if let Some(api_key) = headers.get(API_KEY_HEADER) {
+            // Сырые ключи в хранилище не кладём
+            let digest = key_digest(api_key);
+            if self.api_keys.contains(&digest) {
+                return format!("key:{digest}");
+            }
+        }
+        let peer = req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        match self.client_ip(peer, headers) {
+            Some(ip) => format!("ip:{ip}"),
+            None => "ip:unknown".to_string(),
+        }
+    }
+
+    /// `X-Forwarded-For` учитываем только от доверенных прокси и берём
+    /// самый правый адрес, который не принадлежит им.
+    fn client_ip(
+        &self,
+        peer: Option<IpAddr>,
+        headers: &HeaderMap,
+    ) -> Option<IpAddr> {
+        let peer = peer?;
+        if !self.trusted_proxies.contains(&peer) {
+            return Some(peer);
+        }
+        let forwarded: Vec<IpAddr> = headers
+            .get_all("X-Forwarded-For")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|ip| ip.trim().parse().ok())
+            .collect();
+        forwarded
+            .iter()
+            .rev()
+            .find(|ip| !self.trusted_proxies.contains(ip))
+            .or(forwarded.first())
+            .copied()
+            .or(Some(peer))
+    }
+}
+
+pub async fn rate_limit(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let limiter = &state.rate_limiter;
+    let (scope, quota) = limiter.quota_for(req.uri().path());
+    let key = format!("{scope}|{}", limiter.client_key(&state.cfg, &req));
+
+    let decision = match limiter.store.check(&key, quota.into(), now_ms()).await
+    {
+        Ok(decision) => decision,
+        Err(e) => {
+            // Недоступное хранилище не должно ронять API
+            warn!("Rate limit store error, letting request through: {e:?}");
+            return next.run(req).await;
+        }
+    };
+
+    let mut response = if decision.allowed {
+        next.run(req).await
+    } else {
+        ApiError::TooManyRequests("Rate limit exceeded".to_string())
+            .into_response()
+    };
+    apply_headers(&decision, response.headers_mut());
+    response
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn key_digest(key: impl AsRef<[u8]>) -> String {
+    hex::encode(Sha256::digest(key))
+}
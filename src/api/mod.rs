@@ -1,6 +1,11 @@
+pub mod conditional;
+pub mod errors;
 pub mod extractors;
+pub mod middleware;
+pub mod negotiation;
 pub mod router;
 pub mod server;
 pub mod swagger;
 pub mod v1;
 pub mod v2;
+pub mod ws;
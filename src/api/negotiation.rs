@@ -0,0 +1,191 @@
+use crate::api::errors::{ApiError, FieldError};
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::header::{ACCEPT, CONTENT_TYPE, VARY};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+pub const JSON: &str = "application/json";
+pub const MSGPACK: &str = "application/msgpack";
+pub const CBOR: &str = "application/cbor";
+
+/// Расширение OpenAPI-операции, которая принимает и отдаёт тело через
+/// `Negotiated`: `extensions(("x-negotiated" = json!(true)))` в
+/// `utoipa::path`.
+pub const NEGOTIATED: &str = "x-negotiated";
+
+tokio::task_local! {
+    static RESPONSE_FORMAT: BodyFormat;
+}
+
+/// Формат тела запроса или ответа.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyFormat {
+    #[default]
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+impl BodyFormat {
+    pub fn parse(media_type: &str) -> Option<Self> {
+        let media_type = media_type.split(';').next()?.trim();
+        match media_type.to_ascii_lowercase().as_str() {
+            JSON => Some(BodyFormat::Json),
+            t if t.starts_with("application/") && t.ends_with("+json") => {
+                Some(BodyFormat::Json)
+            }
+            MSGPACK | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(BodyFormat::MessagePack)
+            }
+            CBOR => Some(BodyFormat::Cbor),
+            _ => None,
+        }
+    }
+
+    /// Формат с наибольшим `q` из `Accept`; при равных весах — первый
+    /// в списке. Без подходящего типа отвечаем JSON.
+    pub fn from_accept(headers: &HeaderMap) -> Self {
+        let Some(accept) = headers.get(ACCEPT).and_then(|v| v.to_str().ok())
+        else {
+            return BodyFormat::Json;
+        };
+        let mut best: Option<(BodyFormat, f32)> = None;
+        for item in accept.split(',') {
+            let mut parts = item.split(';');
+            let media_type = parts.next().unwrap_or_default().trim();
+            let format = match media_type {
+                "*/*" | "application/*" => Some(BodyFormat::Json),
+                t => Self::parse(t),
+            };
+            let q = parts
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if let Some(format) = format
+                && q > 0.0
+                && best.is_none_or(|(_, best_q)| q > best_q)
+            {
+                best = Some((format, q));
+            }
+        }
+        best.map(|(format, _)| format).unwrap_or_default()
+    }
+
+    pub fn media_type(self) -> &'static str {
+        match self {
+            BodyFormat::Json => JSON,
+            BodyFormat::MessagePack => MSGPACK,
+            BodyFormat::Cbor => CBOR,
+        }
+    }
+
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            BodyFormat::Json => {
+                serde_json::to_vec(value).map_err(|e| e.to_string())
+            }
+            // Структуры как map с именами полей, чтобы клиенту не нужно было
+            // знать порядок полей
+            BodyFormat::MessagePack => {
+                rmp_serde::to_vec_named(value).map_err(|e| e.to_string())
+            }
+            BodyFormat::Cbor => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(value, &mut buf)
+                    .map_err(|e| e.to_string())?;
+                Ok(buf)
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(
+        self,
+        body: &[u8],
+    ) -> Result<T, ApiError> {
+        match self {
+            BodyFormat::Json => serde_json::from_slice(body).map_err(|e| {
+                if e.is_data() {
+                    invalid_body(e.to_string())
+                } else {
+                    ApiError::BadRequest(e.to_string())
+                }
+            }),
+            BodyFormat::MessagePack => rmp_serde::from_slice(body)
+                .map_err(|e| invalid_body(e.to_string())),
+            BodyFormat::Cbor => ciborium::from_reader(body)
+                .map_err(|e| invalid_body(e.to_string())),
+        }
+    }
+}
+
+fn invalid_body(message: String) -> ApiError {
+    ApiError::Validation(vec![FieldError {
+        field: "body".to_string(),
+        message,
+    }])
+}
+
+/// Запоминает формат ответа из `Accept` на время обработки запроса:
+/// `Negotiated<T>` читает его в `into_response`, поэтому handler-ам
+/// не нужно принимать заголовки.
+pub async fn content_negotiation(req: Request, next: Next) -> Response {
+    let format = BodyFormat::from_accept(req.headers());
+    RESPONSE_FORMAT.scope(format, next.run(req)).await
+}
+
+/// Тело в JSON, MessagePack или CBOR.
+///
+/// Как extractor выбирает формат по `Content-Type`, как ответ — по
+/// `Accept` запроса (через middleware `content_negotiation`).
+#[derive(Debug)]
+pub struct Negotiated<T>(pub T);
+
+impl<S, T> FromRequest<S> for Negotiated<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(
+        req: Request,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let format = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(BodyFormat::parse)
+            .ok_or_else(|| {
+                ApiError::UnsupportedMediaType(format!(
+                    "Expected {JSON}, {MSGPACK} or {CBOR}"
+                ))
+            })?;
+        let body = Bytes::from_request(req, state).await?;
+        format.decode(&body).map(Negotiated)
+    }
+}
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        let format = RESPONSE_FORMAT.try_with(|f| *f).unwrap_or_default();
+        match format.encode(&self.0) {
+            Ok(body) => (
+                [
+                    (
+                        CONTENT_TYPE,
+                        HeaderValue::from_static(format.media_type()),
+                    ),
+                    (VARY, HeaderValue::from_static("accept")),
+                ],
+                body,
+            )
+                .into_response(),
+            Err(e) => ApiError::Internal(e).into_response(),
+        }
+    }
+}
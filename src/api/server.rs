@@ -1,13 +1,21 @@
+use crate::api::errors::problem_details;
+use crate::api::middleware::rate_limit::rate_limit;
+use crate::api::negotiation::content_negotiation;
 use crate::api::router::router;
 use crate::api::swagger;
+use crate::api::ws::connection::ws_handler;
 use crate::state::AppState;
-use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::middleware::{from_fn, from_fn_with_state};
 use axum::routing::get;
-use axum::{response::IntoResponse, serve};
-use futures_util::StreamExt;
+use axum::serve;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio_util::sync::CancellationToken;
+use tower::ServiceBuilder;
+use tower_http::request_id::{
+    MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer,
+};
 use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
 use tracing::{Level, info};
 use utoipa::OpenApi;
@@ -22,15 +30,27 @@ impl ProjectHTTPServer {
     ) -> std::io::Result<()> {
         let app = router()
             .route("/ws", get(ws_handler))
+            .layer(from_fn_with_state(state.clone(), rate_limit))
             .with_state(state.clone())
             .merge(SwaggerUi::new("/docs").url(
                 "/api-doc/openapi.json",
                 swagger::ApiDoc::openapi().clone(),
             ))
             .layer(
-                TraceLayer::new_for_http()
-                    .make_span_with(DefaultMakeSpan::new().level(Level::INFO))
-                    .on_response(DefaultOnResponse::new().level(Level::INFO)),
+                ServiceBuilder::new()
+                    .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+                    .layer(
+                        TraceLayer::new_for_http()
+                            .make_span_with(
+                                DefaultMakeSpan::new().level(Level::INFO),
+                            )
+                            .on_response(
+                                DefaultOnResponse::new().level(Level::INFO),
+                            ),
+                    )
+                    .layer(PropagateRequestIdLayer::x_request_id())
+                    .layer(from_fn(problem_details))
+                    .layer(from_fn(content_negotiation)),
             );
         let listener =
             tokio::net::TcpListener::bind(&state.cfg.server_address).await?;
@@ -41,42 +61,18 @@ impl ProjectHTTPServer {
         );
         info!("Starting server on http://{}", &state.cfg.server_address);
 
-        serve(listener, app)
-            .with_graceful_shutdown(async move {
-                tokio::signal::ctrl_c()
-                    .await
-                    .expect("Failed to listen for Ctrl+C");
-                tokio::time::sleep(Duration::from_secs(5)).await;
+        serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move {
+            tokio::signal::ctrl_c().await.expect("Failed to listen for Ctrl+C");
+            tokio::time::sleep(Duration::from_secs(5)).await;
 
-                info!("SIGINT received, shutting down...");
-                shutdown.cancel();
-            })
-            .await?;
+            info!("SIGINT received, shutting down...");
+            shutdown.cancel();
+        })
+        .await?;
         Ok(())
     }
 }
-
-async fn ws_handler(ws: WebSocketUpgrade) -> impl IntoResponse {
-    ws.on_upgrade(handle_socket)
-}
-
-async fn handle_socket(mut socket: WebSocket) {
-    println!("Client connected");
-
-    while let Some(Ok(msg)) = socket.next().await {
-        match msg {
-            Message::Text(text) => {
-                println!("Received: {}", text);
-                socket
-                    .send(Message::Text(format!("Echo: {}", text).into()))
-                    .await
-                    .unwrap();
-            }
-            Message::Close(_) => {
-                println!("Client disconnected");
-                return;
-            }
-            _ => {}
-        }
-    }
-}
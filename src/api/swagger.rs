@@ -1,10 +1,42 @@
+use crate::api::errors::{FieldError, ProblemDetails};
+use crate::core::handlers::posts::CreatePostPayload;
 use crate::core::models::AuthResult;
+use crate::core::models::PartnerWebhook;
+use crate::core::models::PostRequest;
 use crate::core::models::UserResponse;
+use crate::core::results::blobs::BlobResult;
+use crate::core::results::cron::{
+    CronJobResult, CronRunResult, CronTriggerResult,
+};
+use crate::core::results::posts::PostResult;
 use api::v1::handlers::__path_hello;
 use api::v1::handlers::__path_me;
+use api::v1::handlers::__path_partner_webhook;
+use api::v2::admin::{
+    __path_list_cron_jobs, __path_pause_cron_job, __path_reschedule_cron_job,
+    __path_resume_cron_job, __path_trigger_cron_job, CronScheduleRequest,
+};
+use api::v2::batch::{
+    __path_batch, BatchItemResult, BatchOperation, BatchRequest, BatchResponse,
+};
+use api::v2::events::__path_events;
+use api::v2::handlers::{
+    __path_create_post, __path_delete_post, __path_get_post, __path_list_posts,
+    __path_patch_post, __path_update_post,
+};
+
+use api::v2::files::{
+    __path_create_file_link, __path_download_file, __path_get_file,
+    __path_upload_files, SignedLink,
+};
+use api::v2::transfer::{
+    __path_export_posts, __path_import_posts, ImportError, ImportReport,
+};
 
 use crate::api;
+use crate::api::negotiation::{CBOR, JSON, MSGPACK, NEGOTIATED};
 use utoipa::OpenApi;
+use utoipa::openapi::{Content, RefOr};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -19,13 +51,52 @@ use utoipa::OpenApi;
     ),
     paths(
         me,
-        hello
+        hello,
+        partner_webhook,
+        list_posts,
+        get_post,
+        create_post,
+        update_post,
+        patch_post,
+        delete_post,
+        batch,
+        export_posts,
+        import_posts,
+        upload_files,
+        get_file,
+        create_file_link,
+        download_file,
+        events,
+        list_cron_jobs,
+        pause_cron_job,
+        resume_cron_job,
+        trigger_cron_job,
+        reschedule_cron_job
     ),
     components(schemas(
         UserResponse,
-        AuthResult
+        AuthResult,
+        PartnerWebhook,
+        ProblemDetails,
+        FieldError,
+        PostRequest,
+        PostResult,
+        json_patch::Patch,
+        BatchRequest,
+        BatchOperation,
+        BatchResponse,
+        BatchItemResult,
+        CreatePostPayload,
+        ImportReport,
+        ImportError,
+        BlobResult,
+        SignedLink,
+        CronJobResult,
+        CronRunResult,
+        CronTriggerResult,
+        CronScheduleRequest
     )),
-    modifiers(&SecurityAddon)
+    modifiers(&SecurityAddon, &BinaryMediaTypes)
 )]
 pub struct ApiDoc;
 
@@ -48,6 +119,54 @@ impl utoipa::Modify for SecurityAddon {
         }
     }
 }
+/// Добавляет MessagePack и CBOR с той же схемой, что и JSON, операциям
+/// с расширением `NEGOTIATED`.
+struct BinaryMediaTypes;
+
+impl utoipa::Modify for BinaryMediaTypes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.patch,
+                &mut item.delete,
+            ];
+            for operation in operations.into_iter().flatten() {
+                let negotiated = operation
+                    .extensions
+                    .as_mut()
+                    .and_then(|extensions| extensions.remove(NEGOTIATED));
+                if negotiated != Some(serde_json::Value::Bool(true)) {
+                    continue;
+                }
+                if let Some(body) = operation.request_body.as_mut() {
+                    let variants = binary_variants(body.content.get(JSON));
+                    body.content.extend(variants);
+                }
+                for response in operation.responses.responses.values_mut() {
+                    if let RefOr::T(response) = response {
+                        let variants =
+                            binary_variants(response.content.get(JSON));
+                        response.content.extend(variants);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn binary_variants(json: Option<&Content>) -> Vec<(String, Content)> {
+    json.map(|content| {
+        [MSGPACK, CBOR]
+            .into_iter()
+            .map(|media_type| (media_type.to_string(), content.clone()))
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
 // const API_KEY_NAME: &str = "Some-Auth-Key";
 // components.add_security_scheme(
 //     API_KEY_NAME,
@@ -1,25 +1,30 @@
+use crate::api::errors::{ApiError, ProblemDetails};
+use crate::api::extractors::webhook::SignedJson;
+use crate::api::negotiation::Negotiated;
 use crate::core::handlers::hello::HelloQuery;
-use crate::core::models::{AuthenticatedUser, UserResponse};
+use crate::core::models::{AuthenticatedUser, PartnerWebhook, UserResponse};
 use crate::core::results::hello::GetHelloResult;
 use crate::mediator::mediator::Mediator;
-use crate::state::AppState;
 use axum::extract::State;
+use axum::http::StatusCode;
 use std::sync::Arc;
+use tracing::{debug, info};
 
 #[utoipa::path(
     get,
     path = "/api/v1/me",
     tag = "Users",
+    extensions(("x-negotiated" = json!(true))),
     security(
         ("bearer_auth" = [])
     ),
     responses(
         (status = 200, description = "Информация о текущем пользователе", body = UserResponse),
-        (status = 401, description = "Не авторизован: отсутствует или неверный токен")
+        (status = 401, description = "Не авторизован: отсутствует или неверный токен", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
-pub async fn me(user: AuthenticatedUser) -> UserResponse {
-    UserResponse { user_id: user.0.user_id }
+pub async fn me(user: AuthenticatedUser) -> Negotiated<UserResponse> {
+    Negotiated(UserResponse { user_id: user.0.user_id })
 }
 
 #[utoipa::path(
@@ -27,16 +32,43 @@ pub async fn me(user: AuthenticatedUser) -> UserResponse {
     path = "/api/v1/hello",
     tag = "Hello",
     responses(
-        (status = 200, description = "Приветственное сообщение")
+        (status = 200, description = "Приветственное сообщение"),
+        (status = 500, description = "Внутренняя ошибка", body = ProblemDetails, content_type = "application/problem+json")
     )
 )]
-pub async fn hello(State(mediator): State<Arc<Mediator>>) -> String {
-    // Обрабатывать ошибки
-    mediator
+pub async fn hello(
+    State(mediator): State<Arc<Mediator>>,
+) -> Result<String, ApiError> {
+    let result = mediator
         .query::<HelloQuery, GetHelloResult>(HelloQuery {
             name: "My name".to_string(),
         })
-        .await
-        .unwrap()
-        .name
+        .await?;
+    Ok(result.name)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/partner",
+    tag = "Webhooks",
+    request_body = PartnerWebhook,
+    params(
+        ("X-Signature" = String, Header, description = "sha256=<hex> HMAC-SHA256 от `{timestamp}.{body}`"),
+        ("X-Signature-Timestamp" = u64, Header, description = "Время подписи, unix-секунды")
+    ),
+    responses(
+        (status = 204, description = "Событие принято"),
+        (status = 400, description = "Некорректный JSON", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Подпись отсутствует, неверна или устарела", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Эта доставка уже принята", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 413, description = "Тело слишком большое", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn partner_webhook(
+    SignedJson(webhook): SignedJson<PartnerWebhook>,
+) -> StatusCode {
+    // В data могут быть персональные данные клиентов партнёра
+    info!("📨 Partner webhook {}", webhook.event);
+    debug!("📨 Partner webhook {} data: {}", webhook.event, webhook.data);
+    StatusCode::NO_CONTENT
 }
@@ -1,9 +1,11 @@
-use super::handlers::{hello, me};
+use super::handlers::{hello, me, partner_webhook};
 use crate::state::AppState;
 use axum::Router;
-use axum::routing::get;
-use std::sync::Arc;
+use axum::routing::{get, post};
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/hello", get(hello)).route("/me", get(me))
+    Router::new()
+        .route("/hello", get(hello))
+        .route("/me", get(me))
+        .route("/webhooks/partner", post(partner_webhook))
 }
@@ -0,0 +1,155 @@
+use crate::api::errors::{ApiError, ProblemDetails};
+use crate::api::extractors::json::{ApiJson, ApiPath};
+use crate::core::models::AdminUser;
+use crate::core::results::cron::{CronJobResult, CronTriggerResult};
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Deserialize;
+use tracing::info;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CronScheduleRequest {
+    /// Cron с секундами или по-английски, как в `CRON_JOBS_FILE`
+    #[schema(example = "0 */10 * * * *")]
+    pub schedule: String,
+    /// Без неё зона задачи не меняется
+    #[schema(example = "Europe/Moscow")]
+    pub timezone: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/admin/cron/jobs",
+    tag = "Admin",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Задачи этого инстанса со следующим срабатыванием и последним запуском", body = Vec<CronJobResult>),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Нужна роль администратора", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn list_cron_jobs(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+) -> Result<Json<Vec<CronJobResult>>, ApiError> {
+    Ok(Json(state.cron.jobs().await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v2/admin/cron/jobs/{name}/pause",
+    tag = "Admin",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("name" = String, Path, description = "Имя задачи")
+    ),
+    responses(
+        (status = 200, description = "Задача на паузе на всех инстансах", body = CronJobResult),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Нужна роль администратора", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Задача не найдена", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn pause_cron_job(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    ApiPath(name): ApiPath<String>,
+) -> Result<Json<CronJobResult>, ApiError> {
+    info!("👮 Admin {} pauses cron job {name}", admin.0.user_id);
+    Ok(Json(state.cron.pause(&name).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v2/admin/cron/jobs/{name}/resume",
+    tag = "Admin",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("name" = String, Path, description = "Имя задачи")
+    ),
+    responses(
+        (status = 200, description = "Задача снова идёт по расписанию", body = CronJobResult),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Нужна роль администратора", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Задача не найдена", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn resume_cron_job(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    ApiPath(name): ApiPath<String>,
+) -> Result<Json<CronJobResult>, ApiError> {
+    info!("👮 Admin {} resumes cron job {name}", admin.0.user_id);
+    Ok(Json(state.cron.resume(&name).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v2/admin/cron/jobs/{name}/trigger",
+    tag = "Admin",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("name" = String, Path, description = "Имя задачи")
+    ),
+    responses(
+        (status = 202, description = "Запуск начат, итог появится в last_run", body = CronTriggerResult),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Нужна роль администратора", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Задача не найдена", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 409, description = "Прошлый запуск ещё идёт", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn trigger_cron_job(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    ApiPath(name): ApiPath<String>,
+) -> Result<(StatusCode, Json<CronTriggerResult>), ApiError> {
+    info!("👮 Admin {} triggers cron job {name}", admin.0.user_id);
+    let triggered = state.cron.trigger(&name).await?;
+    Ok((StatusCode::ACCEPTED, Json(triggered)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v2/admin/cron/jobs/{name}/schedule",
+    tag = "Admin",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("name" = String, Path, description = "Имя задачи")
+    ),
+    request_body = CronScheduleRequest,
+    responses(
+        (status = 200, description = "Расписание изменено на всех инстансах", body = CronJobResult),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Нужна роль администратора", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Задача не найдена", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Некорректное расписание или часовой пояс", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn reschedule_cron_job(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    ApiPath(name): ApiPath<String>,
+    ApiJson(body): ApiJson<CronScheduleRequest>,
+) -> Result<Json<CronJobResult>, ApiError> {
+    info!(
+        "👮 Admin {} reschedules cron job {name} to {:?}",
+        admin.0.user_id, body.schedule
+    );
+    let job =
+        state.cron.reschedule(&name, body.schedule, body.timezone).await?;
+    Ok(Json(job))
+}
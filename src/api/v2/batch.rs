@@ -0,0 +1,199 @@
+use crate::api::errors::{ApiError, FieldError, ProblemDetails};
+use crate::api::extractors::json::ApiJson;
+use crate::core::handlers::base::CommandContext;
+use crate::core::models::AuthenticatedUser;
+use crate::infra::storage::tx::transaction;
+use crate::mediator::errors::DispatchError;
+use crate::mediator::mediator::Mediator;
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use sea_orm::TransactionError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchRequest {
+    /// Все операции в одной транзакции: первая ошибка откатывает остальные
+    #[serde(default)]
+    pub atomic: bool,
+    pub operations: Vec<BatchOperation>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchOperation {
+    /// Метка клиента, возвращается в результате как есть
+    #[schema(example = "row-17")]
+    pub id: Option<String>,
+    #[schema(example = "posts.create")]
+    pub command: String,
+    #[serde(default)]
+    #[schema(value_type = Object, example = json!({"title": "Hello", "text": "World"}))]
+    pub payload: Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchResponse {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchItemResult {
+    /// Позиция операции в запросе
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[schema(example = 200)]
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub body: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ProblemDetails>,
+}
+
+impl BatchItemResult {
+    fn success(index: usize, id: Option<String>, value: Value) -> Self {
+        let (status, body) = match value {
+            Value::Null => (StatusCode::NO_CONTENT, None),
+            value => (StatusCode::OK, Some(value)),
+        };
+        Self { index, id, status: status.as_u16(), body, error: None }
+    }
+
+    fn failure(index: usize, id: Option<String>, error: ApiError) -> Self {
+        Self {
+            index,
+            id,
+            status: error.status().as_u16(),
+            body: None,
+            error: Some(error.problem()),
+        }
+    }
+}
+
+impl From<Vec<BatchItemResult>> for BatchResponse {
+    fn from(results: Vec<BatchItemResult>) -> Self {
+        let failed = results.iter().filter(|r| r.error.is_some()).count();
+        Self { succeeded: results.len() - failed, failed, results }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v2/batch",
+    tag = "Batch",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(
+        content = BatchRequest,
+        description = "Операции с именами зарегистрированных команд: posts.create, posts.update, posts.delete"
+    ),
+    responses(
+        (status = 207, description = "Результат по каждой операции", body = BatchResponse),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Пустой или слишком большой batch", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn batch(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ApiJson(request): ApiJson<BatchRequest>,
+) -> Result<(StatusCode, Json<BatchResponse>), ApiError> {
+    let max = state.cfg.batch_max_size;
+    if request.operations.is_empty() || request.operations.len() > max {
+        return Err(ApiError::Validation(vec![FieldError {
+            field: "operations".to_string(),
+            message: format!("must contain between 1 and {max} operations"),
+        }]));
+    }
+
+    let ctx = CommandContext::user(user.0.user_id);
+    let results = if request.atomic {
+        run_atomic(&state, request.operations, ctx).await?
+    } else {
+        run_independent(&state.mediator, request.operations, ctx).await
+    };
+    Ok((StatusCode::MULTI_STATUS, Json(results.into())))
+}
+
+/// Операции выполняются по порядку, ошибка одной не влияет на остальные.
+async fn run_independent(
+    mediator: &Mediator,
+    operations: Vec<BatchOperation>,
+    ctx: CommandContext,
+) -> Vec<BatchItemResult> {
+    let mut results = Vec::with_capacity(operations.len());
+    for (index, op) in operations.into_iter().enumerate() {
+        let result = match mediator.dispatch(&op.command, op.payload, ctx).await
+        {
+            Ok(value) => BatchItemResult::success(index, op.id, value),
+            Err(e) => BatchItemResult::failure(index, op.id, e.into()),
+        };
+        results.push(result);
+    }
+    results
+}
+
+/// Операции выполняются в одной транзакции до первой ошибки; при ошибке
+/// она откатывается, и все остальные операции получают 424.
+async fn run_atomic(
+    state: &AppState,
+    operations: Vec<BatchOperation>,
+    ctx: CommandContext,
+) -> Result<Vec<BatchItemResult>, ApiError> {
+    let ids: Vec<Option<String>> =
+        operations.iter().map(|op| op.id.clone()).collect();
+
+    let outcome = transaction::<_, Vec<Value>, (usize, DispatchError)>(
+        &state.db,
+        async {
+            let mut values = Vec::with_capacity(operations.len());
+            for (index, op) in operations.into_iter().enumerate() {
+                match state
+                    .mediator
+                    .dispatch(&op.command, op.payload, ctx)
+                    .await
+                {
+                    Ok(value) => values.push(value),
+                    Err(e) => return Err((index, e)),
+                }
+            }
+            Ok(values)
+        },
+    )
+    .await;
+
+    match outcome {
+        Ok(values) => Ok(ids
+            .into_iter()
+            .zip(values)
+            .enumerate()
+            .map(|(index, (id, value))| {
+                BatchItemResult::success(index, id, value)
+            })
+            .collect()),
+        Err(TransactionError::Transaction((failed, e))) => {
+            let mut cause = Some(ApiError::from(e));
+            Ok(ids
+                .into_iter()
+                .enumerate()
+                .map(|(index, id)| {
+                    let error = match cause.take_if(|_| index == failed) {
+                        Some(e) => e,
+                        None => ApiError::FailedDependency(format!(
+                            "Batch rolled back: operation {failed} failed"
+                        )),
+                    };
+                    BatchItemResult::failure(index, id, error)
+                })
+                .collect())
+        }
+        Err(TransactionError::Connection(e)) => Err(e.into()),
+    }
+}
@@ -0,0 +1,155 @@
+use crate::api::errors::{ApiError, FieldError, ProblemDetails};
+use crate::core::events::{DomainEvent, StoredEvent, Subscription};
+use crate::core::models::AuthenticatedUser;
+use crate::state::AppState;
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::IntoParams;
+
+const LAST_EVENT_ID: &str = "last-event-id";
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EventsParams {
+    /// Имена событий через запятую, например `post.created,post.deleted`;
+    /// без параметра — все
+    pub types: Option<String>,
+}
+
+/// Что показывать подписчику.
+struct EventFilter {
+    user_id: i32,
+    types: Option<Vec<&'static str>>,
+}
+
+impl EventFilter {
+    fn new(user_id: i32, types: Option<&str>) -> Result<Self, ApiError> {
+        let Some(types) = types else {
+            return Ok(Self { user_id, types: None });
+        };
+        let mut selected = Vec::new();
+        for name in types.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let known = DomainEvent::NAMES
+                .iter()
+                .find(|known| **known == name)
+                .ok_or_else(|| {
+                    ApiError::Validation(vec![FieldError {
+                        field: "types".to_string(),
+                        message: format!(
+                            "unknown event {name}, expected one of: {}",
+                            DomainEvent::NAMES.join(", ")
+                        ),
+                    }])
+                })?;
+            selected.push(*known);
+        }
+        Ok(Self { user_id, types: Some(selected) })
+    }
+
+    fn accepts(&self, stored: &StoredEvent) -> bool {
+        stored.event.is_visible_to(self.user_id)
+            && self
+                .types
+                .as_ref()
+                .is_none_or(|types| types.contains(&stored.event.name()))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/events",
+    tag = "Events",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        EventsParams,
+        ("Last-Event-ID" = Option<u64>, Header, description = "id последнего полученного события: пропущенные события придут из буфера")
+    ),
+    responses(
+        (status = 200, description = "Поток событий. `event` — имя события, `data` — JSON, `id` — номер для Last-Event-ID. \
+            Событие `resync` означает, что часть событий потеряна и состояние нужно перечитать. \
+            Каждые SSE_HEARTBEAT_SECS приходит комментарий-heartbeat",
+            body = String, content_type = "text/event-stream"),
+        (status = 400, description = "Некорректный Last-Event-ID", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Неизвестное имя события", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn events(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<EventsParams>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    let filter = EventFilter::new(user.0.user_id, params.types.as_deref())?;
+    let last_event_id = headers
+        .get(LAST_EVENT_ID)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .ok_or_else(|| {
+                    ApiError::BadRequest(
+                        "Last-Event-ID must be an event id".to_string(),
+                    )
+                })
+        })
+        .transpose()?;
+
+    let Subscription { replay, gap, live } =
+        state.events.subscribe(last_event_id);
+    let filter = Arc::new(filter);
+
+    // Без id: у клиента остаётся прежний Last-Event-ID. Данные нужны,
+    // иначе браузер событие не доставит
+    let resync = stream::iter(
+        gap.then(|| Ok(Event::default().event("resync").data("{}"))),
+    );
+    let replay = {
+        let filter = filter.clone();
+        stream::iter(replay)
+            .filter(move |stored| std::future::ready(filter.accepts(stored)))
+            .map(|stored| to_sse(&stored))
+    };
+    // При переполнении канала (медленный клиент) закрываем поток:
+    // клиент переподключится с Last-Event-ID и дочитает из буфера
+    let live = stream::unfold(live, move |mut live| {
+        let filter = filter.clone();
+        async move {
+            loop {
+                match live.recv().await {
+                    Ok(stored) if filter.accepts(&stored) => {
+                        return Some((to_sse(&stored), live));
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(_) | RecvError::Closed) => {
+                        return None;
+                    }
+                }
+            }
+        }
+    });
+
+    let stream = resync
+        .chain(replay)
+        .chain(live)
+        .take_until(state.shutdown.clone().cancelled_owned());
+    Ok(Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(state.cfg.sse_heartbeat_secs)),
+    ))
+}
+
+fn to_sse(stored: &StoredEvent) -> Result<Event, axum::Error> {
+    Event::default()
+        .id(stored.id.to_string())
+        .event(stored.event.name())
+        .json_data(&stored.event)
+}
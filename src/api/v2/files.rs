@@ -0,0 +1,419 @@
+use crate::api::errors::{ApiError, ProblemDetails};
+use crate::api::extractors::extractors::bearer_user;
+use crate::api::extractors::json::ApiPath;
+use crate::api::negotiation::Negotiated;
+use crate::configs::Config;
+use crate::core::errors::DomainError;
+use crate::core::handlers::blobs::{
+    BlobOutcome, GetBlobQuery, StoreBlobCommand,
+};
+use crate::core::models::AuthenticatedUser;
+use crate::core::results::blobs::BlobResult;
+use crate::infra::storage::blobs::ByteRange;
+use crate::state::AppState;
+use axum::body::Body;
+use axum::extract::multipart::MultipartRejection;
+use axum::extract::{Multipart, Query, State};
+use axum::http::header::{
+    ACCEPT_RANGES, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_RANGE,
+    CONTENT_TYPE, RANGE, X_CONTENT_TYPE_OPTIONS,
+};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tracing::error;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+const MAX_FILES_PER_REQUEST: usize = 10;
+
+#[utoipa::path(
+    post,
+    path = "/api/v2/files",
+    tag = "Files",
+    extensions(("x-negotiated" = json!(true))),
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(
+        content_type = "multipart/form-data",
+        description = "Одно или несколько полей `file`; размер и тип ограничены UPLOAD_MAX_BYTES и UPLOAD_ALLOWED_TYPES"
+    ),
+    responses(
+        (status = 201, description = "Файлы сохранены", body = Vec<BlobResult>),
+        (status = 400, description = "Некорректный multipart", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 413, description = "Файл больше допустимого", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Недопустимый тип файла", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn upload_files(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<(StatusCode, Negotiated<Vec<BlobResult>>), ApiError> {
+    let mut multipart = multipart?;
+    let mut stored = Vec::new();
+    while let Some(mut field) = multipart.next_field().await? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        if stored.len() == MAX_FILES_PER_REQUEST {
+            return Err(ApiError::PayloadTooLarge(format!(
+                "At most {MAX_FILES_PER_REQUEST} files per request"
+            )));
+        }
+        let content_type = field
+            .content_type()
+            .unwrap_or("application/octet-stream")
+            .to_ascii_lowercase();
+        if !is_allowed_type(&state.cfg, &content_type) {
+            return Err(ApiError::UnsupportedMediaType(format!(
+                "Files of type {content_type} are not allowed"
+            )));
+        }
+        let file_name =
+            field.file_name().unwrap_or("upload").chars().take(255).collect();
+
+        // Пишем на диск по мере чтения, считая хеш и размер, — в памяти
+        // держим только текущий кусок
+        let spool = Spool::new();
+        let mut file = File::create(&spool.0).await.map_err(internal)?;
+        let mut hasher = Sha256::new();
+        let mut size = 0u64;
+        while let Some(chunk) = field.chunk().await? {
+            size += chunk.len() as u64;
+            if size > state.cfg.upload_max_bytes {
+                return Err(ApiError::PayloadTooLarge(format!(
+                    "File exceeds {} bytes",
+                    state.cfg.upload_max_bytes
+                )));
+            }
+            hasher.update(&chunk);
+            file.write_all(&chunk).await.map_err(internal)?;
+        }
+        file.flush().await.map_err(internal)?;
+
+        let command = StoreBlobCommand {
+            owner_id: user.0.user_id,
+            file_name,
+            content_type,
+            size,
+            sha256: hex::encode(hasher.finalize()),
+            path: spool.0.clone(),
+        };
+        stored.push(state.mediator.send::<_, BlobOutcome>(command).await??);
+    }
+    if stored.is_empty() {
+        return Err(ApiError::BadRequest(
+            "No `file` field in form".to_string(),
+        ));
+    }
+    Ok((StatusCode::CREATED, Negotiated(stored)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/files/{id}",
+    tag = "Files",
+    extensions(("x-negotiated" = json!(true))),
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(("id" = i32, Path, description = "Идентификатор файла")),
+    responses(
+        (status = 200, description = "Метаданные файла", body = BlobResult),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Файл принадлежит другому пользователю", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Файл не найден", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn get_file(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ApiPath(id): ApiPath<i32>,
+) -> Result<Negotiated<BlobResult>, ApiError> {
+    Ok(Negotiated(owned_blob(&state, &user, id).await?))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SignedLink {
+    #[schema(
+        example = "/api/v2/files/7/content?expires=1792400000&signature=9f2c…"
+    )]
+    pub url: String,
+    /// Unix-время, до которого ссылка действует
+    #[schema(example = 1792400000)]
+    pub expires: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v2/files/{id}/link",
+    tag = "Files",
+    extensions(("x-negotiated" = json!(true))),
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(("id" = i32, Path, description = "Идентификатор файла")),
+    responses(
+        (status = 200, description = "Ссылка на скачивание без авторизации, действует SIGNED_URL_TTL_SECS", body = SignedLink),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Файл принадлежит другому пользователю", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Файл не найден", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn create_file_link(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    ApiPath(id): ApiPath<i32>,
+) -> Result<Negotiated<SignedLink>, ApiError> {
+    owned_blob(&state, &user, id).await?;
+    let expires = unix_now() + state.cfg.signed_url_ttl_secs;
+    let signature = sign(&state.cfg, id, expires);
+    Ok(Negotiated(SignedLink {
+        url: format!(
+            "/api/v2/files/{id}/content?expires={expires}&signature={signature}"
+        ),
+        expires,
+    }))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SignedQuery {
+    /// Из подписанной ссылки
+    expires: Option<u64>,
+    /// Из подписанной ссылки
+    signature: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/files/{id}/content",
+    tag = "Files",
+    security(
+        (),
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = i32, Path, description = "Идентификатор файла"),
+        SignedQuery,
+        ("Range" = Option<String>, Header, description = "Один диапазон: bytes=0-1023, bytes=1024- или bytes=-512")
+    ),
+    responses(
+        (status = 200, description = "Содержимое файла"),
+        (status = 206, description = "Запрошенный диапазон",
+            headers(("Content-Range" = String, description = "bytes start-end/size"))),
+        (status = 401, description = "Нет ни владельца, ни действующей подписи", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Файл принадлежит другому пользователю", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Файл не найден", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 416, description = "Диапазон за пределами файла", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn download_file(
+    State(state): State<AppState>,
+    ApiPath(id): ApiPath<i32>,
+    Query(signed): Query<SignedQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    // Подписанная ссылка заменяет авторизацию владельца
+    let blob = match (signed.expires, signed.signature) {
+        (Some(expires), Some(signature)) => {
+            verify(&state.cfg, id, expires, &signature)?;
+            state
+                .mediator
+                .query::<_, BlobOutcome>(GetBlobQuery { id })
+                .await??
+        }
+        _ => {
+            let user = bearer_user(&state.cfg, &headers)?;
+            owned_blob(&state, &user, id).await?
+        }
+    };
+
+    let size = blob.size as u64;
+    let range = match headers.get(RANGE).and_then(|v| v.to_str().ok()) {
+        Some(raw) => match parse_range(raw, size) {
+            Ok(range) => range,
+            Err(()) => {
+                return Ok((
+                    [(CONTENT_RANGE, format!("bytes */{size}"))],
+                    ApiError::RangeNotSatisfiable(format!(
+                        "Range {raw} is outside of {size} bytes"
+                    )),
+                )
+                    .into_response());
+            }
+        },
+        None => None,
+    };
+    let stream = state
+        .blobs
+        .get(&blob.sha256, range)
+        .await
+        .map_err(DomainError::from)?;
+
+    let mut response = Body::from_stream(stream).into_response();
+    let headers = response.headers_mut();
+    headers.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    // Content-Type задаёт загрузивший, браузер не должен угадывать свой
+    headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    if let Ok(value) = HeaderValue::from_str(&blob.content_type) {
+        headers.insert(CONTENT_TYPE, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&format!(
+        "attachment; filename=\"{}\"",
+        safe_file_name(&blob.file_name)
+    )) {
+        headers.insert(CONTENT_DISPOSITION, value);
+    }
+    match range {
+        Some(range) => {
+            headers.insert(CONTENT_LENGTH, range.len().into());
+            if let Ok(value) = HeaderValue::from_str(&format!(
+                "bytes {}-{}/{size}",
+                range.start, range.end
+            )) {
+                headers.insert(CONTENT_RANGE, value);
+            }
+            *response.status_mut() = StatusCode::PARTIAL_CONTENT;
+        }
+        None => {
+            headers.insert(CONTENT_LENGTH, size.into());
+        }
+    }
+    Ok(response)
+}
+
+async fn owned_blob(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    id: i32,
+) -> Result<BlobResult, ApiError> {
+    let blob =
+        state.mediator.query::<_, BlobOutcome>(GetBlobQuery { id }).await??;
+    if blob.owner_id != user.0.user_id {
+        return Err(ApiError::Forbidden(format!(
+            "File {id} belongs to another user"
+        )));
+    }
+    Ok(blob)
+}
+
+fn is_allowed_type(cfg: &Config, content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or("").trim();
+    cfg.upload_allowed_types.iter().any(|allowed| {
+        match allowed.strip_suffix("/*") {
+            Some(prefix) => {
+                essence.split_once('/').is_some_and(|(top, _)| top == prefix)
+            }
+            None => allowed == essence,
+        }
+    })
+}
+
+/// `bytes=a-b`, `bytes=a-` или `bytes=-n`. Несколько диапазонов не
+/// поддерживаем и отдаём файл целиком, как разрешает RFC 9110.
+fn parse_range(raw: &str, size: u64) -> Result<Option<ByteRange>, ()> {
+    let Some(spec) = raw.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let (start, end) = spec.split_once('-').ok_or(())?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().map_err(|_| ())?;
+            if suffix == 0 {
+                return Err(());
+            }
+            (size.saturating_sub(suffix), size.checked_sub(1).ok_or(())?)
+        }
+        (start, "") => {
+            (start.parse().map_err(|_| ())?, size.checked_sub(1).ok_or(())?)
+        }
+        (start, end) => {
+            let end: u64 = end.parse().map_err(|_| ())?;
+            (start.parse().map_err(|_| ())?, end.min(size.saturating_sub(1)))
+        }
+    };
+    if start > end || start >= size {
+        return Err(());
+    }
+    Ok(Some(ByteRange { start, end }))
+}
+
+fn sign(cfg: &Config, id: i32, expires: u64) -> String {
+    hex::encode(signature_mac(cfg, id, expires).finalize().into_bytes())
+}
+
+fn verify(
+    cfg: &Config,
+    id: i32,
+    expires: u64,
+    signature: &str,
+) -> Result<(), ApiError> {
+    if expires < unix_now() {
+        return Err(ApiError::Unauthorized("Link has expired".to_string()));
+    }
+    let signature = hex::decode(signature)
+        .map_err(|_| ApiError::Unauthorized("Invalid signature".to_string()))?;
+    signature_mac(cfg, id, expires)
+        .verify_slice(&signature)
+        .map_err(|_| ApiError::Unauthorized("Invalid signature".to_string()))
+}
+
+fn signature_mac(cfg: &Config, id: i32, expires: u64) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(cfg.secret_token.as_bytes())
+        .expect("HMAC accepts any key size");
+    mac.update(format!("blob:{id}:{expires}").as_bytes());
+    mac
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Имя для `Content-Disposition` без кавычек и управляющих символов.
+fn safe_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_graphic() && c != '"' && c != '\\' || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn internal(e: std::io::Error) -> ApiError {
+    error!("Upload spool error: {e}");
+    ApiError::Internal("Upload could not be stored".to_string())
+}
+
+/// Временный файл загрузки; удаляется при выходе из handler-а, в том
+/// числе при ошибке.
+struct Spool(PathBuf);
+
+impl Spool {
+    fn new() -> Self {
+        Self(std::env::temp_dir().join(format!("upload-{}", Uuid::new_v4())))
+    }
+}
+
+impl Drop for Spool {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
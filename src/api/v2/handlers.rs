@@ -1 +1,253 @@
+use crate::api::conditional::{ETag, Preconditions, WithETag};
+use crate::api::errors::{ApiError, ProblemDetails};
+use crate::api::extractors::json::ApiPath;
+use crate::api::extractors::list::ListParams;
+use crate::api::extractors::patch::PatchDocument;
+use crate::api::negotiation::Negotiated;
+use crate::core::errors::DomainError;
+use crate::core::handlers::posts::{
+    CreatePostCommand, DeletePostCommand, GetPostQuery, ListPostsQuery,
+    PostOutcome, UpdatePostCommand,
+};
+use crate::core::listing::Page;
+use crate::core::models::{AuthenticatedUser, PostRequest};
+use crate::core::results::posts::PostResult;
+use crate::mediator::mediator::Mediator;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::Response;
+use std::sync::Arc;
 
+#[utoipa::path(
+    get,
+    path = "/api/v2/posts",
+    tag = "Posts",
+    extensions(("x-negotiated" = json!(true))),
+    params(ListParams<PostResult>),
+    responses(
+        (status = 200, description = "Страница постов", body = Page<PostResult>,
+            headers(("ETag" = String, description = "Хеш содержимого страницы"))),
+        (status = 304, description = "Страница не изменилась с указанного If-None-Match"),
+        (status = 422, description = "Некорректные параметры", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn list_posts(
+    State(mediator): State<Arc<Mediator>>,
+    preconditions: Preconditions,
+    ListParams(request, _): ListParams<PostResult>,
+) -> Result<Response, ApiError> {
+    let posts = mediator
+        .query::<_, Result<Page<PostResult>, DomainError>>(ListPostsQuery {
+            request,
+        })
+        .await??;
+    Ok(preconditions.respond(ETag::from_content(&posts), Negotiated(posts)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/posts/{id}",
+    tag = "Posts",
+    extensions(("x-negotiated" = json!(true))),
+    params(
+        ("id" = i32, Path, description = "Идентификатор поста"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag сохранённой копии")
+    ),
+    responses(
+        (status = 200, description = "Пост", body = PostResult,
+            headers(("ETag" = String, description = "Версия поста"))),
+        (status = 304, description = "Пост не изменился"),
+        (status = 404, description = "Пост не найден", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn get_post(
+    State(mediator): State<Arc<Mediator>>,
+    preconditions: Preconditions,
+    ApiPath(id): ApiPath<i32>,
+) -> Result<Response, ApiError> {
+    let post = mediator.query::<_, PostOutcome>(GetPostQuery { id }).await??;
+    Ok(preconditions
+        .respond(ETag::from_version(post.version), Negotiated(post)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v2/posts",
+    tag = "Posts",
+    extensions(("x-negotiated" = json!(true))),
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = PostRequest,
+    responses(
+        (status = 201, description = "Пост создан", body = PostResult,
+            headers(("ETag" = String, description = "Версия поста"))),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Ошибка валидации", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn create_post(
+    State(mediator): State<Arc<Mediator>>,
+    user: AuthenticatedUser,
+    Negotiated(body): Negotiated<PostRequest>,
+) -> Result<WithETag<(StatusCode, Negotiated<PostResult>)>, ApiError> {
+    let command = CreatePostCommand {
+        author_id: user.0.user_id,
+        title: body.title,
+        text: body.text,
+    };
+    let post = mediator.send::<_, PostOutcome>(command).await??;
+    Ok(WithETag(
+        ETag::from_version(post.version),
+        (StatusCode::CREATED, Negotiated(post)),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v2/posts/{id}",
+    tag = "Posts",
+    extensions(("x-negotiated" = json!(true))),
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = i32, Path, description = "Идентификатор поста"),
+        ("If-Match" = Option<String>, Header, description = "ETag версии, которую клиент редактирует")
+    ),
+    request_body = PostRequest,
+    responses(
+        (status = 200, description = "Пост обновлён", body = PostResult,
+            headers(("ETag" = String, description = "Новая версия поста"))),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Пост принадлежит другому пользователю", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Пост не найден", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "Пост изменён с момента получения ETag", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Ошибка валидации", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn update_post(
+    State(mediator): State<Arc<Mediator>>,
+    user: AuthenticatedUser,
+    preconditions: Preconditions,
+    ApiPath(id): ApiPath<i32>,
+    Negotiated(body): Negotiated<PostRequest>,
+) -> Result<WithETag<Negotiated<PostResult>>, ApiError> {
+    let command = UpdatePostCommand {
+        id,
+        user_id: user.0.user_id,
+        expected_version: preconditions
+            .expected_version(current_version(&mediator, id))
+            .await?,
+        title: body.title,
+        text: body.text,
+    };
+    let post = mediator.send::<_, PostOutcome>(command).await??;
+    Ok(WithETag(ETag::from_version(post.version), Negotiated(post)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v2/posts/{id}",
+    tag = "Posts",
+    extensions(("x-negotiated" = json!(true))),
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = i32, Path, description = "Идентификатор поста"),
+        ("If-Match" = Option<String>, Header, description = "ETag версии, которую клиент редактирует")
+    ),
+    request_body(
+        description = "Частичное обновление: RFC 7396 Merge Patch (null удаляет поле) или RFC 6902 JSON Patch",
+        content(
+            (PostRequest = "application/merge-patch+json"),
+            (json_patch::Patch = "application/json-patch+json")
+        )
+    ),
+    responses(
+        (status = 200, description = "Пост обновлён", body = PostResult,
+            headers(("ETag" = String, description = "Новая версия поста"))),
+        (status = 400, description = "Некорректный патч", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Пост принадлежит другому пользователю", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Пост не найден", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "Пост изменён с момента получения ETag", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 413, description = "Патч слишком большой", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Неподдерживаемый Content-Type", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Патч не применился или результат невалиден", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn patch_post(
+    State(mediator): State<Arc<Mediator>>,
+    user: AuthenticatedUser,
+    preconditions: Preconditions,
+    ApiPath(id): ApiPath<i32>,
+    patch: PatchDocument,
+) -> Result<WithETag<Negotiated<PostResult>>, ApiError> {
+    let current =
+        mediator.query::<_, PostOutcome>(GetPostQuery { id }).await??;
+    // Без If-Match всё равно обновляем только ту версию, к которой
+    // применяли патч, иначе параллельная правка потеряется
+    let expected_version = preconditions
+        .expected_version(async { Ok(current.version) })
+        .await?
+        .or(Some(current.version));
+    let patched = patch
+        .apply(&PostRequest { title: current.title, text: current.text })?;
+
+    let command = UpdatePostCommand {
+        id,
+        user_id: user.0.user_id,
+        expected_version,
+        title: patched.title,
+        text: patched.text,
+    };
+    let post = mediator.send::<_, PostOutcome>(command).await??;
+    Ok(WithETag(ETag::from_version(post.version), Negotiated(post)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v2/posts/{id}",
+    tag = "Posts",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = i32, Path, description = "Идентификатор поста"),
+        ("If-Match" = Option<String>, Header, description = "ETag версии, которую клиент удаляет")
+    ),
+    responses(
+        (status = 204, description = "Пост удалён"),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Пост принадлежит другому пользователю", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 404, description = "Пост не найден", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 412, description = "Пост изменён с момента получения ETag", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn delete_post(
+    State(mediator): State<Arc<Mediator>>,
+    user: AuthenticatedUser,
+    preconditions: Preconditions,
+    ApiPath(id): ApiPath<i32>,
+) -> Result<StatusCode, ApiError> {
+    let command = DeletePostCommand {
+        id,
+        user_id: user.0.user_id,
+        expected_version: preconditions
+            .expected_version(current_version(&mediator, id))
+            .await?,
+    };
+    mediator.send::<_, Result<(), DomainError>>(command).await??;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Текущая версия поста, когда `If-Match` перечисляет несколько.
+async fn current_version(
+    mediator: &Mediator,
+    id: i32,
+) -> Result<i32, ApiError> {
+    let post = mediator.query::<_, PostOutcome>(GetPostQuery { id }).await??;
+    Ok(post.version)
+}
@@ -1,2 +1,7 @@
+pub mod admin;
+pub mod batch;
+pub mod events;
+pub mod files;
 pub mod handlers;
 pub mod router;
+pub mod transfer;
@@ -1,7 +1,42 @@
+use super::admin::{
+    list_cron_jobs, pause_cron_job, reschedule_cron_job, resume_cron_job,
+    trigger_cron_job,
+};
+use super::batch::batch;
+use super::events::events;
+use super::files::{create_file_link, download_file, get_file, upload_files};
+use super::handlers::{
+    create_post, delete_post, get_post, list_posts, patch_post, update_post,
+};
+use super::transfer::{export_posts, import_posts};
 use crate::state::AppState;
 use axum::Router;
+use axum::extract::DefaultBodyLimit;
+use axum::routing::{get, post, put};
 use std::sync::Arc;
 
 pub fn router() -> Router<AppState> {
     Router::new()
+        .route("/posts", get(list_posts).post(create_post))
+        .route("/posts/export", get(export_posts))
+        .route("/posts/import", post(import_posts))
+        .route(
+            "/posts/{id}",
+            get(get_post)
+                .put(update_post)
+                .patch(patch_post)
+                .delete(delete_post),
+        )
+        .route("/batch", post(batch))
+        .route("/events", get(events))
+        // Размер файлов проверяет сам handler по UPLOAD_MAX_BYTES, по мере чтения
+        .route("/files", post(upload_files).layer(DefaultBodyLimit::disable()))
+        .route("/files/{id}", get(get_file))
+        .route("/files/{id}/link", post(create_file_link))
+        .route("/files/{id}/content", get(download_file))
+        .route("/admin/cron/jobs", get(list_cron_jobs))
+        .route("/admin/cron/jobs/{name}/pause", post(pause_cron_job))
+        .route("/admin/cron/jobs/{name}/resume", post(resume_cron_job))
+        .route("/admin/cron/jobs/{name}/trigger", post(trigger_cron_job))
+        .route("/admin/cron/jobs/{name}/schedule", put(reschedule_cron_job))
 }
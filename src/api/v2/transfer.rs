@@ -0,0 +1,462 @@
+use crate::api::errors::{ApiError, ProblemDetails};
+use crate::api::extractors::list::ListParams;
+use crate::core::errors::DomainError;
+use crate::core::handlers::posts::{
+    CreatePostPayload, ImportPostRow, ImportPostsCommand, ListPostsQuery,
+};
+use crate::core::listing::{Cursor, ListRequest, Page, Pagination};
+use crate::core::models::AuthenticatedUser;
+use crate::core::results::posts::{ImportedPosts, PostResult};
+use crate::mediator::mediator::Mediator;
+use axum::Json;
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::http::header::{ACCEPT, CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::http::{HeaderMap, HeaderValue};
+use axum::response::{IntoResponse, Response};
+use futures_util::{TryStreamExt, stream};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::io::{self, BufRead, BufReader, Read};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::spawn_blocking;
+use tokio_util::io::{StreamReader, SyncIoBridge};
+use tracing::error;
+use utoipa::ToSchema;
+
+pub const NDJSON: &str = "application/x-ndjson";
+pub const CSV: &str = "text/csv";
+
+/// Сколько постов читаем из базы за один запрос при экспорте.
+const EXPORT_CHUNK: u64 = 500;
+/// Сколько строк импорта вставляем одним `INSERT`.
+const IMPORT_CHUNK: usize = 500;
+/// Ошибки строк сверх этого числа только считаются.
+const MAX_REPORTED_ERRORS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TransferFormat {
+    Ndjson,
+    Csv,
+}
+
+impl TransferFormat {
+    fn parse(media_type: &str) -> Option<Self> {
+        let media_type = media_type.split(';').next()?.trim();
+        match media_type.to_ascii_lowercase().as_str() {
+            NDJSON | "application/ndjson" | "application/jsonl" => {
+                Some(TransferFormat::Ndjson)
+            }
+            CSV => Some(TransferFormat::Csv),
+            _ => None,
+        }
+    }
+
+    /// Первый поддерживаемый тип из `Accept`; без заголовка или с `*/*` — NDJSON.
+    fn from_accept(headers: &HeaderMap) -> Result<Self, ApiError> {
+        let Some(accept) = headers.get(ACCEPT).and_then(|v| v.to_str().ok())
+        else {
+            return Ok(TransferFormat::Ndjson);
+        };
+        for item in accept.split(',') {
+            if let Some(format) = Self::parse(item) {
+                return Ok(format);
+            }
+            if item.split(';').next().is_some_and(|t| t.trim() == "*/*") {
+                return Ok(TransferFormat::Ndjson);
+            }
+        }
+        Err(ApiError::NotAcceptable(format!("Expected {NDJSON} or {CSV}")))
+    }
+
+    fn from_content_type(headers: &HeaderMap) -> Result<Self, ApiError> {
+        headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(Self::parse)
+            .ok_or_else(|| {
+                ApiError::UnsupportedMediaType(format!(
+                    "Expected {NDJSON} or {CSV}"
+                ))
+            })
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            TransferFormat::Ndjson => NDJSON,
+            TransferFormat::Csv => CSV,
+        }
+    }
+
+    fn content_disposition(self) -> &'static str {
+        match self {
+            TransferFormat::Ndjson => "attachment; filename=\"posts.ndjson\"",
+            TransferFormat::Csv => "attachment; filename=\"posts.csv\"",
+        }
+    }
+
+    /// Последняя запись оборванной выгрузки: в NDJSON строка
+    /// `{"error": {...}}`, в CSV запись `#error,<detail>`.
+    fn encode_error(self, problem: &ProblemDetails) -> Bytes {
+        let detail = problem.detail.clone().unwrap_or_default();
+        match self {
+            TransferFormat::Ndjson => {
+                let mut buf = serde_json::to_vec(
+                    &serde_json::json!({ "error": problem }),
+                )
+                .unwrap_or_default();
+                buf.push(b'\n');
+                buf.into()
+            }
+            TransferFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                let _ = writer.write_record(["#error", detail.as_str()]);
+                writer.into_inner().map(Bytes::from).unwrap_or_default()
+            }
+        }
+    }
+
+    fn encode(self, posts: &[PostResult], header: bool) -> io::Result<Bytes> {
+        match self {
+            TransferFormat::Ndjson => {
+                let mut buf = Vec::new();
+                for post in posts {
+                    serde_json::to_writer(&mut buf, post)?;
+                    buf.push(b'\n');
+                }
+                Ok(buf.into())
+            }
+            TransferFormat::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(header)
+                    .from_writer(Vec::new());
+                for post in posts {
+                    writer.serialize(post)?;
+                }
+                writer.into_inner().map(Bytes::from).map_err(|e| e.into_error())
+            }
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v2/posts/export",
+    tag = "Posts",
+    params(
+        ListParams<PostResult>,
+        ("Accept" = Option<String>, Header, description = "application/x-ndjson (по умолчанию) или text/csv")
+    ),
+    responses(
+        (status = 200, description = "Все посты, подходящие под фильтры, потоком; cursor, page и size игнорируются. Если выгрузка оборвалась, последняя запись — ошибка: `{\"error\": {...}}` в NDJSON или `#error,<detail>` в CSV",
+            content(
+                (PostResult = "application/x-ndjson"),
+                (String = "text/csv")
+            )),
+        (status = 406, description = "Неподдерживаемый Accept", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Некорректные параметры", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn export_posts(
+    State(mediator): State<Arc<Mediator>>,
+    headers: HeaderMap,
+    ListParams(request, _): ListParams<PostResult>,
+) -> Result<Response, ApiError> {
+    let format = TransferFormat::from_accept(&headers)?;
+    let request = ListRequest {
+        pagination: Pagination::Cursor(None),
+        size: EXPORT_CHUNK,
+        ..request
+    };
+
+    // В памяти только одна страница: следующая читается, когда клиент
+    // забрал предыдущую. Статус 200 уже отправлен, поэтому ошибка
+    // посреди выгрузки становится последней записью, а не обрывом
+    let chunks = stream::unfold(Some((request, true)), move |next| {
+        let mediator = mediator.clone();
+        async move {
+            let (request, first) = next?;
+            let (bytes, next) =
+                match export_page(&mediator, format, request, first).await {
+                    Ok(page) => page,
+                    Err(e) => {
+                        error!("Export aborted: {e}");
+                        (format.encode_error(&e.problem()), None)
+                    }
+                };
+            Some((Ok::<_, Infallible>(bytes), next))
+        }
+    });
+
+    Ok((
+        [
+            (CONTENT_TYPE, HeaderValue::from_static(format.content_type())),
+            (
+                CONTENT_DISPOSITION,
+                HeaderValue::from_static(format.content_disposition()),
+            ),
+        ],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
+
+/// Страница выгрузки и запрос за следующей, если она есть.
+async fn export_page(
+    mediator: &Mediator,
+    format: TransferFormat,
+    request: ListRequest,
+    first: bool,
+) -> Result<(Bytes, Option<(ListRequest, bool)>), ApiError> {
+    let page = mediator
+        .query::<_, Result<Page<PostResult>, DomainError>>(ListPostsQuery {
+            request: request.clone(),
+        })
+        .await??;
+    let bytes = format
+        .encode(&page.items, first)
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    let next =
+        page.next_cursor.as_deref().and_then(Cursor::decode).map(|cursor| {
+            let pagination = Pagination::Cursor(Some(cursor));
+            (ListRequest { pagination, ..request }, false)
+        });
+    Ok((bytes, next))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportError {
+    #[schema(example = 17)]
+    pub line: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "title")]
+    pub field: Option<String>,
+    #[schema(example = "must not be empty")]
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ImportReport {
+    pub imported: u64,
+    pub rejected: u64,
+    /// Первые 100 ошибок по строкам
+    pub errors: Vec<ImportError>,
+    /// Почему импорт остановился раньше конца тела; строки после
+    /// последней вставленной пачки не обработаны
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aborted: Option<String>,
+}
+
+impl ImportReport {
+    fn reject(&mut self, error: ImportError) {
+        self.rejected += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(error);
+        }
+    }
+
+    fn merge(&mut self, chunk: ImportedPosts) {
+        self.imported += chunk.imported;
+        for row in chunk.rejected {
+            let mut violations = row.violations.into_iter();
+            let first = violations.next();
+            self.reject(ImportError {
+                line: row.line,
+                field: first.as_ref().map(|v| v.field.clone()),
+                message: first.map(|v| v.message).unwrap_or_default(),
+            });
+            // Остальные нарушения той же строки — без повторного счёта
+            for v in violations {
+                if self.errors.len() < MAX_REPORTED_ERRORS {
+                    self.errors.push(ImportError {
+                        line: row.line,
+                        field: Some(v.field),
+                        message: v.message,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v2/posts/import",
+    tag = "Posts",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(
+        description = "Посты построчно; для CSV первая строка — заголовок с колонками title и text, остальные колонки игнорируются",
+        content(
+            (CreatePostPayload = "application/x-ndjson"),
+            (String = "text/csv")
+        )
+    ),
+    responses(
+        (status = 200, description = "Отчёт об импорте: валидные строки вставлены пачками, ошибки с номерами строк", body = ImportReport),
+        (status = 500, description = "Импорт остановлен ошибкой: уже вставленные пачки остаются, причина в aborted", body = ImportReport),
+        (status = 503, description = "Импорт остановлен недоступностью хранилища, причина в aborted", body = ImportReport),
+        (status = 401, description = "Не авторизован", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 415, description = "Неподдерживаемый Content-Type", body = ProblemDetails, content_type = "application/problem+json")
+    )
+)]
+pub async fn import_posts(
+    State(mediator): State<Arc<Mediator>>,
+    user: AuthenticatedUser,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Response, ApiError> {
+    let format = TransferFormat::from_content_type(&headers)?;
+    let reader = SyncIoBridge::new(StreamReader::new(
+        body.into_data_stream().map_err(io::Error::other),
+    ));
+
+    // Разбор идёт в blocking-потоке и читает тело по мере поступления;
+    // канал ограничен, так что медленная вставка притормаживает чтение
+    let (tx, mut rx) = mpsc::channel(IMPORT_CHUNK);
+    let parser = spawn_blocking(move || parse_rows(format, reader, tx));
+
+    let mut report = ImportReport::default();
+    let mut chunk = Vec::with_capacity(IMPORT_CHUNK);
+    let mut failure = None;
+    while let Some(row) = rx.recv().await {
+        match row {
+            Ok(row) => chunk.push(row),
+            Err(e) => report.reject(e),
+        }
+        if chunk.len() == IMPORT_CHUNK {
+            let rows =
+                std::mem::replace(&mut chunk, Vec::with_capacity(IMPORT_CHUNK));
+            match import_chunk(&mediator, user.0.user_id, rows).await {
+                Ok(imported) => report.merge(imported),
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+    }
+    if failure.is_none() && !chunk.is_empty() {
+        match import_chunk(&mediator, user.0.user_id, chunk).await {
+            Ok(imported) => report.merge(imported),
+            Err(e) => failure = Some(e),
+        }
+    }
+    // Закрытый канал останавливает разбор
+    drop(rx);
+
+    // Уже вставленные пачки остаются: клиент получает отчёт о них и
+    // статус ошибки, а не голый 500
+    if let Some(e) = failure {
+        error!("Import aborted after {} posts: {e}", report.imported);
+        let _ = parser.await;
+        report.aborted = Some(e.to_string());
+        return Ok((e.status(), Json(report)).into_response());
+    }
+
+    match parser.await {
+        Ok(Ok(())) => {}
+        // Уже вставленные пачки остаются, клиент видит, где остановились
+        Ok(Err((line, e))) => report.reject(ImportError {
+            line,
+            field: None,
+            message: format!("Body could not be read: {e}"),
+        }),
+        Err(e) => return Err(ApiError::Internal(e.to_string())),
+    }
+    Ok(Json(report).into_response())
+}
+
+async fn import_chunk(
+    mediator: &Mediator,
+    author_id: i32,
+    rows: Vec<ImportPostRow>,
+) -> Result<ImportedPosts, ApiError> {
+    Ok(mediator
+        .send::<_, Result<ImportedPosts, DomainError>>(ImportPostsCommand {
+            author_id,
+            rows,
+        })
+        .await??)
+}
+
+type ParsedRow = Result<ImportPostRow, ImportError>;
+
+/// Читает строки и отправляет их в канал; ошибка чтения возвращается
+/// вместе с номером строки, на которой оборвался ввод.
+fn parse_rows(
+    format: TransferFormat,
+    reader: impl Read,
+    tx: mpsc::Sender<ParsedRow>,
+) -> Result<(), (u64, io::Error)> {
+    match format {
+        TransferFormat::Ndjson => {
+            let mut reader = BufReader::new(reader);
+            let mut buf = Vec::new();
+            for line in 1.. {
+                buf.clear();
+                if reader.read_until(b'\n', &mut buf).map_err(|e| (line, e))?
+                    == 0
+                {
+                    break;
+                }
+                // Битая кодировка портит только свою строку
+                let row = match std::str::from_utf8(&buf) {
+                    Ok(raw) if raw.trim().is_empty() => continue,
+                    Ok(raw) => parse_ndjson_row(line, raw),
+                    Err(_) => Err(ImportError {
+                        line,
+                        field: None,
+                        message: "line is not valid UTF-8".to_string(),
+                    }),
+                };
+                if tx.blocking_send(row).is_err() {
+                    break;
+                }
+            }
+        }
+        TransferFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(reader);
+            let headers = reader.headers().map_err(|e| (1, e.into()))?.clone();
+            for record in reader.records() {
+                let line = record
+                    .as_ref()
+                    .map(|r| r.position())
+                    .unwrap_or_else(|e| e.position())
+                    .map_or(0, |p| p.line());
+                let row = match record {
+                    Err(e) if e.is_io_error() => return Err((line, e.into())),
+                    Err(e) => Err(ImportError {
+                        line,
+                        field: None,
+                        message: e.to_string(),
+                    }),
+                    Ok(record) => record
+                        .deserialize::<CreatePostPayload>(Some(&headers))
+                        .map(|p| ImportPostRow {
+                            line,
+                            title: p.title,
+                            text: p.text,
+                        })
+                        .map_err(|e| ImportError {
+                            line,
+                            field: None,
+                            message: e.to_string(),
+                        }),
+                };
+                if tx.blocking_send(row).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_ndjson_row(line: u64, raw: &str) -> ParsedRow {
+    serde_json::from_str::<CreatePostPayload>(raw)
+        .map(|p| ImportPostRow { line, title: p.title, text: p.text })
+        .map_err(|e| ImportError { line, field: None, message: e.to_string() })
+}
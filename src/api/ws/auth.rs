@@ -0,0 +1,44 @@
+use crate::api::extractors::extractors::{
+    AuthError, authenticate, bearer_user,
+};
+use crate::api::ws::protocol::AuthPayload;
+use crate::configs::Config;
+use crate::core::models::AuthenticatedUser;
+use axum::http::HeaderMap;
+use axum::http::header::AUTHORIZATION;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct WsParams {
+    /// Токен для клиентов, которые не могут поставить заголовок
+    /// (браузерный `WebSocket`)
+    pub access_token: Option<String>,
+}
+
+/// Пользователь при установке соединения: из `Authorization: Bearer`
+/// или `?access_token=`. `None` — токена нет, он должен прийти первым
+/// кадром `auth`. Неверный токен отклоняет upgrade.
+pub fn upgrade_user(
+    cfg: &Config,
+    headers: &HeaderMap,
+    params: &WsParams,
+) -> Result<Option<AuthenticatedUser>, AuthError> {
+    if headers.contains_key(AUTHORIZATION) {
+        return bearer_user(cfg, headers).map(Some);
+    }
+    match &params.access_token {
+        Some(token) => authenticate(cfg, token)
+            .map(|auth| Some(AuthenticatedUser(auth)))
+            .ok_or(AuthError::InvalidToken),
+        None => Ok(None),
+    }
+}
+
+pub fn message_user(
+    cfg: &Config,
+    payload: &AuthPayload,
+) -> Result<AuthenticatedUser, AuthError> {
+    authenticate(cfg, &payload.token)
+        .map(AuthenticatedUser)
+        .ok_or(AuthError::InvalidToken)
+}
@@ -0,0 +1,384 @@
+use crate::api::errors::ApiError;
+use crate::api::ws::auth::{WsParams, message_user, upgrade_user};
+use crate::api::ws::hub::{ConnectionId, Hub, JoinError, Registration};
+use crate::api::ws::protocol::{
+    ClientMessage, Envelope, ProtocolError, RequestPayload,
+};
+use crate::core::handlers::base::CommandContext;
+use crate::game::replication::Replica;
+use crate::mediator::mediator::Mediator;
+use crate::state::AppState;
+use axum::body::Bytes;
+use axum::extract::ws::{
+    CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code,
+};
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::Response;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::time::{Instant, MissedTickBehavior, interval, timeout};
+use tracing::{debug, info};
+
+/// Больше не нужно ни одному кадру протокола.
+const MAX_MESSAGE_BYTES: usize = 64 * 1024;
+/// Сколько ждать кадра `auth`, если токена не было при upgrade.
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Одновременно выполняемых `request` на одно соединение.
+const MAX_IN_FLIGHT_REQUESTS: usize = 16;
+
+pub async fn ws_handler(
+    State(state): State<AppState>,
+    Query(params): Query<WsParams>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    let user_id =
+        upgrade_user(&state.cfg, &headers, &params)?.map(|user| user.0.user_id);
+    Ok(ws
+        .max_message_size(MAX_MESSAGE_BYTES)
+        .on_upgrade(move |socket| handle_socket(socket, state, user_id)))
+}
+
+/// Почему закончилась сессия.
+enum Exit {
+    /// Клиент ушёл или сокет сломан — закрывать нечего
+    Gone,
+    Close(CloseFrame),
+}
+
+impl Exit {
+    fn close(code: u16, reason: &'static str) -> Self {
+        Exit::Close(CloseFrame { code, reason: reason.into() })
+    }
+}
+
+async fn handle_socket(
+    mut socket: WebSocket,
+    state: AppState,
+    user_id: Option<i32>,
+) {
+    let user_id = match user_id {
+        Some(user_id) => Ok(user_id),
+        None => first_message_auth(&mut socket, &state).await,
+    };
+    let exit = match user_id {
+        Ok(user_id) => run_session(&mut socket, &state, user_id).await,
+        Err(exit) => exit,
+    };
+    if let Exit::Close(frame) = exit {
+        let _ = socket.send(Message::Close(Some(frame))).await;
+    }
+}
+
+/// Ждёт кадр `auth`; любой другой кадр или таймаут закрывает соединение.
+async fn first_message_auth(
+    socket: &mut WebSocket,
+    state: &AppState,
+) -> Result<i32, Exit> {
+    let unauthorized =
+        || Exit::close(close_code::POLICY, "Authentication required");
+    let text = loop {
+        match timeout(AUTH_TIMEOUT, socket.recv()).await {
+            Err(_) => return Err(unauthorized()),
+            Ok(Some(Ok(Message::Text(text)))) => break text,
+            Ok(Some(Ok(Message::Ping(_) | Message::Pong(_)))) => continue,
+            Ok(Some(Ok(Message::Binary(_)))) => return Err(unauthorized()),
+            Ok(Some(Ok(Message::Close(_)) | Err(_)) | None) => {
+                return Err(Exit::Gone);
+            }
+        }
+    };
+    let envelope = serde_json::from_str::<Envelope>(&text).ok();
+    let reply_to = envelope.as_ref().and_then(|e| e.id.clone());
+    let user = match envelope.as_ref().map(ClientMessage::parse) {
+        Some(Ok(ClientMessage::Auth(payload))) => {
+            message_user(&state.cfg, &payload)
+                .map_err(|e| ProtocolError::new("unauthorized", e.to_string()))
+        }
+        _ => Err(ProtocolError::new(
+            "unauthorized",
+            "First message must be auth",
+        )),
+    };
+    match user {
+        Ok(user) => {
+            let user_id = user.0.user_id;
+            if let Some(reply_to) = reply_to {
+                let ack =
+                    Envelope::ack(reply_to, json!({ "user_id": user_id }));
+                if !send(socket, &ack).await {
+                    return Err(Exit::Gone);
+                }
+            }
+            Ok(user_id)
+        }
+        Err(error) => {
+            let _ = send(socket, &Envelope::error(reply_to, error)).await;
+            Err(unauthorized())
+        }
+    }
+}
+
+async fn run_session(
+    socket: &mut WebSocket,
+    state: &AppState,
+    user_id: i32,
+) -> Exit {
+    let hub = state.ws_hub.clone();
+    let mut registration = hub.register(user_id);
+    let mut session = Session {
+        id: registration.id,
+        user_id,
+        hub: hub.clone(),
+        mediator: state.mediator.clone(),
+        in_flight: Arc::new(Semaphore::new(MAX_IN_FLIGHT_REQUESTS)),
+        replica: Replica::new(user_id, state.cfg.game_interest_radius),
+    };
+    info!("🔌 WebSocket {} connected (user {user_id})", session.id);
+    let exit = session.run(socket, state, &mut registration).await;
+    hub.unregister(session.id);
+    state.rooms.disconnected(user_id);
+    info!("🔌 WebSocket {} disconnected", session.id);
+    exit
+}
+
+/// Аутентифицированное соединение.
+struct Session {
+    id: ConnectionId,
+    user_id: i32,
+    hub: Arc<Hub>,
+    mediator: Arc<Mediator>,
+    in_flight: Arc<Semaphore>,
+    /// Что из игрового мира клиент уже подтвердил
+    replica: Replica,
+}
+
+impl Session {
+    async fn run(
+        &mut self,
+        socket: &mut WebSocket,
+        state: &AppState,
+        registration: &mut Registration,
+    ) -> Exit {
+        let Registration { outbox, kicked, .. } = registration;
+        let welcome = Envelope::new(
+            "welcome",
+            json!({ "connection_id": self.id, "user_id": self.user_id }),
+        );
+        if !send(socket, &welcome).await {
+            return Exit::Gone;
+        }
+
+        let idle_timeout = Duration::from_secs(state.cfg.ws_idle_timeout_secs);
+        let mut ping =
+            interval(Duration::from_secs(state.cfg.ws_ping_interval_secs));
+        ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_seen = Instant::now();
+        let mut room_changes = state.rooms.subscribe();
+        let mut snapshots = state.rooms.snapshots_for(self.user_id);
+
+        loop {
+            tokio::select! {
+                _ = state.shutdown.cancelled() => {
+                    return Exit::close(close_code::AWAY, "Server is shutting down");
+                }
+                _ = kicked.cancelled() => {
+                    return Exit::close(close_code::AGAIN, "Outbound queue overflow");
+                }
+                Some(text) = outbox.recv() => {
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        return Exit::Gone;
+                    }
+                }
+                // Игрок вошёл в матч или вернулся в лобби: состояние
+                // нового мира клиент получит заново полным снимком
+                Ok(()) = room_changes.changed() => {
+                    let next = state.rooms.snapshots_for(self.user_id);
+                    if !next.same_channel(&snapshots) {
+                        snapshots = next;
+                        self.replica = Replica::new(
+                            self.user_id,
+                            state.cfg.game_interest_radius,
+                        );
+                    }
+                }
+                // Ошибка значит, что игровой цикл остановлен, и ветка
+                // отключается до перехода в другой мир
+                Ok(()) = snapshots.changed() => {
+                    let snapshot = snapshots.borrow_and_update().clone();
+                    if let Some(update) = self.replica.update(&snapshot) {
+                        let frame = Envelope::new(update.kind(), &update);
+                        if !send(socket, &frame).await {
+                            return Exit::Gone;
+                        }
+                    }
+                }
+                _ = ping.tick() => {
+                    if last_seen.elapsed() > idle_timeout {
+                        return Exit::close(close_code::NORMAL, "Idle timeout");
+                    }
+                    if socket.send(Message::Ping(Bytes::new())).await.is_err() {
+                        return Exit::Gone;
+                    }
+                }
+                incoming = socket.recv() => {
+                    let message = match incoming {
+                        Some(Ok(message)) => message,
+                        Some(Err(e)) => {
+                            debug!("WebSocket {} read error: {e}", self.id);
+                            return Exit::Gone;
+                        }
+                        None => return Exit::Gone,
+                    };
+                    last_seen = Instant::now();
+                    let reply = match message {
+                        Message::Text(text) => self.handle_text(&text),
+                        Message::Binary(_) => Some(Envelope::error(
+                            None,
+                            ProtocolError::bad_request("Binary frames are not supported"),
+                        )),
+                        // На Ping отвечает сама библиотека, Pong только
+                        // продлевает жизнь соединения
+                        Message::Ping(_) | Message::Pong(_) => None,
+                        Message::Close(_) => return Exit::Gone,
+                    };
+                    if let Some(reply) = reply
+                        && !send(socket, &reply).await
+                    {
+                        return Exit::Gone;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Обрабатывает кадр клиента; возвращает ответ, если он нужен сразу.
+    fn handle_text(&mut self, text: &str) -> Option<Envelope> {
+        let envelope: Envelope = match serde_json::from_str(text) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                return Some(Envelope::error(
+                    None,
+                    ProtocolError::bad_request(format!(
+                        "Invalid envelope: {e}"
+                    )),
+                ));
+            }
+        };
+        let result = ClientMessage::parse(&envelope)
+            .and_then(|message| self.execute(message, envelope.id.clone()));
+        match (result, envelope.id) {
+            (Ok(Some(payload)), Some(reply_to)) => {
+                Some(Envelope::ack(reply_to, payload))
+            }
+            (Ok(_), _) => None,
+            (Err(error), reply_to) => Some(Envelope::error(reply_to, error)),
+        }
+    }
+
+    /// Результат для `ack`; `None` — ответ придёт позже (`request`).
+    fn execute(
+        &mut self,
+        message: ClientMessage,
+        reply_to: Option<String>,
+    ) -> Result<Option<Value>, ProtocolError> {
+        let id = self.id;
+        match message {
+            ClientMessage::Join(p) => match self.hub.join(id, &p.room) {
+                Ok(()) => Ok(Some(json!({ "room": p.room }))),
+                Err(JoinError::TooManyRooms) => Err(ProtocolError::new(
+                    "too_many_rooms",
+                    "Connection has joined too many rooms",
+                )),
+                Err(JoinError::UnknownConnection) => Err(ProtocolError::new(
+                    "disconnected",
+                    "Connection is closing",
+                )),
+            },
+            ClientMessage::Leave(p) => {
+                self.hub.leave(id, &p.room);
+                Ok(Some(json!({ "room": p.room })))
+            }
+            ClientMessage::Publish(p) => {
+                if !self.hub.is_member(id, &p.room) {
+                    return Err(ProtocolError::new(
+                        "not_a_member",
+                        format!("Join room {} before publishing", p.room),
+                    ));
+                }
+                let message = Envelope::new(
+                    "message",
+                    json!({ "room": p.room, "from": id, "data": p.data }),
+                );
+                let delivered =
+                    self.hub.broadcast_room(&p.room, &message, Some(id));
+                Ok(Some(json!({ "delivered": delivered })))
+            }
+            ClientMessage::Auth(_) => Err(ProtocolError::bad_request(
+                "Connection is already authenticated",
+            )),
+            ClientMessage::Request(request) => {
+                self.spawn_request(reply_to, request).map(|()| None)
+            }
+            ClientMessage::GameAck(p) => {
+                if self.replica.ack(p.seq) {
+                    Ok(Some(json!({ "seq": p.seq })))
+                } else {
+                    Err(ProtocolError::new(
+                        "unknown_snapshot",
+                        format!(
+                            "Snapshot {} was not sent or is too old",
+                            p.seq
+                        ),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Запускает `request` в отдельной задаче, чтобы медленная команда
+    /// не задерживала остальные кадры. Ответ уходит через очередь
+    /// соединения и может обогнать ответы на более ранние запросы.
+    fn spawn_request(
+        &self,
+        reply_to: Option<String>,
+        request: RequestPayload,
+    ) -> Result<(), ProtocolError> {
+        let reply_to = reply_to.ok_or_else(|| {
+            ProtocolError::bad_request("Request must have an id")
+        })?;
+        let permit =
+            self.in_flight.clone().try_acquire_owned().map_err(|_| {
+                ProtocolError::new(
+                    "too_many_requests",
+                    format!(
+                        "At most {MAX_IN_FLIGHT_REQUESTS} requests may run at once"
+                    ),
+                )
+            })?;
+        let (id, hub, mediator) =
+            (self.id, self.hub.clone(), self.mediator.clone());
+        let ctx = CommandContext::user(self.user_id);
+        tokio::spawn(async move {
+            let reply = match mediator
+                .dispatch(&request.command, request.payload, ctx)
+                .await
+            {
+                Ok(result) => Envelope::ack(reply_to, result),
+                Err(e) => {
+                    Envelope::error(Some(reply_to), ApiError::from(e).problem())
+                }
+            };
+            hub.send_to(id, &reply);
+            drop(permit);
+        });
+        Ok(())
+    }
+}
+
+async fn send(socket: &mut WebSocket, envelope: &Envelope) -> bool {
+    socket.send(Message::Text(envelope.to_text().into())).await.is_ok()
+}
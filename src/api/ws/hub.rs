@@ -0,0 +1,203 @@
+use crate::api::ws::protocol::Envelope;
+use crate::game::rooms::Presence;
+use axum::extract::ws::Utf8Bytes;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+pub type ConnectionId = u64;
+
+const MAX_ROOMS_PER_CONNECTION: usize = 32;
+
+/// Сторона соединения, которую видит хаб: очередь исходящих кадров
+/// и сигнал принудительного закрытия.
+struct Peer {
+    user_id: i32,
+    rooms: HashSet<String>,
+    outbox: mpsc::Sender<Utf8Bytes>,
+    kicked: CancellationToken,
+}
+
+#[derive(Default)]
+struct Registry {
+    peers: HashMap<ConnectionId, Peer>,
+    rooms: HashMap<String, HashSet<ConnectionId>>,
+}
+
+impl Registry {
+    fn remove(&mut self, id: ConnectionId) -> Option<Peer> {
+        let peer = self.peers.remove(&id)?;
+        for room in &peer.rooms {
+            self.leave_room(id, room);
+        }
+        Some(peer)
+    }
+
+    fn leave_room(&mut self, id: ConnectionId, room: &str) {
+        if let Some(members) = self.rooms.get_mut(room) {
+            members.remove(&id);
+            if members.is_empty() {
+                self.rooms.remove(room);
+            }
+        }
+    }
+}
+
+/// Принятое хабом соединение: его id, очередь кадров для отправки
+/// клиенту и сигнал, что хаб его отключил (медленный клиент).
+pub struct Registration {
+    pub id: ConnectionId,
+    pub outbox: mpsc::Receiver<Utf8Bytes>,
+    pub kicked: CancellationToken,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum JoinError {
+    TooManyRooms,
+    UnknownConnection,
+}
+
+/// Реестр WebSocket-соединений и комнат.
+///
+/// Отправка никогда не ждёт клиента: кадр кладётся в ограниченную очередь
+/// соединения, и если она полна, соединение отключается — один медленный
+/// клиент не тормозит рассылку остальным.
+pub struct Hub {
+    registry: Mutex<Registry>,
+    next_id: AtomicU64,
+    queue_size: usize,
+}
+
+impl Hub {
+    pub fn new(queue_size: usize) -> Self {
+        Self {
+            registry: Mutex::new(Registry::default()),
+            next_id: AtomicU64::new(1),
+            queue_size: queue_size.max(1),
+        }
+    }
+
+    fn registry(&self) -> std::sync::MutexGuard<'_, Registry> {
+        self.registry.lock().expect("ws registry poisoned")
+    }
+
+    pub fn register(&self, user_id: i32) -> Registration {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::channel(self.queue_size);
+        let kicked = CancellationToken::new();
+        self.registry().peers.insert(
+            id,
+            Peer {
+                user_id,
+                rooms: HashSet::new(),
+                outbox: tx,
+                kicked: kicked.clone(),
+            },
+        );
+        Registration { id, outbox: rx, kicked }
+    }
+
+    pub fn unregister(&self, id: ConnectionId) {
+        self.registry().remove(id);
+    }
+
+    pub fn join(&self, id: ConnectionId, room: &str) -> Result<(), JoinError> {
+        let mut registry = self.registry();
+        let peer =
+            registry.peers.get_mut(&id).ok_or(JoinError::UnknownConnection)?;
+        if !peer.rooms.contains(room) {
+            if peer.rooms.len() >= MAX_ROOMS_PER_CONNECTION {
+                return Err(JoinError::TooManyRooms);
+            }
+            peer.rooms.insert(room.to_string());
+        }
+        registry.rooms.entry(room.to_string()).or_default().insert(id);
+        Ok(())
+    }
+
+    pub fn leave(&self, id: ConnectionId, room: &str) {
+        let mut registry = self.registry();
+        if let Some(peer) = registry.peers.get_mut(&id) {
+            peer.rooms.remove(room);
+        }
+        registry.leave_room(id, room);
+    }
+
+    pub fn is_member(&self, id: ConnectionId, room: &str) -> bool {
+        self.registry().rooms.get(room).is_some_and(|m| m.contains(&id))
+    }
+
+    /// Всем участникам комнаты, кроме `except`. Возвращает число адресатов.
+    pub fn broadcast_room(
+        &self,
+        room: &str,
+        envelope: &Envelope,
+        except: Option<ConnectionId>,
+    ) -> usize {
+        let mut registry = self.registry();
+        let targets: Vec<ConnectionId> = registry
+            .rooms
+            .get(room)
+            .map(|m| {
+                m.iter().copied().filter(|id| Some(*id) != except).collect()
+            })
+            .unwrap_or_default();
+        deliver(&mut registry, &targets, envelope)
+    }
+
+    /// В одно соединение; `false`, если его уже нет.
+    pub fn send_to(&self, id: ConnectionId, envelope: &Envelope) -> bool {
+        deliver(&mut self.registry(), &[id], envelope) == 1
+    }
+
+    /// Во все соединения пользователя.
+    pub fn send_to_user(&self, user_id: i32, envelope: &Envelope) -> usize {
+        let mut registry = self.registry();
+        let targets: Vec<ConnectionId> = registry
+            .peers
+            .iter()
+            .filter(|(_, peer)| peer.user_id == user_id)
+            .map(|(id, _)| *id)
+            .collect();
+        deliver(&mut registry, &targets, envelope)
+    }
+}
+
+impl Presence for Hub {
+    fn is_online(&self, user_id: i32) -> bool {
+        self.registry().peers.values().any(|peer| peer.user_id == user_id)
+    }
+}
+
+fn deliver(
+    registry: &mut Registry,
+    targets: &[ConnectionId],
+    envelope: &Envelope,
+) -> usize {
+    if targets.is_empty() {
+        return 0;
+    }
+    // Сериализуем один раз на всех адресатов
+    let text = Utf8Bytes::from(envelope.to_text());
+    let mut delivered = 0;
+    for id in targets {
+        let Some(peer) = registry.peers.get(id) else { continue };
+        match peer.outbox.try_send(text.clone()) {
+            Ok(()) => delivered += 1,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                warn!(
+                    "🐢 WebSocket {id}: outbound queue is full, disconnecting"
+                );
+                if let Some(peer) = registry.remove(*id) {
+                    peer.kicked.cancel();
+                }
+            }
+            // Соединение уже закрывается и само себя удалит
+            Err(mpsc::error::TrySendError::Closed(_)) => {}
+        }
+    }
+    delivered
+}
@@ -0,0 +1,45 @@
+pub mod auth;
+pub mod connection;
+pub mod hub;
+pub mod protocol;
+
+use crate::api::ws::hub::Hub;
+use crate::api::ws::protocol::Envelope;
+use crate::core::events::EventBus;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Комната, в которую приходят публичные события предметной области.
+pub const EVENTS_ROOM: &str = "events";
+
+/// Пересылает события шины в WebSocket: публичные — в комнату
+/// `EVENTS_ROOM`, приватные — во все соединения владельца.
+pub async fn forward_events(
+    hub: Arc<Hub>,
+    events: EventBus,
+    shutdown: CancellationToken,
+) {
+    let mut live = events.subscribe(None).live;
+    loop {
+        let stored = tokio::select! {
+            _ = shutdown.cancelled() => break,
+            received = live.recv() => match received {
+                Ok(stored) => stored,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("WebSocket event forwarding lagged, {skipped} events skipped");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+        let envelope = Envelope::new(stored.event.name(), &stored.event)
+            .with_id(stored.id.to_string());
+        match stored.event.recipient() {
+            Some(user_id) => hub.send_to_user(user_id, &envelope),
+            None => hub.broadcast_room(EVENTS_ROOM, &envelope, None),
+        };
+    }
+    info!("🛑 WebSocket event forwarding stopped");
+}
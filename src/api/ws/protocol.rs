@@ -0,0 +1,162 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const ROOM_MAX_LEN: usize = 64;
+
+/// Кадр протокола в обе стороны.
+///
+/// `id` клиент ставит, если ждёт подтверждения: сервер ответит кадром
+/// `ack` (или `error`) с тем же значением в поле `ack`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Envelope {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub payload: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ack: Option<String>,
+}
+
+impl Envelope {
+    pub fn new(kind: impl Into<String>, payload: impl Serialize) -> Self {
+        Self {
+            kind: kind.into(),
+            id: None,
+            payload: serde_json::to_value(payload).unwrap_or(Value::Null),
+            ack: None,
+        }
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Подтверждение кадра `id` с результатом `payload`.
+    pub fn ack(id: String, payload: impl Serialize) -> Self {
+        Self { ack: Some(id), ..Self::new("ack", payload) }
+    }
+
+    /// Ошибка кадра `ack`: `ProtocolError` или, для `request`,
+    /// `ProblemDetails` как в HTTP API.
+    pub fn error(ack: Option<String>, error: impl Serialize) -> Self {
+        Self { ack, ..Self::new("error", error) }
+    }
+
+    pub fn to_text(&self) -> String {
+        // Value и строки сериализуются всегда
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Ошибка обработки кадра; уходит клиенту в `payload` кадра `error`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProtocolError {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl ProtocolError {
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new("bad_request", message)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RoomPayload {
+    pub room: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublishPayload {
+    pub room: String,
+    #[serde(default)]
+    pub data: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthPayload {
+    pub token: String,
+}
+
+/// Вызов команды или запроса Mediator по имени, как операция batch.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RequestPayload {
+    /// Например `posts.create` или `posts.get`
+    pub command: String,
+    #[serde(default)]
+    pub payload: Value,
+}
+
+/// Клиент применил снимок игры `seq` (кадр `game.snapshot` или
+/// `game.delta`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct GameAckPayload {
+    pub seq: u64,
+}
+
+/// Кадры, которые понимает сервер.
+#[derive(Debug, Clone)]
+pub enum ClientMessage {
+    Join(RoomPayload),
+    Leave(RoomPayload),
+    /// Сообщение всем участникам комнаты, кроме отправителя
+    Publish(PublishPayload),
+    /// Вход по токену, если он не пришёл при установке соединения
+    Auth(AuthPayload),
+    /// Ответ придёт кадром `ack` или `error` с тем же `id`
+    Request(RequestPayload),
+    GameAck(GameAckPayload),
+}
+
+impl ClientMessage {
+    pub fn parse(envelope: &Envelope) -> Result<Self, ProtocolError> {
+        let message = match envelope.kind.as_str() {
+            "join" => ClientMessage::Join(payload(envelope)?),
+            "leave" => ClientMessage::Leave(payload(envelope)?),
+            "publish" => ClientMessage::Publish(payload(envelope)?),
+            "auth" => ClientMessage::Auth(payload(envelope)?),
+            "request" => ClientMessage::Request(payload(envelope)?),
+            "game.ack" => ClientMessage::GameAck(payload(envelope)?),
+            other => {
+                return Err(ProtocolError::new(
+                    "unknown_type",
+                    format!("Unknown message type {other}"),
+                ));
+            }
+        };
+        let room = match &message {
+            ClientMessage::Join(p) | ClientMessage::Leave(p) => &p.room,
+            ClientMessage::Publish(p) => &p.room,
+            ClientMessage::Auth(_)
+            | ClientMessage::Request(_)
+            | ClientMessage::GameAck(_) => {
+                return Ok(message);
+            }
+        };
+        if room.is_empty() || room.chars().count() > ROOM_MAX_LEN {
+            return Err(ProtocolError::bad_request(format!(
+                "room must be 1..={ROOM_MAX_LEN} characters"
+            )));
+        }
+        Ok(message)
+    }
+}
+
+fn payload<T: DeserializeOwned>(
+    envelope: &Envelope,
+) -> Result<T, ProtocolError> {
+    serde_json::from_value(envelope.payload.clone()).map_err(|e| {
+        ProtocolError::bad_request(format!(
+            "Invalid {} payload: {e}",
+            envelope.kind
+        ))
+    })
+}
@@ -1,15 +1,55 @@
 use crate::api::server::ProjectHTTPServer;
+use crate::api::ws::forward_events;
+use crate::api::ws::hub::Hub;
 use crate::configs::Config;
+use crate::core::errors::DomainError;
+use crate::core::events::EventBus;
+use crate::core::handlers::blobs::{
+    BlobOutcome, GetBlobHandler, GetBlobQuery, StoreBlobCommand,
+    StoreBlobHandler,
+};
+use crate::core::handlers::game::{GameInputCommand, GameInputHandler};
 use crate::core::handlers::hello::{
     GetHelloHandler, HelloQuery, HelloRepository,
 };
+use crate::core::handlers::matchmaking::{
+    JoinQueueCommand, JoinQueueHandler, LeaveQueueCommand, LeaveQueueHandler,
+    MatchmakingStatusHandler, MatchmakingStatusQuery, QueueOutcome,
+    StatusOutcome,
+};
+use crate::core::handlers::posts::{
+    CreatePostCommand, CreatePostHandler, DeletePostCommand, DeletePostHandler,
+    FetchRemotePostsCommand, FetchRemotePostsHandler, GetPostHandler,
+    GetPostQuery, ImportPostsCommand, ImportPostsHandler, ListPostsHandler,
+    ListPostsQuery, PostOutcome, UpdatePostCommand, UpdatePostHandler,
+};
+use crate::core::listing::Page;
 use crate::core::results::hello::GetHelloResult;
+use crate::core::results::posts::{ImportedPosts, PostResult, RemotePosts};
 use crate::cron::ProjectCron;
+use crate::game::clock::SystemClock;
+use crate::game::engine::GameLoop;
+use crate::game::input::input_queue;
+use crate::game::matchmaking::Matchmaker;
+use crate::game::persistence::WorldPersister;
+use crate::game::rooms::Rooms;
+use crate::game::simulation::Simulation;
+use crate::game::snapshot::{SnapshotReceiver, snapshot_channel};
+use crate::infra::clients::client::PostClient;
+use crate::infra::clients::http::HttpClient;
+use crate::infra::storage::blob_meta::BlobRepository;
+use crate::infra::storage::blobs::{self, BlobStore};
+use crate::infra::storage::cron_jobs::CronJobStateRepository;
+use crate::infra::storage::cron_leases::CronLeaseRepository;
+use crate::infra::storage::cron_runs::CronRunRepository;
+use crate::infra::storage::matches::MatchRepository;
+use crate::infra::storage::posts::PostRepository;
+use crate::infra::storage::world_snapshots::WorldSnapshotRepository;
 use crate::mediator::mediator::Mediator;
 use crate::state::AppState;
+use migration::{Migrator, MigratorTrait};
 use sea_orm::{Database, DatabaseConnection};
 use std::sync::Arc;
-use std::thread;
 use std::time::Duration;
 use tokio::task::spawn_blocking;
 use tokio::{signal, spawn};
@@ -28,31 +68,114 @@ impl App {
     pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
         let db: DatabaseConnection =
             Database::connect(&self.cfg.db_url).await?;
+        Migrator::up(&db, None).await?;
+        info!("✅ Database migrations applied");
 
-        let mediator = self.setup_mediator().await;
-        let state = AppState::setup(self.cfg.clone(), mediator).await;
+        // Мир поднимается до запуска цикла и сервера: игроки подключаются
+        // уже к восстановленному состоянию
+        let persister = WorldPersister::new(
+            &self.cfg,
+            WorldSnapshotRepository::new(db.clone()),
+        );
+        let world = persister.restore().await?;
 
-        self.run_and_wait_tasks(state).await
+        let events = EventBus::new(self.cfg.sse_replay_size);
+        let (game_inputs, game_input_rx) =
+            input_queue(self.cfg.game_input_queue);
+        let (game_snapshots, game_snapshot_rx) = snapshot_channel();
+        let ws_hub = Arc::new(Hub::new(self.cfg.ws_queue_size));
+        let matches = MatchRepository::new(db.clone());
+        let rooms = Arc::new(Rooms::new(
+            self.cfg.clone(),
+            ws_hub.clone(),
+            matches.clone(),
+            events.clone(),
+            game_inputs,
+            game_snapshot_rx.clone(),
+        ));
+        let matchmaker = Arc::new(Matchmaker::new(&self.cfg));
+        let http = HttpClient::new(&self.cfg.http_client)?;
+        let blobs = blobs::from_config(&self.cfg.blob_store, http.clone());
+        let mediator = self
+            .setup_mediator(
+                &db,
+                http,
+                blobs.clone(),
+                events.clone(),
+                rooms.clone(),
+                matchmaker.clone(),
+                matches,
+            )
+            .await;
+        let shutdown = CancellationToken::new();
+        let cron = Arc::new(
+            ProjectCron::new(
+                &self.cfg,
+                mediator.clone(),
+                CronLeaseRepository::new(db.clone()),
+                CronRunRepository::new(db.clone()),
+                CronJobStateRepository::new(db.clone()),
+                shutdown.clone(),
+            )
+            .await?,
+        );
+        let state = AppState::setup(
+            self.cfg.clone(),
+            mediator,
+            db,
+            blobs,
+            events,
+            ws_hub,
+            rooms,
+            cron,
+            shutdown,
+        )
+        .await;
+        let game = GameLoop::new(
+            &self.cfg,
+            Simulation::new(world),
+            SystemClock,
+            game_input_rx,
+            game_snapshots,
+        );
+
+        self.run_and_wait_tasks(
+            state,
+            game,
+            matchmaker,
+            persister,
+            game_snapshot_rx,
+        )
+        .await
     }
     async fn run_and_wait_tasks(
         &self,
         state: AppState,
+        game: GameLoop,
+        matchmaker: Arc<Matchmaker>,
+        persister: WorldPersister,
+        game_snapshots: SnapshotReceiver,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let shutdown = CancellationToken::new();
+        let shutdown = state.shutdown.clone();
         let server_shutdown = shutdown.clone();
         let game_shutdown = shutdown.clone();
         let some_shutdown = shutdown.clone();
-        let cron_shutdown = shutdown.clone();
 
         // ---------------- RUN GAME LOOP (sync blocking func)
         let game_loop_handle = spawn_blocking(move || {
-            while !game_shutdown.is_cancelled() {
-                info!("🎮 Работает игровой цикл...");
-                thread::sleep(Duration::from_millis(50));
-            }
+            let world = game.run(game_shutdown);
             info!("🛑 Game task stopped gracefully");
+            world
         });
 
+        // ---------------- PERSIST GAME WORLD
+        let persistence_handle =
+            spawn(persister.clone().run(game_snapshots, shutdown.clone()));
+
+        // ---------------- RUN MATCHMAKING AND MATCH ROOMS
+        let matchmaking_handle =
+            spawn(state.rooms.clone().run(matchmaker, shutdown.clone()));
+
         // ---------------- RUN ASYNC LOOP
         let some_loop_handle = spawn(async move {
             while !some_shutdown.is_cancelled() {
@@ -62,13 +185,25 @@ impl App {
             info!("🛑 Async task stopped gracefully");
         });
 
+        // ---------------- PURGE EXPIRED RATE LIMITS
+        let rate_limit_handle =
+            spawn(state.rate_limiter.clone().run_purge(shutdown.clone()));
+
         // ---------------- RUN CRON JOBS
+        let cron = state.cron.clone();
         let cron_handle = spawn(async move {
-            if let Err(e) = ProjectCron::start(cron_shutdown).await {
+            if let Err(e) = cron.run().await {
                 error!("Cron error: {:?}", e);
             }
         });
 
+        // ---------------- FORWARD DOMAIN EVENTS TO WEBSOCKETS
+        let ws_events_handle = spawn(forward_events(
+            state.ws_hub.clone(),
+            state.events.clone(),
+            shutdown.clone(),
+        ));
+
         // ---------------- RUN HTTP SERVER
         let server_handle = spawn(async move {
             if let Err(e) =
@@ -83,24 +218,137 @@ impl App {
         info!("✅ Ctrl+C received");
         shutdown.cancel();
 
-        tokio::try_join!(
+        let (_, _, _, _, world, _, _, _) = tokio::try_join!(
             server_handle,
             cron_handle,
+            rate_limit_handle,
             some_loop_handle,
-            game_loop_handle
+            game_loop_handle,
+            ws_events_handle,
+            matchmaking_handle,
+            persistence_handle
         )?;
+        persister.save_on_shutdown(world).await;
         info!("✅ Application stopped cleanly");
 
         Ok(())
     }
 
-    async fn setup_mediator(&self) -> Arc<Mediator> {
+    #[allow(clippy::too_many_arguments)]
+    async fn setup_mediator(
+        &self,
+        db: &DatabaseConnection,
+        http: HttpClient,
+        blobs: Arc<dyn BlobStore>,
+        events: EventBus,
+        rooms: Arc<Rooms>,
+        matchmaker: Arc<Matchmaker>,
+        matches: MatchRepository,
+    ) -> Arc<Mediator> {
         let mediator = Arc::new(Mediator::new());
         mediator
             .register_query::<HelloQuery, GetHelloResult, GetHelloHandler>(
                 GetHelloHandler::new(HelloRepository {}),
             )
             .await;
+
+        let posts = PostRepository::new(db.clone());
+        mediator
+            .register_command::<CreatePostCommand, PostOutcome, _>(
+                CreatePostHandler::new(posts.clone(), events.clone()),
+            )
+            .await;
+        mediator
+            .register_command::<UpdatePostCommand, PostOutcome, _>(
+                UpdatePostHandler::new(posts.clone(), events.clone()),
+            )
+            .await;
+        mediator
+            .register_command::<DeletePostCommand, Result<(), DomainError>, _>(
+                DeletePostHandler::new(posts.clone(), events.clone()),
+            )
+            .await;
+        mediator
+            .register_command::<ImportPostsCommand, Result<ImportedPosts, DomainError>, _>(
+                ImportPostsHandler::new(posts.clone(), events.clone()),
+            )
+            .await;
+        mediator
+            .register_query::<GetPostQuery, PostOutcome, _>(
+                GetPostHandler::new(posts.clone()),
+            )
+            .await;
+        mediator
+            .register_query::<ListPostsQuery, Result<Page<PostResult>, DomainError>, _>(
+                ListPostsHandler::new(posts),
+            )
+            .await;
+        mediator
+            .register_command::<FetchRemotePostsCommand, Result<RemotePosts, DomainError>, _>(
+                FetchRemotePostsHandler::new(Arc::new(PostClient::new(
+                    http,
+                    self.cfg.remote_posts_base_url.clone(),
+                ))),
+            )
+            .await;
+
+        let blob_records = BlobRepository::new(db.clone());
+        mediator
+            .register_command::<StoreBlobCommand, BlobOutcome, _>(
+                StoreBlobHandler::new(blob_records.clone(), blobs, events),
+            )
+            .await;
+        mediator
+            .register_query::<GetBlobQuery, BlobOutcome, _>(
+                GetBlobHandler::new(blob_records),
+            )
+            .await;
+
+        mediator
+            .register_command::<GameInputCommand, Result<(), DomainError>, _>(
+                GameInputHandler::new(rooms.clone()),
+            )
+            .await;
+        mediator
+            .register_command::<JoinQueueCommand, QueueOutcome, _>(
+                JoinQueueHandler::new(
+                    matchmaker.clone(),
+                    rooms.clone(),
+                    matches,
+                ),
+            )
+            .await;
+        mediator
+            .register_command::<LeaveQueueCommand, Result<(), DomainError>, _>(
+                LeaveQueueHandler::new(matchmaker.clone()),
+            )
+            .await;
+        mediator
+            .register_query::<MatchmakingStatusQuery, StatusOutcome, _>(
+                MatchmakingStatusHandler::new(matchmaker, rooms),
+            )
+            .await;
+
+        // Команды и запросы, доступные по имени (batch, WebSocket)
+        mediator.register_named::<CreatePostCommand, PostOutcome>().await;
+        mediator.register_named::<UpdatePostCommand, PostOutcome>().await;
+        mediator
+            .register_named::<DeletePostCommand, Result<(), DomainError>>()
+            .await;
+        mediator.register_named_query::<GetPostQuery, PostOutcome>().await;
+        mediator
+            .register_named::<FetchRemotePostsCommand, Result<RemotePosts, DomainError>>()
+            .await;
+        mediator
+            .register_named::<GameInputCommand, Result<(), DomainError>>()
+            .await;
+        mediator.register_named::<JoinQueueCommand, QueueOutcome>().await;
+        mediator
+            .register_named::<LeaveQueueCommand, Result<(), DomainError>>()
+            .await;
+        mediator
+            .register_named_query::<MatchmakingStatusQuery, StatusOutcome>()
+            .await;
         mediator
     }
 }
@@ -1,17 +1,247 @@
+use chrono_tz::Tz;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::HashSet;
 use std::env::var;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::Duration;
 use tracing::Level;
 use tracing::level_filters::LevelFilter;
 
 #[derive(Clone)]
 pub struct Config {
-    #[allow(dead_code)]
     pub secret_token: String,
     pub server_address: String,
+    /// Пользователи с ролью администратора
+    pub admin_user_ids: Vec<i32>,
     pub workers_count: usize,
     pub log_level: String,
     pub log_file_name_prefix: String,
     pub log_dir_path: String,
     pub db_url: String,
+    pub webhook_secrets: Vec<String>,
+    pub webhook_tolerance_secs: u64,
+    pub rate_limit_store: RateLimitStoreKind,
+    pub rate_limit_default: RateQuota,
+    pub rate_limit_overrides: Vec<(String, RateQuota)>,
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Как часто удалять из хранилища лимитов истёкшие ключи
+    pub rate_limit_purge_secs: u64,
+    /// API-ключи клиентов; по известному ключу у клиента своя квота
+    pub api_keys: Vec<String>,
+    pub batch_max_size: usize,
+    pub blob_store: BlobStoreConfig,
+    pub upload_max_bytes: u64,
+    pub upload_allowed_types: Vec<String>,
+    pub signed_url_ttl_secs: u64,
+    pub sse_replay_size: usize,
+    pub sse_heartbeat_secs: u64,
+    pub ws_queue_size: usize,
+    pub ws_ping_interval_secs: u64,
+    pub ws_idle_timeout_secs: u64,
+    pub game_tick_rate: u32,
+    pub game_catch_up: CatchUpPolicy,
+    pub game_input_queue: usize,
+    pub game_snapshot_every: u64,
+    pub game_interest_radius: i32,
+    /// Зерно генератора; без него выбирается случайно при запуске
+    pub game_seed: Option<u64>,
+    /// Каталог для реплеев; без него реплей не пишется
+    pub game_replay_dir: Option<String>,
+    /// Как часто сохранять общий мир; 0 — не сохранять и не восстанавливать
+    pub game_persist_secs: u64,
+    pub game_persist_compress: bool,
+    /// Сколько последних снимков мира хранить
+    pub game_persist_keep: u64,
+    /// Игроков в матче
+    pub match_size: usize,
+    pub match_bucket_width: i32,
+    /// Через сколько секунд ожидания допускается следующая корзина
+    pub match_widen_secs: u64,
+    /// Максимальная разница корзин рейтинга в одном матче
+    pub match_max_spread: i32,
+    pub match_duration_secs: u64,
+    pub match_ready_timeout_secs: u64,
+    pub match_reconnect_secs: u64,
+    /// Сколько матчей идёт одновременно; у каждого свой поток цикла
+    pub match_max_rooms: usize,
+    /// Задачи по расписанию из `CRON_JOBS_FILE`
+    pub cron_jobs: Vec<CronJobConfig>,
+    /// Имя инстанса в арендах cron; должно различаться у реплик
+    pub cron_instance_id: String,
+    /// Через сколько аренда упавшего инстанса освобождается сама
+    pub cron_lock_ttl_secs: u64,
+    /// Как часто подтягивать паузы и расписания, изменённые через API
+    /// на других инстансах
+    pub cron_sync_secs: u64,
+    /// Адрес API, откуда `posts.fetch_remote` забирает посты
+    pub remote_posts_base_url: String,
+    /// Исходящие запросы к внешним API
+    pub http_client: HttpClientConfig,
+}
+
+#[derive(Clone)]
+pub struct HttpClientConfig {
+    pub connect_timeout: Duration,
+    /// Ограничение на одну попытку, вместе с чтением тела
+    pub request_timeout: Duration,
+    /// Повторы идемпотентных запросов после сетевой ошибки, 5xx или 429
+    pub retries: u32,
+    /// Потолок паузы перед первым повтором, дальше удваивается
+    pub retry_backoff: Duration,
+    /// Дольше ждать не будем, даже если просит `Retry-After`
+    pub retry_max_delay: Duration,
+    /// Столько сбоев подряд открывают автомат хоста
+    pub breaker_failures: u32,
+    /// Сколько автомат открыт до пробного запроса
+    pub breaker_open: Duration,
+}
+
+#[derive(Clone)]
+pub enum BlobStoreConfig {
+    Local { root: String },
+    S3(S3Config),
+}
+
+#[derive(Clone)]
+pub struct S3Config {
+    /// Например `http://localhost:9000` для MinIO
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+/// Задача по расписанию: в момент срабатывания вызывает команду или
+/// запрос по имени, как batch-эндпоинт, но без пользователя.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CronJobConfig {
+    /// Уникальное имя задачи
+    pub name: String,
+    /// Cron с секундами (`0 */5 * * * *`) или по-английски
+    /// (`every 5 minutes`)
+    pub schedule: String,
+    #[serde(default = "default_timezone", deserialize_with = "timezone")]
+    pub timezone: Tz,
+    /// Имя команды, например `posts.fetch_remote`
+    pub command: String,
+    #[serde(default)]
+    pub payload: Value,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    /// Аренда запуска между инстансами; без неё — `CRON_LOCK_TTL_SECS`
+    #[serde(default)]
+    pub lock_ttl_secs: Option<u64>,
+    /// Ограничение на одну попытку; без него попытка не прерывается
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Сколько раз повторить после временной ошибки или таймаута
+    #[serde(default)]
+    pub retries: u32,
+    /// Пауза перед первым повтором, дальше удваивается
+    #[serde(default = "default_retry_backoff_secs")]
+    pub retry_backoff_secs: u64,
+    #[serde(default)]
+    pub overlap: CronOverlap,
+    #[serde(default)]
+    pub catch_up: CronCatchUp,
+}
+
+/// Что делать, если срабатывание наступило, а прошлый запуск ещё идёт.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CronOverlap {
+    /// Пропустить срабатывание
+    #[default]
+    Skip,
+    /// Дождаться конца прошлого запуска. Если за это время начать
+    /// успело более позднее срабатывание, ожидающее отменяется
+    Queue,
+}
+
+/// Что делать со срабатываниями, пропущенными, пока сервис не работал.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CronCatchUp {
+    /// Не выполнять
+    #[default]
+    Skip,
+    /// Выполнить одно, самое позднее
+    Latest,
+    /// Выполнить все по порядку
+    All,
+}
+
+fn default_retry_backoff_secs() -> u64 {
+    5
+}
+
+fn default_timezone() -> Tz {
+    Tz::UTC
+}
+
+fn timezone<'de, D: Deserializer<'de>>(d: D) -> Result<Tz, D::Error> {
+    String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn load_cron_jobs(path: &str) -> Vec<CronJobConfig> {
+    let text = std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Cannot read CRON_JOBS_FILE {path}: {e}"));
+    let jobs: Vec<CronJobConfig> = serde_json::from_str(&text)
+        .unwrap_or_else(|e| panic!("Invalid CRON_JOBS_FILE {path}: {e}"));
+    let mut names = HashSet::new();
+    for job in &jobs {
+        if !names.insert(job.name.as_str()) {
+            panic!("Duplicate cron job name {} in {path}", job.name);
+        }
+    }
+    jobs
+}
+
+/// Что делать, если игровой цикл отстал от расписания на несколько тиков.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CatchUpPolicy {
+    /// Выполнить пропущенные тики подряд, но не больше `max_ticks`
+    /// за раз; остальные пропустить
+    CatchUp { max_ticks: u64 },
+    /// Пропустить все пропущенные тики и продолжить по расписанию
+    Skip,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitStoreKind {
+    Memory,
+    Postgres,
+}
+
+/// Квота вида `100/60`: не больше 100 запросов за 60 секунд.
+#[derive(Clone, Copy, Debug)]
+pub struct RateQuota {
+    pub requests: u32,
+    pub period: Duration,
+}
+
+impl FromStr for RateQuota {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (requests, secs) =
+            s.trim().split_once('/').ok_or(format!("invalid quota {s}"))?;
+        let requests: u32 =
+            requests.parse().map_err(|_| format!("invalid quota {s}"))?;
+        let secs: u64 =
+            secs.parse().map_err(|_| format!("invalid quota {s}"))?;
+        if requests == 0 || secs == 0 {
+            return Err(format!("quota must be positive: {s}"));
+        }
+        Ok(Self { requests, period: Duration::from_secs(secs) })
+    }
 }
 
 impl Config {
@@ -24,6 +254,13 @@ impl Config {
             var("HOST").expect("HOST must be set"),
             var("PORT").expect("PORT must be set")
         );
+        // Пусто — администраторов нет, админские эндпоинты закрыты для всех
+        let admin_user_ids = var("ADMIN_USER_IDS")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.trim().parse().expect("ADMIN_USER_IDS must be user ids"))
+            .collect();
         let workers_count = var("WORKERS_COUNT")
             .expect("WORKERS_COUNT must be set")
             .parse()
@@ -34,14 +271,336 @@ impl Config {
         let log_dir_path =
             var("LOG_DIR_PATH").expect("LOG_DIR_PATH must be set");
         let db_url = var("DATABASE_URL").expect("DATABASE_URL must be set");
+        // Несколько секретов через запятую: старый и новый на время ротации
+        let webhook_secrets = var("WEBHOOK_SECRETS")
+            .expect("WEBHOOK_SECRETS must be set")
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let webhook_tolerance_secs = var("WEBHOOK_TOLERANCE_SECS")
+            .expect("WEBHOOK_TOLERANCE_SECS must be set")
+            .parse()
+            .expect("WEBHOOK_TOLERANCE_SECS must be a number");
+        let rate_limit_store = match var("RATE_LIMIT_STORE")
+            .expect("RATE_LIMIT_STORE must be set")
+            .as_str()
+        {
+            "memory" => RateLimitStoreKind::Memory,
+            "postgres" => RateLimitStoreKind::Postgres,
+            other => panic!(
+                "RATE_LIMIT_STORE must be memory or postgres, got {other}"
+            ),
+        };
+        let rate_limit_default = var("RATE_LIMIT_DEFAULT")
+            .expect("RATE_LIMIT_DEFAULT must be set")
+            .parse()
+            .expect("RATE_LIMIT_DEFAULT must look like 100/60");
+        // Формат: /ws=10/60,/api/v1/webhooks=300/60 (самый длинный префикс побеждает)
+        let rate_limit_overrides = var("RATE_LIMIT_OVERRIDES")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| {
+                let (prefix, quota) = s
+                    .split_once('=')
+                    .expect("RATE_LIMIT_OVERRIDES must look like /path=10/60");
+                let quota = quota
+                    .parse()
+                    .expect("RATE_LIMIT_OVERRIDES must look like /path=10/60");
+                (prefix.trim().to_string(), quota)
+            })
+            .collect();
+        let trusted_proxies = var("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| {
+                s.trim().parse().expect("TRUSTED_PROXIES must be IP addresses")
+            })
+            .collect();
+        let rate_limit_purge_secs = var("RATE_LIMIT_PURGE_SECS")
+            .expect("RATE_LIMIT_PURGE_SECS must be set")
+            .parse::<u64>()
+            .expect("RATE_LIMIT_PURGE_SECS must be a number")
+            .max(1);
+        let api_keys = var("API_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let batch_max_size = var("BATCH_MAX_SIZE")
+            .expect("BATCH_MAX_SIZE must be set")
+            .parse()
+            .expect("BATCH_MAX_SIZE must be a number");
+        let blob_store =
+            match var("BLOB_STORE").expect("BLOB_STORE must be set").as_str() {
+                "local" => BlobStoreConfig::Local {
+                    root: var("BLOB_LOCAL_DIR")
+                        .expect("BLOB_LOCAL_DIR must be set"),
+                },
+                "s3" => BlobStoreConfig::S3(S3Config {
+                    endpoint: var("S3_ENDPOINT")
+                        .expect("S3_ENDPOINT must be set"),
+                    bucket: var("S3_BUCKET").expect("S3_BUCKET must be set"),
+                    region: var("S3_REGION").unwrap_or("us-east-1".to_string()),
+                    access_key: var("S3_ACCESS_KEY")
+                        .expect("S3_ACCESS_KEY must be set"),
+                    secret_key: var("S3_SECRET_KEY")
+                        .expect("S3_SECRET_KEY must be set"),
+                }),
+                other => panic!("BLOB_STORE must be local or s3, got {other}"),
+            };
+        let upload_max_bytes = var("UPLOAD_MAX_BYTES")
+            .expect("UPLOAD_MAX_BYTES must be set")
+            .parse()
+            .expect("UPLOAD_MAX_BYTES must be a number");
+        // Точные типы или маски вида image/*
+        let upload_allowed_types = var("UPLOAD_ALLOWED_TYPES")
+            .expect("UPLOAD_ALLOWED_TYPES must be set")
+            .split(',')
+            .map(|s| s.trim().to_ascii_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let signed_url_ttl_secs = var("SIGNED_URL_TTL_SECS")
+            .expect("SIGNED_URL_TTL_SECS must be set")
+            .parse()
+            .expect("SIGNED_URL_TTL_SECS must be a number");
+        // Сколько последних событий помнить для Last-Event-ID
+        let sse_replay_size = var("SSE_REPLAY_SIZE")
+            .expect("SSE_REPLAY_SIZE must be set")
+            .parse()
+            .expect("SSE_REPLAY_SIZE must be a number");
+        let sse_heartbeat_secs = var("SSE_HEARTBEAT_SECS")
+            .expect("SSE_HEARTBEAT_SECS must be set")
+            .parse()
+            .expect("SSE_HEARTBEAT_SECS must be a number");
+        // Сколько кадров может ждать отправки одному клиенту, прежде чем
+        // его отключат как медленного
+        let ws_queue_size = var("WS_QUEUE_SIZE")
+            .expect("WS_QUEUE_SIZE must be set")
+            .parse()
+            .expect("WS_QUEUE_SIZE must be a number");
+        let ws_ping_interval_secs = var("WS_PING_INTERVAL_SECS")
+            .expect("WS_PING_INTERVAL_SECS must be set")
+            .parse::<u64>()
+            .expect("WS_PING_INTERVAL_SECS must be a number")
+            .max(1);
+        let ws_idle_timeout_secs = var("WS_IDLE_TIMEOUT_SECS")
+            .expect("WS_IDLE_TIMEOUT_SECS must be set")
+            .parse()
+            .expect("WS_IDLE_TIMEOUT_SECS must be a number");
+        let game_tick_rate = var("GAME_TICK_RATE")
+            .expect("GAME_TICK_RATE must be set")
+            .parse()
+            .expect("GAME_TICK_RATE must be a number of ticks per second");
+        let game_catch_up = match var("GAME_CATCH_UP")
+            .expect("GAME_CATCH_UP must be set")
+            .as_str()
+        {
+            "catch-up" => CatchUpPolicy::CatchUp {
+                max_ticks: var("GAME_MAX_CATCH_UP_TICKS")
+                    .expect("GAME_MAX_CATCH_UP_TICKS must be set")
+                    .parse::<u64>()
+                    .expect("GAME_MAX_CATCH_UP_TICKS must be a number")
+                    .max(1),
+            },
+            "skip" => CatchUpPolicy::Skip,
+            other => {
+                panic!("GAME_CATCH_UP must be catch-up or skip, got {other}")
+            }
+        };
+        let game_input_queue = var("GAME_INPUT_QUEUE")
+            .expect("GAME_INPUT_QUEUE must be set")
+            .parse()
+            .expect("GAME_INPUT_QUEUE must be a number");
+        let game_snapshot_every = var("GAME_SNAPSHOT_EVERY")
+            .expect("GAME_SNAPSHOT_EVERY must be set")
+            .parse::<u64>()
+            .expect("GAME_SNAPSHOT_EVERY must be a number of ticks")
+            .max(1);
+        let game_interest_radius = var("GAME_INTEREST_RADIUS")
+            .expect("GAME_INTEREST_RADIUS must be set")
+            .parse()
+            .expect("GAME_INTEREST_RADIUS must be a number");
+        let game_seed = var("GAME_SEED")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().expect("GAME_SEED must be a number"));
+        let game_replay_dir =
+            var("GAME_REPLAY_DIR").ok().filter(|s| !s.is_empty());
+        let game_persist_secs = var("GAME_PERSIST_SECS")
+            .expect("GAME_PERSIST_SECS must be set")
+            .parse()
+            .expect("GAME_PERSIST_SECS must be a number");
+        let game_persist_compress = var("GAME_PERSIST_COMPRESS")
+            .expect("GAME_PERSIST_COMPRESS must be set")
+            .parse()
+            .expect("GAME_PERSIST_COMPRESS must be true or false");
+        let game_persist_keep = var("GAME_PERSIST_KEEP")
+            .expect("GAME_PERSIST_KEEP must be set")
+            .parse()
+            .expect("GAME_PERSIST_KEEP must be a number");
+        let match_size = var("MATCH_SIZE")
+            .expect("MATCH_SIZE must be set")
+            .parse()
+            .expect("MATCH_SIZE must be a number of players");
+        let match_bucket_width = var("MATCH_BUCKET_WIDTH")
+            .expect("MATCH_BUCKET_WIDTH must be set")
+            .parse()
+            .expect("MATCH_BUCKET_WIDTH must be a number");
+        let match_widen_secs = var("MATCH_WIDEN_SECS")
+            .expect("MATCH_WIDEN_SECS must be set")
+            .parse()
+            .expect("MATCH_WIDEN_SECS must be a number");
+        let match_max_spread = var("MATCH_MAX_SPREAD")
+            .expect("MATCH_MAX_SPREAD must be set")
+            .parse()
+            .expect("MATCH_MAX_SPREAD must be a number of buckets");
+        let match_duration_secs = var("MATCH_DURATION_SECS")
+            .expect("MATCH_DURATION_SECS must be set")
+            .parse()
+            .expect("MATCH_DURATION_SECS must be a number");
+        let match_ready_timeout_secs = var("MATCH_READY_TIMEOUT_SECS")
+            .expect("MATCH_READY_TIMEOUT_SECS must be set")
+            .parse()
+            .expect("MATCH_READY_TIMEOUT_SECS must be a number");
+        let match_reconnect_secs = var("MATCH_RECONNECT_SECS")
+            .expect("MATCH_RECONNECT_SECS must be set")
+            .parse()
+            .expect("MATCH_RECONNECT_SECS must be a number");
+        let match_max_rooms = var("MATCH_MAX_ROOMS")
+            .expect("MATCH_MAX_ROOMS must be set")
+            .parse()
+            .expect("MATCH_MAX_ROOMS must be a number of rooms");
+        // Пустое значение — без задач по расписанию
+        let cron_jobs = var("CRON_JOBS_FILE")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|path| load_cron_jobs(&path))
+            .unwrap_or_default();
+        // Без CRON_INSTANCE_ID имя уникально для каждого запуска
+        let cron_instance_id = var("CRON_INSTANCE_ID")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| {
+                let host = var("HOSTNAME").unwrap_or_else(|_| "local".into());
+                let suffix = uuid::Uuid::new_v4().simple().to_string();
+                format!("{host}-{}", &suffix[..8])
+            });
+        let cron_lock_ttl_secs = var("CRON_LOCK_TTL_SECS")
+            .expect("CRON_LOCK_TTL_SECS must be set")
+            .parse::<u64>()
+            .expect("CRON_LOCK_TTL_SECS must be a number")
+            .max(1);
+        let cron_sync_secs = var("CRON_SYNC_SECS")
+            .expect("CRON_SYNC_SECS must be set")
+            .parse::<u64>()
+            .expect("CRON_SYNC_SECS must be a number")
+            .max(1);
+        // Раньше задавался полный адрес списка в REMOTE_POSTS_URL: его
+        // по-прежнему читаем, отрезая путь endpoint-а
+        let remote_posts_base_url = var("REMOTE_POSTS_BASE_URL")
+            .or_else(|_| {
+                var("REMOTE_POSTS_URL").map(|url| {
+                    let url = url.trim_end_matches('/');
+                    url.strip_suffix("/posts").unwrap_or(url).to_string()
+                })
+            })
+            .expect("REMOTE_POSTS_BASE_URL must be set");
+        let http_client = HttpClientConfig {
+            connect_timeout: Duration::from_secs(
+                var("HTTP_CONNECT_TIMEOUT_SECS")
+                    .expect("HTTP_CONNECT_TIMEOUT_SECS must be set")
+                    .parse()
+                    .expect("HTTP_CONNECT_TIMEOUT_SECS must be a number"),
+            ),
+            request_timeout: Duration::from_secs(
+                var("HTTP_REQUEST_TIMEOUT_SECS")
+                    .expect("HTTP_REQUEST_TIMEOUT_SECS must be set")
+                    .parse()
+                    .expect("HTTP_REQUEST_TIMEOUT_SECS must be a number"),
+            ),
+            retries: var("HTTP_RETRIES")
+                .expect("HTTP_RETRIES must be set")
+                .parse()
+                .expect("HTTP_RETRIES must be a number"),
+            retry_backoff: Duration::from_millis(
+                var("HTTP_RETRY_BACKOFF_MS")
+                    .expect("HTTP_RETRY_BACKOFF_MS must be set")
+                    .parse()
+                    .expect("HTTP_RETRY_BACKOFF_MS must be a number"),
+            ),
+            retry_max_delay: Duration::from_secs(
+                var("HTTP_RETRY_MAX_DELAY_SECS")
+                    .expect("HTTP_RETRY_MAX_DELAY_SECS must be set")
+                    .parse()
+                    .expect("HTTP_RETRY_MAX_DELAY_SECS must be a number"),
+            ),
+            breaker_failures: var("HTTP_BREAKER_FAILURES")
+                .expect("HTTP_BREAKER_FAILURES must be set")
+                .parse::<u32>()
+                .expect("HTTP_BREAKER_FAILURES must be a number")
+                .max(1),
+            breaker_open: Duration::from_secs(
+                var("HTTP_BREAKER_OPEN_SECS")
+                    .expect("HTTP_BREAKER_OPEN_SECS must be set")
+                    .parse()
+                    .expect("HTTP_BREAKER_OPEN_SECS must be a number"),
+            ),
+        };
         Self {
             secret_token,
             server_address,
+            admin_user_ids,
             log_level,
             workers_count,
             log_file_name_prefix,
             log_dir_path,
             db_url,
+            webhook_secrets,
+            webhook_tolerance_secs,
+            rate_limit_store,
+            rate_limit_default,
+            rate_limit_overrides,
+            trusted_proxies,
+            rate_limit_purge_secs,
+            api_keys,
+            batch_max_size,
+            blob_store,
+            upload_max_bytes,
+            upload_allowed_types,
+            signed_url_ttl_secs,
+            sse_replay_size,
+            sse_heartbeat_secs,
+            ws_queue_size,
+            ws_ping_interval_secs,
+            ws_idle_timeout_secs,
+            game_tick_rate,
+            game_catch_up,
+            game_input_queue,
+            game_snapshot_every,
+            game_interest_radius,
+            game_seed,
+            game_replay_dir,
+            game_persist_secs,
+            game_persist_compress,
+            game_persist_keep,
+            match_size,
+            match_bucket_width,
+            match_widen_secs,
+            match_max_spread,
+            match_duration_secs,
+            match_ready_timeout_secs,
+            match_reconnect_secs,
+            match_max_rooms,
+            cron_jobs,
+            cron_instance_id,
+            cron_lock_ttl_secs,
+            cron_sync_secs,
+            remote_posts_base_url,
+            http_client,
         }
     }
     pub fn get_log_level_filter(&self) -> LevelFilter {
@@ -0,0 +1,46 @@
+use crate::infra::storage::blobs::BlobError;
+use sea_orm::DbErr;
+use thiserror::Error;
+
+#[derive(Debug, Clone)]
+pub struct FieldViolation {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldViolation {
+    pub fn new(field: &str, message: &str) -> Self {
+        Self { field: field.to_string(), message: message.to_string() }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DomainError {
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("{0}")]
+    PreconditionFailed(String),
+
+    /// Действие противоречит текущему состоянию: уже в очереди, уже
+    /// в матче
+    #[error("{0}")]
+    Conflict(String),
+
+    /// Временно не можем выполнить: очередь переполнена, сервис
+    /// останавливается
+    #[error("{0}")]
+    Unavailable(String),
+
+    #[error("Validation failed")]
+    Validation(Vec<FieldViolation>),
+
+    #[error(transparent)]
+    Storage(#[from] DbErr),
+
+    #[error(transparent)]
+    Blob(#[from] BlobError),
+}
@@ -0,0 +1,149 @@
+use crate::core::results::blobs::BlobResult;
+use crate::core::results::posts::PostResult;
+use crate::game::rooms::RoomInfo;
+use crate::infra::storage::tx::after_commit;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Событие предметной области для живых подписчиков (SSE).
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum DomainEvent {
+    PostCreated(PostResult),
+    PostUpdated(PostResult),
+    PostDeleted {
+        id: i32,
+        author_id: i32,
+    },
+    PostsImported {
+        author_id: i32,
+        imported: u64,
+    },
+    BlobStored(BlobResult),
+    /// Матч игрока собран, начался, закончился или ждёт переподключения
+    RoomUpdated {
+        user_id: i32,
+        room: RoomInfo,
+    },
+}
+
+impl DomainEvent {
+    pub const NAMES: &'static [&'static str] = &[
+        "post.created",
+        "post.updated",
+        "post.deleted",
+        "posts.imported",
+        "blob.stored",
+        "room.updated",
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::PostCreated(_) => "post.created",
+            DomainEvent::PostUpdated(_) => "post.updated",
+            DomainEvent::PostDeleted { .. } => "post.deleted",
+            DomainEvent::PostsImported { .. } => "posts.imported",
+            DomainEvent::BlobStored(_) => "blob.stored",
+            DomainEvent::RoomUpdated { .. } => "room.updated",
+        }
+    }
+
+    /// Единственный получатель приватного события; `None` — событие
+    /// публичное. Посты видят все, импорт, файлы и матчи — только
+    /// владелец.
+    pub fn recipient(&self) -> Option<i32> {
+        match self {
+            DomainEvent::PostCreated(_)
+            | DomainEvent::PostUpdated(_)
+            | DomainEvent::PostDeleted { .. } => None,
+            DomainEvent::PostsImported { author_id, .. } => Some(*author_id),
+            DomainEvent::BlobStored(blob) => Some(blob.owner_id),
+            DomainEvent::RoomUpdated { user_id, .. } => Some(*user_id),
+        }
+    }
+
+    pub fn is_visible_to(&self, user_id: i32) -> bool {
+        self.recipient().is_none_or(|recipient| recipient == user_id)
+    }
+}
+
+/// Опубликованное событие с порядковым номером (он же SSE `id`).
+#[derive(Debug)]
+pub struct StoredEvent {
+    pub id: u64,
+    pub event: DomainEvent,
+}
+
+/// Подписка: события из буфера после `Last-Event-ID` и живой канал.
+pub struct Subscription {
+    pub replay: Vec<Arc<StoredEvent>>,
+    /// Часть событий после `Last-Event-ID` уже вытеснена из буфера
+    /// (или id из прошлого запуска): клиенту нужно перечитать состояние
+    pub gap: bool,
+    pub live: broadcast::Receiver<Arc<StoredEvent>>,
+}
+
+struct Journal {
+    next_id: u64,
+    recent: VecDeque<Arc<StoredEvent>>,
+}
+
+/// Шина событий процесса: раздаёт события подписчикам и хранит последние
+/// `capacity` штук для продолжения после переподключения.
+#[derive(Clone)]
+pub struct EventBus {
+    journal: Arc<Mutex<Journal>>,
+    sender: broadcast::Sender<Arc<StoredEvent>>,
+    capacity: usize,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            journal: Arc::new(Mutex::new(Journal {
+                next_id: 1,
+                recent: VecDeque::with_capacity(capacity),
+            })),
+            sender,
+            capacity,
+        }
+    }
+
+    /// Публикует событие после commit текущей транзакции: подписчики
+    /// не увидят изменений, которые потом откатятся.
+    pub fn publish(&self, event: DomainEvent) {
+        let bus = self.clone();
+        after_commit(move || bus.publish_now(event));
+    }
+
+    fn publish_now(&self, event: DomainEvent) {
+        // Номер, буфер и отправка под одной блокировкой, иначе подписчик
+        // между чтением буфера и subscribe() мог бы потерять событие
+        let mut journal = self.journal.lock().expect("event journal poisoned");
+        let stored = Arc::new(StoredEvent { id: journal.next_id, event });
+        journal.next_id += 1;
+        if journal.recent.len() == self.capacity {
+            journal.recent.pop_front();
+        }
+        journal.recent.push_back(stored.clone());
+        // Ошибка означает лишь отсутствие подписчиков
+        let _ = self.sender.send(stored);
+    }
+
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> Subscription {
+        let journal = self.journal.lock().expect("event journal poisoned");
+        let live = self.sender.subscribe();
+        let Some(last) = last_event_id else {
+            return Subscription { replay: Vec::new(), gap: false, live };
+        };
+        let oldest = journal.recent.front().map_or(journal.next_id, |e| e.id);
+        let gap = last >= journal.next_id || last + 1 < oldest;
+        let replay =
+            journal.recent.iter().filter(|e| e.id > last).cloned().collect();
+        Subscription { replay, gap, live }
+    }
+}
@@ -1 +1,79 @@
+use crate::core::errors::DomainError;
+use crate::core::handlers::hello::{Command, Query};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 
+/// От чьего имени выполняется команда, вызванная по имени.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CommandContext {
+    /// `None` — системный вызов без пользователя
+    pub user_id: Option<i32>,
+}
+
+impl CommandContext {
+    pub fn user(user_id: i32) -> Self {
+        Self { user_id: Some(user_id) }
+    }
+
+    pub fn require_user(&self) -> Result<i32, DomainError> {
+        self.user_id.ok_or_else(|| {
+            DomainError::Forbidden(
+                "Command requires an authenticated user".to_string(),
+            )
+        })
+    }
+
+    /// Для служебных команд, которые нельзя вызвать снаружи от имени
+    /// пользователя: cron, внутренние задачи.
+    pub fn require_system(&self) -> Result<(), DomainError> {
+        match self.user_id {
+            None => Ok(()),
+            Some(_) => Err(DomainError::Forbidden(
+                "Command is only available to the system".to_string(),
+            )),
+        }
+    }
+}
+
+/// Команда, которую можно вызвать по имени с JSON-телом, а не только
+/// типизированно: batch-эндпоинт, WebSocket, cron.
+pub trait NamedCommand: Command + Sized + 'static {
+    /// Имя для внешних вызовов, например `posts.create`
+    const NAME: &'static str;
+
+    type Payload: DeserializeOwned + Send;
+
+    fn from_payload(
+        payload: Self::Payload,
+        ctx: &CommandContext,
+    ) -> Result<Self, DomainError>;
+}
+
+/// Запрос на чтение, вызываемый по имени, как `NamedCommand`.
+pub trait NamedQuery: Query + Sized + 'static {
+    /// Имя для внешних вызовов, например `posts.get`
+    const NAME: &'static str;
+
+    type Payload: DeserializeOwned + Send;
+
+    fn from_payload(
+        payload: Self::Payload,
+        ctx: &CommandContext,
+    ) -> Result<Self, DomainError>;
+}
+
+/// Результат команды, который можно отдать наружу как JSON.
+pub trait CommandOutcome: Send + Sync + 'static {
+    fn into_json(self) -> Result<Value, DomainError>;
+}
+
+impl<T> CommandOutcome for Result<T, DomainError>
+where
+    T: Serialize + Send + Sync + 'static,
+{
+    fn into_json(self) -> Result<Value, DomainError> {
+        // `()` превращается в null — вызывающий отвечает без тела
+        Ok(serde_json::to_value(self?).unwrap_or(Value::Null))
+    }
+}
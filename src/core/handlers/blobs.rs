@@ -0,0 +1,100 @@
+use crate::core::errors::DomainError;
+use crate::core::events::{DomainEvent, EventBus};
+use crate::core::handlers::hello::{
+    Command, CommandHandler, Query, QueryHandler,
+};
+use crate::core::results::blobs::BlobResult;
+use crate::infra::storage::blob_meta::BlobRepository;
+use crate::infra::storage::blobs::BlobStore;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Сохранить загруженный файл, уже записанный во временный `path`
+/// и посчитанный `sha256`.
+#[derive(Debug, Clone)]
+pub struct StoreBlobCommand {
+    pub owner_id: i32,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: u64,
+    pub sha256: String,
+    pub path: PathBuf,
+}
+
+impl Command for StoreBlobCommand {}
+
+#[derive(Debug, Clone)]
+pub struct GetBlobQuery {
+    pub id: i32,
+}
+
+impl Query for GetBlobQuery {}
+
+pub type BlobOutcome = Result<BlobResult, DomainError>;
+
+pub struct StoreBlobHandler {
+    repo: BlobRepository,
+    store: Arc<dyn BlobStore>,
+    events: EventBus,
+}
+
+impl StoreBlobHandler {
+    pub fn new(
+        repo: BlobRepository,
+        store: Arc<dyn BlobStore>,
+        events: EventBus,
+    ) -> Self {
+        Self { repo, store, events }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<StoreBlobCommand, BlobOutcome> for StoreBlobHandler {
+    async fn execute(&self, command: StoreBlobCommand) -> BlobOutcome {
+        // Ключ объекта — хеш содержимого: одинаковый файл второй раз
+        // не загружаем, только добавляем строку метаданных
+        if !self.store.exists(&command.sha256).await? {
+            self.store
+                .put_file(
+                    &command.sha256,
+                    &command.path,
+                    command.size,
+                    &command.content_type,
+                )
+                .await?;
+        }
+        let blob = self
+            .repo
+            .create(
+                command.owner_id,
+                command.file_name,
+                command.content_type,
+                command.size as i64,
+                command.sha256,
+            )
+            .await?;
+        let blob = BlobResult::from(blob);
+        self.events.publish(DomainEvent::BlobStored(blob.clone()));
+        Ok(blob)
+    }
+}
+
+pub struct GetBlobHandler {
+    repo: BlobRepository,
+}
+
+impl GetBlobHandler {
+    pub fn new(repo: BlobRepository) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl QueryHandler<GetBlobQuery, BlobOutcome> for GetBlobHandler {
+    async fn execute(&self, query: GetBlobQuery) -> BlobOutcome {
+        self.repo.get_by_id(query.id).await?.map(BlobResult::from).ok_or_else(
+            || DomainError::NotFound(format!("File {} not found", query.id)),
+        )
+    }
+}
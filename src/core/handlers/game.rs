@@ -0,0 +1,72 @@
+use crate::core::errors::{DomainError, FieldViolation};
+use crate::core::handlers::base::{CommandContext, NamedCommand};
+use crate::core::handlers::hello::{Command, CommandHandler};
+use crate::game::input::{InputKind, PlayerInput};
+use crate::game::rooms::Rooms;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Ввод игрока для игрового цикла.
+#[derive(Debug, Clone)]
+pub struct GameInputCommand {
+    pub player_id: i32,
+    pub kind: InputKind,
+}
+
+impl Command for GameInputCommand {}
+
+impl NamedCommand for GameInputCommand {
+    const NAME: &'static str = "game.input";
+    type Payload = InputKind;
+
+    fn from_payload(
+        payload: InputKind,
+        ctx: &CommandContext,
+    ) -> Result<Self, DomainError> {
+        Ok(Self { player_id: ctx.require_user()?, kind: payload })
+    }
+}
+
+pub struct GameInputHandler {
+    rooms: Arc<Rooms>,
+}
+
+impl GameInputHandler {
+    pub fn new(rooms: Arc<Rooms>) -> Self {
+        Self { rooms }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<GameInputCommand, Result<(), DomainError>>
+    for GameInputHandler
+{
+    /// Только ставит ввод в очередь мира, где сейчас игрок (лобби или
+    /// матч): применится он в ближайшем тике.
+    async fn execute(
+        &self,
+        command: GameInputCommand,
+    ) -> Result<(), DomainError> {
+        if let InputKind::Move { dx, dy } = command.kind {
+            let mut violations = Vec::new();
+            if !(-1..=1).contains(&dx) {
+                violations
+                    .push(FieldViolation::new("dx", "must be -1, 0 or 1"));
+            }
+            if !(-1..=1).contains(&dy) {
+                violations
+                    .push(FieldViolation::new("dy", "must be -1, 0 or 1"));
+            }
+            if !violations.is_empty() {
+                return Err(DomainError::Validation(violations));
+            }
+        }
+        self.rooms
+            .input_for(command.player_id)?
+            .send(PlayerInput {
+                player_id: command.player_id,
+                kind: command.kind,
+            })
+            .map_err(|e| DomainError::Unavailable(e.to_string()))
+    }
+}
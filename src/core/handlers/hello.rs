@@ -26,8 +26,8 @@ pub struct HelloCommand {
 impl Command for HelloCommand {}
 
 #[async_trait]
-pub trait CommandHandler<C: Command>: Send + Sync {
-    async fn execute(&self, command: C);
+pub trait CommandHandler<C: Command, R: Send + Sync>: Send + Sync {
+    async fn execute(&self, command: C) -> R;
 }
 
 #[async_trait]
@@ -38,7 +38,7 @@ pub trait QueryHandler<Q: Query, R: Send + Sync>: Send + Sync {
 pub struct CreateHelloHandler;
 
 #[async_trait]
-impl CommandHandler<HelloCommand> for CreateHelloHandler {
+impl CommandHandler<HelloCommand, ()> for CreateHelloHandler {
     async fn execute(&self, command: HelloCommand) {
         println!("Hello from HelloHandler: {}", command.name);
     }
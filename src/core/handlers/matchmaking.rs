@@ -0,0 +1,174 @@
+use crate::core::errors::DomainError;
+use crate::core::handlers::base::{CommandContext, NamedCommand, NamedQuery};
+use crate::core::handlers::hello::{
+    Command, CommandHandler, Query, QueryHandler,
+};
+use crate::core::results::matchmaking::MatchmakingStatus;
+use crate::game::matchmaking::{Matchmaker, QueueStatus};
+use crate::game::results::DEFAULT_RATING;
+use crate::game::rooms::Rooms;
+use crate::infra::storage::matches::MatchRepository;
+use async_trait::async_trait;
+use serde::de::IgnoredAny;
+use std::sync::Arc;
+
+/// Встать в очередь подбора.
+#[derive(Debug, Clone)]
+pub struct JoinQueueCommand {
+    pub user_id: i32,
+}
+
+impl Command for JoinQueueCommand {}
+
+impl NamedCommand for JoinQueueCommand {
+    const NAME: &'static str = "matchmaking.join";
+    type Payload = IgnoredAny;
+
+    fn from_payload(
+        _payload: IgnoredAny,
+        ctx: &CommandContext,
+    ) -> Result<Self, DomainError> {
+        Ok(Self { user_id: ctx.require_user()? })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LeaveQueueCommand {
+    pub user_id: i32,
+}
+
+impl Command for LeaveQueueCommand {}
+
+impl NamedCommand for LeaveQueueCommand {
+    const NAME: &'static str = "matchmaking.leave";
+    type Payload = IgnoredAny;
+
+    fn from_payload(
+        _payload: IgnoredAny,
+        ctx: &CommandContext,
+    ) -> Result<Self, DomainError> {
+        Ok(Self { user_id: ctx.require_user()? })
+    }
+}
+
+/// Где игрок: в очереди, в матче или нигде. После переподключения
+/// клиент узнаёт отсюда свою комнату.
+#[derive(Debug, Clone)]
+pub struct MatchmakingStatusQuery {
+    pub user_id: i32,
+}
+
+impl Query for MatchmakingStatusQuery {}
+
+impl NamedQuery for MatchmakingStatusQuery {
+    const NAME: &'static str = "matchmaking.status";
+    type Payload = IgnoredAny;
+
+    fn from_payload(
+        _payload: IgnoredAny,
+        ctx: &CommandContext,
+    ) -> Result<Self, DomainError> {
+        Ok(Self { user_id: ctx.require_user()? })
+    }
+}
+
+pub type QueueOutcome = Result<QueueStatus, DomainError>;
+pub type StatusOutcome = Result<MatchmakingStatus, DomainError>;
+
+pub struct JoinQueueHandler {
+    matchmaker: Arc<Matchmaker>,
+    rooms: Arc<Rooms>,
+    repo: MatchRepository,
+}
+
+impl JoinQueueHandler {
+    pub fn new(
+        matchmaker: Arc<Matchmaker>,
+        rooms: Arc<Rooms>,
+        repo: MatchRepository,
+    ) -> Self {
+        Self { matchmaker, rooms, repo }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<JoinQueueCommand, QueueOutcome> for JoinQueueHandler {
+    async fn execute(&self, command: JoinQueueCommand) -> QueueOutcome {
+        let user_id = command.user_id;
+        // Ушедших из сети очередь выкидывает на следующем проходе, так
+        // что встать в неё можно только с открытым WebSocket
+        if !self.rooms.is_online(user_id) {
+            return Err(DomainError::Conflict(
+                "Open a game WebSocket connection before joining the queue"
+                    .to_string(),
+            ));
+        }
+        if let Some(room) = self.rooms.room_of(user_id) {
+            return Err(DomainError::Conflict(format!(
+                "Already playing in room {}",
+                room.id
+            )));
+        }
+        let rating = self.repo.rating(user_id).await?.unwrap_or(DEFAULT_RATING);
+        if !self.matchmaker.enqueue(user_id, rating) {
+            return Err(DomainError::Conflict(
+                "Already in the matchmaking queue".to_string(),
+            ));
+        }
+        self.matchmaker.status(user_id).ok_or_else(|| {
+            // Матч мог собраться сразу после постановки в очередь
+            DomainError::Conflict("Match has already been found".to_string())
+        })
+    }
+}
+
+pub struct LeaveQueueHandler {
+    matchmaker: Arc<Matchmaker>,
+}
+
+impl LeaveQueueHandler {
+    pub fn new(matchmaker: Arc<Matchmaker>) -> Self {
+        Self { matchmaker }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<LeaveQueueCommand, Result<(), DomainError>>
+    for LeaveQueueHandler
+{
+    async fn execute(
+        &self,
+        command: LeaveQueueCommand,
+    ) -> Result<(), DomainError> {
+        if self.matchmaker.dequeue(command.user_id) {
+            Ok(())
+        } else {
+            Err(DomainError::NotFound(
+                "Not in the matchmaking queue".to_string(),
+            ))
+        }
+    }
+}
+
+pub struct MatchmakingStatusHandler {
+    matchmaker: Arc<Matchmaker>,
+    rooms: Arc<Rooms>,
+}
+
+impl MatchmakingStatusHandler {
+    pub fn new(matchmaker: Arc<Matchmaker>, rooms: Arc<Rooms>) -> Self {
+        Self { matchmaker, rooms }
+    }
+}
+
+#[async_trait]
+impl QueryHandler<MatchmakingStatusQuery, StatusOutcome>
+    for MatchmakingStatusHandler
+{
+    async fn execute(&self, query: MatchmakingStatusQuery) -> StatusOutcome {
+        Ok(MatchmakingStatus {
+            queue: self.matchmaker.status(query.user_id),
+            room: self.rooms.room_of(query.user_id),
+        })
+    }
+}
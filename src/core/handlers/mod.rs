@@ -1,2 +1,6 @@
 pub mod base;
+pub mod blobs;
+pub mod game;
 pub mod hello;
+pub mod matchmaking;
+pub mod posts;
@@ -0,0 +1,485 @@
+use crate::core::errors::{DomainError, FieldViolation};
+use crate::core::events::{DomainEvent, EventBus};
+use crate::core::handlers::base::{CommandContext, NamedCommand, NamedQuery};
+use crate::core::handlers::hello::{
+    Command, CommandHandler, Query, QueryHandler,
+};
+use crate::core::listing::{ListRequest, Page};
+use crate::core::results::posts::{
+    ImportedPosts, PostResult, RejectedRow, RemotePosts,
+};
+use crate::infra::clients::client::PostsApi;
+use crate::infra::storage::entities::post;
+use crate::infra::storage::posts::PostRepository;
+use async_trait::async_trait;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::debug;
+use utoipa::ToSchema;
+
+const TITLE_MAX_LEN: usize = 200;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CreatePostCommand {
+    pub author_id: i32,
+    pub title: String,
+    pub text: String,
+}
+
+impl Command for CreatePostCommand {}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CreatePostPayload {
+    #[schema(example = "Hello")]
+    pub title: String,
+    #[schema(example = "First post")]
+    pub text: String,
+}
+
+impl NamedCommand for CreatePostCommand {
+    const NAME: &'static str = "posts.create";
+    type Payload = CreatePostPayload;
+
+    fn from_payload(
+        payload: CreatePostPayload,
+        ctx: &CommandContext,
+    ) -> Result<Self, DomainError> {
+        Ok(Self {
+            author_id: ctx.require_user()?,
+            title: payload.title,
+            text: payload.text,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdatePostCommand {
+    pub id: i32,
+    pub user_id: i32,
+    /// Версия из `If-Match`; `None` — безусловное обновление
+    pub expected_version: Option<i32>,
+    pub title: String,
+    pub text: String,
+}
+
+impl Command for UpdatePostCommand {}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdatePostPayload {
+    pub id: i32,
+    #[serde(default)]
+    pub expected_version: Option<i32>,
+    pub title: String,
+    pub text: String,
+}
+
+impl NamedCommand for UpdatePostCommand {
+    const NAME: &'static str = "posts.update";
+    type Payload = UpdatePostPayload;
+
+    fn from_payload(
+        payload: UpdatePostPayload,
+        ctx: &CommandContext,
+    ) -> Result<Self, DomainError> {
+        Ok(Self {
+            id: payload.id,
+            user_id: ctx.require_user()?,
+            expected_version: payload.expected_version,
+            title: payload.title,
+            text: payload.text,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeletePostCommand {
+    pub id: i32,
+    pub user_id: i32,
+    pub expected_version: Option<i32>,
+}
+
+impl Command for DeletePostCommand {}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeletePostPayload {
+    pub id: i32,
+    #[serde(default)]
+    pub expected_version: Option<i32>,
+}
+
+impl NamedCommand for DeletePostCommand {
+    const NAME: &'static str = "posts.delete";
+    type Payload = DeletePostPayload;
+
+    fn from_payload(
+        payload: DeletePostPayload,
+        ctx: &CommandContext,
+    ) -> Result<Self, DomainError> {
+        Ok(Self {
+            id: payload.id,
+            user_id: ctx.require_user()?,
+            expected_version: payload.expected_version,
+        })
+    }
+}
+
+/// Разобранная строка импорта; `line` — её номер во входном файле.
+#[derive(Debug, Clone)]
+pub struct ImportPostRow {
+    pub line: u64,
+    pub title: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportPostsCommand {
+    pub author_id: i32,
+    pub rows: Vec<ImportPostRow>,
+}
+
+impl Command for ImportPostsCommand {}
+
+/// Забрать посты из внешнего API. Служебная: адрес задан конфигом,
+/// вызывается по расписанию, а не пользователями.
+#[derive(Debug, Clone)]
+pub struct FetchRemotePostsCommand;
+
+impl Command for FetchRemotePostsCommand {}
+
+impl NamedCommand for FetchRemotePostsCommand {
+    const NAME: &'static str = "posts.fetch_remote";
+    type Payload = IgnoredAny;
+
+    fn from_payload(
+        _payload: IgnoredAny,
+        ctx: &CommandContext,
+    ) -> Result<Self, DomainError> {
+        ctx.require_system()?;
+        Ok(Self)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GetPostQuery {
+    pub id: i32,
+}
+
+impl Query for GetPostQuery {}
+
+impl NamedQuery for GetPostQuery {
+    const NAME: &'static str = "posts.get";
+    type Payload = GetPostQuery;
+
+    fn from_payload(
+        payload: GetPostQuery,
+        _ctx: &CommandContext,
+    ) -> Result<Self, DomainError> {
+        Ok(payload)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ListPostsQuery {
+    pub request: ListRequest,
+}
+
+impl Query for ListPostsQuery {}
+
+pub type PostOutcome = Result<PostResult, DomainError>;
+
+fn validate(title: &str, text: &str) -> Result<(), DomainError> {
+    let mut violations = Vec::new();
+    if title.trim().is_empty() {
+        violations.push(FieldViolation::new("title", "must not be empty"));
+    }
+    if title.chars().count() > TITLE_MAX_LEN {
+        violations.push(FieldViolation::new(
+            "title",
+            "must be at most 200 characters",
+        ));
+    }
+    if text.trim().is_empty() {
+        violations.push(FieldViolation::new("text", "must not be empty"));
+    }
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(DomainError::Validation(violations))
+    }
+}
+
+/// Загружает пост и проверяет, что его меняет автор и версия актуальна.
+async fn load_owned(
+    repo: &PostRepository,
+    id: i32,
+    user_id: i32,
+    expected_version: Option<i32>,
+) -> Result<post::Model, DomainError> {
+    let post = repo
+        .get_by_id(id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound(format!("Post {id} not found")))?;
+    if post.author_id != user_id {
+        return Err(DomainError::Forbidden(format!(
+            "Post {id} belongs to another user"
+        )));
+    }
+    if expected_version.is_some_and(|v| v != post.version) {
+        return Err(version_conflict(id));
+    }
+    Ok(post)
+}
+
+fn version_conflict(id: i32) -> DomainError {
+    DomainError::PreconditionFailed(format!(
+        "Post {id} was modified by someone else"
+    ))
+}
+
+pub struct CreatePostHandler {
+    repo: PostRepository,
+    events: EventBus,
+}
+
+impl CreatePostHandler {
+    pub fn new(repo: PostRepository, events: EventBus) -> Self {
+        Self { repo, events }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<CreatePostCommand, PostOutcome> for CreatePostHandler {
+    async fn execute(&self, command: CreatePostCommand) -> PostOutcome {
+        validate(&command.title, &command.text)?;
+        let post = self
+            .repo
+            .create(command.author_id, command.title, command.text)
+            .await?;
+        let post = PostResult::from(post);
+        self.events.publish(DomainEvent::PostCreated(post.clone()));
+        Ok(post)
+    }
+}
+
+pub struct UpdatePostHandler {
+    repo: PostRepository,
+    events: EventBus,
+}
+
+impl UpdatePostHandler {
+    pub fn new(repo: PostRepository, events: EventBus) -> Self {
+        Self { repo, events }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<UpdatePostCommand, PostOutcome> for UpdatePostHandler {
+    async fn execute(&self, command: UpdatePostCommand) -> PostOutcome {
+        validate(&command.title, &command.text)?;
+        let UpdatePostCommand { id, user_id, expected_version, title, text } =
+            command;
+        load_owned(&self.repo, id, user_id, expected_version).await?;
+        // Версию проверяем ещё раз атомарно: между чтением и записью
+        // пост мог изменить другой запрос
+        let post = self
+            .repo
+            .update(id, expected_version, title, text)
+            .await?
+            .map(PostResult::from)
+            .ok_or_else(|| version_conflict(id))?;
+        self.events.publish(DomainEvent::PostUpdated(post.clone()));
+        Ok(post)
+    }
+}
+
+pub struct DeletePostHandler {
+    repo: PostRepository,
+    events: EventBus,
+}
+
+impl DeletePostHandler {
+    pub fn new(repo: PostRepository, events: EventBus) -> Self {
+        Self { repo, events }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<DeletePostCommand, Result<(), DomainError>>
+    for DeletePostHandler
+{
+    async fn execute(
+        &self,
+        command: DeletePostCommand,
+    ) -> Result<(), DomainError> {
+        let DeletePostCommand { id, user_id, expected_version } = command;
+        load_owned(&self.repo, id, user_id, expected_version).await?;
+        if !self.repo.delete(id, expected_version).await? {
+            return Err(version_conflict(id));
+        }
+        self.events
+            .publish(DomainEvent::PostDeleted { id, author_id: user_id });
+        Ok(())
+    }
+}
+
+pub struct ImportPostsHandler {
+    repo: PostRepository,
+    events: EventBus,
+}
+
+impl ImportPostsHandler {
+    pub fn new(repo: PostRepository, events: EventBus) -> Self {
+        Self { repo, events }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<ImportPostsCommand, Result<ImportedPosts, DomainError>>
+    for ImportPostsHandler
+{
+    /// Невалидные строки отбрасываются с причиной, остальные вставляются
+    /// одним запросом.
+    async fn execute(
+        &self,
+        command: ImportPostsCommand,
+    ) -> Result<ImportedPosts, DomainError> {
+        let mut valid = Vec::with_capacity(command.rows.len());
+        let mut rejected = Vec::new();
+        for row in command.rows {
+            match validate(&row.title, &row.text) {
+                Ok(()) => valid.push((row.title, row.text)),
+                Err(DomainError::Validation(violations)) => {
+                    rejected.push(RejectedRow { line: row.line, violations })
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        let imported = self.repo.insert_many(command.author_id, valid).await?;
+        if imported > 0 {
+            self.events.publish(DomainEvent::PostsImported {
+                author_id: command.author_id,
+                imported,
+            });
+        }
+        Ok(ImportedPosts { imported, rejected })
+    }
+}
+
+pub struct GetPostHandler {
+    repo: PostRepository,
+}
+
+impl GetPostHandler {
+    pub fn new(repo: PostRepository) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl QueryHandler<GetPostQuery, PostOutcome> for GetPostHandler {
+    async fn execute(&self, query: GetPostQuery) -> PostOutcome {
+        self.repo.get_by_id(query.id).await?.map(PostResult::from).ok_or_else(
+            || DomainError::NotFound(format!("Post {} not found", query.id)),
+        )
+    }
+}
+
+pub struct ListPostsHandler {
+    repo: PostRepository,
+}
+
+impl ListPostsHandler {
+    pub fn new(repo: PostRepository) -> Self {
+        Self { repo }
+    }
+}
+
+#[async_trait]
+impl QueryHandler<ListPostsQuery, Result<Page<PostResult>, DomainError>>
+    for ListPostsHandler
+{
+    async fn execute(
+        &self,
+        query: ListPostsQuery,
+    ) -> Result<Page<PostResult>, DomainError> {
+        let page = self.repo.list(&query.request).await?;
+        Ok(page.map(PostResult::from))
+    }
+}
+
+pub struct FetchRemotePostsHandler {
+    client: Arc<dyn PostsApi>,
+}
+
+impl FetchRemotePostsHandler {
+    pub fn new(client: Arc<dyn PostsApi>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<FetchRemotePostsCommand, Result<RemotePosts, DomainError>>
+    for FetchRemotePostsHandler
+{
+    async fn execute(
+        &self,
+        _command: FetchRemotePostsCommand,
+    ) -> Result<RemotePosts, DomainError> {
+        let posts = self.client.list_posts().await.map_err(|e| {
+            DomainError::Unavailable(format!("Remote posts unavailable: {e}"))
+        })?;
+        for post in &posts {
+            debug!("📬 Post {} {}", post.id, post.body);
+        }
+        Ok(RemotePosts { fetched: posts.len() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::clients::client::{MockPostsApi, Post};
+    use crate::infra::clients::http::HttpError;
+    use reqwest::StatusCode;
+
+    fn handler(mock: &Arc<MockPostsApi>) -> FetchRemotePostsHandler {
+        FetchRemotePostsHandler::new(mock.clone())
+    }
+
+    #[tokio::test]
+    async fn fetch_remote_posts_counts_fetched() {
+        let mock = Arc::new(MockPostsApi::default());
+        mock.list_posts.returns(|()| {
+            Ok(vec![
+                Post { id: 1, body: "first".to_string() },
+                Post { id: 2, body: "second".to_string() },
+            ])
+        });
+
+        let result =
+            handler(&mock).execute(FetchRemotePostsCommand).await.unwrap();
+        assert_eq!(result.fetched, 2);
+        assert_eq!(mock.list_posts.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fetch_remote_posts_maps_http_error_to_unavailable() {
+        let mock = Arc::new(MockPostsApi::default());
+        mock.list_posts.returns(|()| {
+            Err(HttpError::Status {
+                url: "https://example.com/posts".to_string(),
+                status: StatusCode::BAD_GATEWAY,
+            })
+        });
+
+        let err =
+            handler(&mock).execute(FetchRemotePostsCommand).await.unwrap_err();
+        match err {
+            DomainError::Unavailable(message) => {
+                assert!(message.contains("502"), "{message}")
+            }
+            other => panic!("expected Unavailable, got {other:?}"),
+        }
+    }
+}
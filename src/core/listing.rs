@@ -0,0 +1,186 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Int,
+    Text,
+}
+
+/// Описание поля ресурса, по которому разрешены сортировка и фильтры.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    pub sortable: bool,
+    pub filterable: bool,
+}
+
+/// Ресурс, который умеет отдаваться списком через `ListParams`.
+pub trait Listable {
+    const FIELDS: &'static [FieldSpec];
+    /// Уникальное поле для стабильного порядка keyset-пагинации.
+    const TIE_BREAKER: &'static str = "id";
+
+    fn field(name: &str) -> Option<&'static FieldSpec> {
+        Self::FIELDS.iter().find(|f| f.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone)]
+pub struct SortField {
+    pub field: &'static FieldSpec,
+    pub direction: SortDirection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    In,
+    Like,
+}
+
+impl FilterOp {
+    pub fn parse(op: &str) -> Option<Self> {
+        Some(match op {
+            "eq" => FilterOp::Eq,
+            "ne" => FilterOp::Ne,
+            "gt" => FilterOp::Gt,
+            "gte" => FilterOp::Gte,
+            "lt" => FilterOp::Lt,
+            "lte" => FilterOp::Lte,
+            "in" => FilterOp::In,
+            "like" => FilterOp::Like,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Int(i64),
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    pub field: &'static FieldSpec,
+    pub op: FilterOp,
+    pub values: Vec<FilterValue>,
+}
+
+/// Позиция в выдаче: значения полей сортировки у граничной записи.
+///
+/// `scope` — отпечаток сортировки и фильтров, с которыми курсор выдан:
+/// с другими он указывал бы на чужую позицию.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cursor {
+    pub values: Vec<serde_json::Value>,
+    pub backward: bool,
+    #[serde(default)]
+    pub scope: String,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(raw: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(raw).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Подходят ли значения курсора к типам полей сортировки.
+    pub fn fits(&self, sort: &[SortField]) -> bool {
+        self.values.len() == sort.len()
+            && self.values.iter().zip(sort).all(|(value, sort)| {
+                match sort.field.kind {
+                    FieldKind::Int => value.is_i64(),
+                    FieldKind::Text => value.is_string(),
+                }
+            })
+    }
+}
+
+/// Отпечаток сортировки и фильтров для `Cursor::scope`. Порядок
+/// фильтров в запросе на него не влияет.
+pub fn list_scope(sort: &[SortField], filters: &[Filter]) -> String {
+    let mut hasher = Sha256::new();
+    for sort in sort {
+        hasher.update(format!("{}:{:?};", sort.field.name, sort.direction));
+    }
+    let mut filters: Vec<String> = filters
+        .iter()
+        .map(|f| format!("{}:{:?}:{:?}", f.field.name, f.op, f.values))
+        .collect();
+    filters.sort();
+    for filter in filters {
+        hasher.update(filter);
+        hasher.update(";");
+    }
+    hex::encode(&hasher.finalize()[..8])
+}
+
+#[derive(Debug, Clone)]
+pub enum Pagination {
+    Cursor(Option<Cursor>),
+    Page(u64),
+}
+
+/// Разобранный и провалидированный запрос на список.
+#[derive(Debug, Clone)]
+pub struct ListRequest {
+    pub pagination: Pagination,
+    pub size: u64,
+    /// Всегда заканчивается tie-breaker полем.
+    pub sort: Vec<SortField>,
+    pub filters: Vec<Filter>,
+}
+
+impl ListRequest {
+    pub fn scope(&self) -> String {
+        list_scope(&self.sort, &self.filters)
+    }
+}
+
+/// Страница результатов с курсорами для перехода вперёд и назад.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    #[schema(example = 20)]
+    pub size: u64,
+    /// Номер страницы, если запрос был в режиме page/size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            size: self.size,
+            page: self.page,
+            next_cursor: self.next_cursor,
+            prev_cursor: self.prev_cursor,
+        }
+    }
+}
@@ -1,3 +1,7 @@
+pub mod errors;
+pub mod events;
 pub mod handlers;
+pub mod listing;
 pub mod models;
+pub mod rate_limit;
 pub mod results;
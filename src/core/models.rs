@@ -1,6 +1,3 @@
-use axum::Json;
-use axum::http::StatusCode;
-use axum::response::{IntoResponse, Response};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -15,10 +12,25 @@ pub struct UserResponse {
     #[schema(example = 42)]
     pub user_id: i32,
 }
-impl IntoResponse for UserResponse {
-    fn into_response(self) -> Response {
-        (StatusCode::OK, Json(self)).into_response()
-    }
-}
 #[derive(Debug)]
 pub struct AuthenticatedUser(pub AuthResult);
+
+/// Пользователь с ролью администратора из `ADMIN_USER_IDS`.
+#[derive(Debug)]
+pub struct AdminUser(pub AuthResult);
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PartnerWebhook {
+    #[schema(example = "order.paid")]
+    pub event: String,
+    #[schema(value_type = Object)]
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PostRequest {
+    #[schema(example = "Hello")]
+    pub title: String,
+    #[schema(example = "First post")]
+    pub text: String,
+}
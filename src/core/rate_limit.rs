@@ -0,0 +1,77 @@
+use crate::configs::RateQuota;
+use async_trait::async_trait;
+
+/// Параметры GCRA для квоты: интервал между запросами и допустимый burst.
+#[derive(Clone, Copy, Debug)]
+pub struct Gcra {
+    pub limit: u32,
+    pub interval_ms: i64,
+    pub tolerance_ms: i64,
+}
+
+impl From<RateQuota> for Gcra {
+    fn from(quota: RateQuota) -> Self {
+        let period_ms = quota.period.as_millis() as i64;
+        Self {
+            limit: quota.requests,
+            interval_ms: (period_ms / quota.requests as i64).max(1),
+            tolerance_ms: period_ms,
+        }
+    }
+}
+
+impl Gcra {
+    /// Новый TAT, если запрос в `now_ms` укладывается в квоту.
+    pub fn next_tat(&self, tat: Option<i64>, now_ms: i64) -> Option<i64> {
+        let next = tat.unwrap_or(now_ms).max(now_ms) + self.interval_ms;
+        (next - now_ms <= self.tolerance_ms).then_some(next)
+    }
+
+    pub fn allowed(&self, tat: i64, now_ms: i64) -> Decision {
+        let remaining =
+            (self.tolerance_ms - (tat - now_ms)).max(0) / self.interval_ms;
+        Decision {
+            allowed: true,
+            limit: self.limit,
+            remaining: remaining as u32,
+            reset_ms: tat - now_ms,
+            retry_after_ms: 0,
+        }
+    }
+
+    pub fn denied(&self, tat: i64, now_ms: i64) -> Decision {
+        let next = tat.max(now_ms) + self.interval_ms;
+        Decision {
+            allowed: false,
+            limit: self.limit,
+            remaining: 0,
+            reset_ms: tat - now_ms,
+            retry_after_ms: next - self.tolerance_ms - now_ms,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_ms: i64,
+    pub retry_after_ms: i64,
+}
+
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Атомарно проверяет квоту для ключа и, если запрос разрешён,
+    /// резервирует под него место.
+    async fn check(
+        &self,
+        key: &str,
+        gcra: Gcra,
+        now_ms: i64,
+    ) -> anyhow::Result<Decision>;
+
+    /// Удаляет ключи, чей TAT уже в прошлом: для них квота и так
+    /// полная, как для незнакомого ключа. Возвращает число удалённых.
+    async fn purge(&self, now_ms: i64) -> anyhow::Result<u64>;
+}
@@ -0,0 +1,35 @@
+use crate::infra::storage::entities::blob;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, ToSchema, Deserialize, Serialize)]
+pub struct BlobResult {
+    #[schema(example = 7)]
+    pub id: i32,
+    #[schema(example = 42)]
+    pub owner_id: i32,
+    #[schema(example = "avatar.png")]
+    pub file_name: String,
+    #[schema(example = "image/png")]
+    pub content_type: String,
+    #[schema(example = 18231)]
+    pub size: i64,
+    /// Hex sha256 содержимого
+    pub sha256: String,
+    #[schema(example = "2026-10-19T12:00:00+00:00")]
+    pub created_at: String,
+}
+
+impl From<blob::Model> for BlobResult {
+    fn from(model: blob::Model) -> Self {
+        Self {
+            id: model.id,
+            owner_id: model.owner_id,
+            file_name: model.file_name,
+            content_type: model.content_type,
+            size: model.size,
+            sha256: model.sha256,
+            created_at: model.created_at.to_rfc3339(),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::infra::storage::entities::cron_run;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CronJobResult {
+    #[schema(example = "fetch-remote-posts")]
+    pub name: String,
+    #[schema(example = "posts.fetch_remote")]
+    pub command: String,
+    #[schema(example = "0 */5 * * * *")]
+    pub schedule: String,
+    #[schema(example = "UTC")]
+    pub timezone: String,
+    pub paused: bool,
+    /// Следующее срабатывание по планировщику этого инстанса; у
+    /// задачи на паузе его нет
+    #[schema(example = "2026-10-19T12:05:00+00:00")]
+    pub next_run_at: Option<String>,
+    pub last_run: Option<CronRunResult>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CronRunResult {
+    #[schema(example = "2026-10-19T12:00:00+00:00")]
+    pub scheduled_at: String,
+    /// Инстанс, который выполнял запуск
+    #[schema(example = "api-1-3f9c2a1b")]
+    pub instance: String,
+    #[schema(example = "2026-10-19T12:00:00.412+00:00")]
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// `running`, `succeeded`, `failed`, `timed_out` или `abandoned`
+    #[schema(example = "succeeded")]
+    pub outcome: String,
+    #[schema(example = 1)]
+    pub attempts: i32,
+    pub error: Option<String>,
+}
+
+/// Ответ на внеочередной запуск.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CronTriggerResult {
+    #[schema(example = "fetch-remote-posts")]
+    pub name: String,
+    /// Время, под которым запуск записан в `cron_runs`
+    #[schema(example = "2026-10-19T12:03:17+00:00")]
+    pub scheduled_at: String,
+}
+
+impl From<cron_run::Model> for CronRunResult {
+    fn from(model: cron_run::Model) -> Self {
+        Self {
+            scheduled_at: model.scheduled_at.to_rfc3339(),
+            instance: model.instance,
+            started_at: model.started_at.to_rfc3339(),
+            finished_at: model.finished_at.map(|at| at.to_rfc3339()),
+            outcome: model.outcome,
+            attempts: model.attempts,
+            error: model.error,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use crate::game::matchmaking::QueueStatus;
+use crate::game::rooms::RoomInfo;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchmakingStatus {
+    /// Есть, пока игрок ждёт матча
+    pub queue: Option<QueueStatus>,
+    /// Есть, пока матч собран или идёт
+    pub room: Option<RoomInfo>,
+}
@@ -1 +1,5 @@
+pub mod blobs;
+pub mod cron;
 pub mod hello;
+pub mod matchmaking;
+pub mod posts;
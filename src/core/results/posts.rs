@@ -0,0 +1,80 @@
+use crate::core::errors::FieldViolation;
+use crate::core::listing::{FieldKind, FieldSpec, Listable};
+use crate::infra::storage::entities::post;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, ToSchema, Deserialize, Serialize)]
+pub struct PostResult {
+    #[schema(example = 13)]
+    pub id: i32,
+    #[schema(example = "Hello")]
+    pub title: String,
+    #[schema(example = "First post")]
+    pub text: String,
+    #[schema(example = 42)]
+    pub author_id: i32,
+    /// Растёт на 1 при каждом изменении, из него строится ETag
+    #[schema(example = 1)]
+    pub version: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RejectedRow {
+    pub line: u64,
+    pub violations: Vec<FieldViolation>,
+}
+
+/// Итог импорта одной пачки строк.
+#[derive(Debug, Clone)]
+pub struct ImportedPosts {
+    pub imported: u64,
+    pub rejected: Vec<RejectedRow>,
+}
+
+/// Итог `posts.fetch_remote`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RemotePosts {
+    pub fetched: usize,
+}
+
+impl From<post::Model> for PostResult {
+    fn from(model: post::Model) -> Self {
+        Self {
+            id: model.id,
+            title: model.title,
+            text: model.text,
+            author_id: model.author_id,
+            version: model.version,
+        }
+    }
+}
+
+impl Listable for PostResult {
+    const FIELDS: &'static [FieldSpec] = &[
+        FieldSpec {
+            name: "id",
+            kind: FieldKind::Int,
+            sortable: true,
+            filterable: true,
+        },
+        FieldSpec {
+            name: "title",
+            kind: FieldKind::Text,
+            sortable: true,
+            filterable: true,
+        },
+        FieldSpec {
+            name: "text",
+            kind: FieldKind::Text,
+            sortable: false,
+            filterable: true,
+        },
+        FieldSpec {
+            name: "author_id",
+            kind: FieldKind::Int,
+            sortable: true,
+            filterable: true,
+        },
+    ];
+}
@@ -0,0 +1,424 @@
+pub mod runner;
+pub mod schedule;
+pub mod stats;
+
+use crate::configs::{Config, CronJobConfig};
+use crate::core::errors::{DomainError, FieldViolation};
+use crate::core::results::cron::{CronJobResult, CronTriggerResult};
+use crate::cron::runner::{CronJob, Runner};
+use crate::cron::schedule::Schedule;
+use crate::cron::stats::JobStats;
+use crate::infra::storage::cron_jobs::{CronJobState, CronJobStateRepository};
+use crate::infra::storage::cron_leases::CronLeaseRepository;
+use crate::infra::storage::cron_runs::CronRunRepository;
+use crate::mediator::mediator::Mediator;
+use chrono::Utc;
+use chrono_tz::Tz;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::spawn;
+use tokio::sync::Mutex;
+use tokio::time::{MissedTickBehavior, interval};
+use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Задачи по расписанию из конфига.
+///
+/// Каждая задача в момент срабатывания вызывает команду по имени через
+/// `Mediator::dispatch`, без пользователя в контексте. Новая работа по
+/// расписанию — это обработчик с `register_named` и запись в
+/// `CRON_JOBS_FILE`.
+///
+/// Планировщик работает на каждой реплике, но срабатывание выполняет
+/// одна: перед запуском инстанс берёт аренду задачи на это назначенное
+/// время в `cron_lease`. Пока запуск идёт, аренда продлевается; если
+/// инстанс упал, она истекает через TTL, и следующее срабатывание
+/// возьмёт кто-то другой. Каждый запуск пишется в `cron_runs`.
+///
+/// Пауза и смена расписания через API пишутся в `cron_job_state` и
+/// сразу применяются на этом инстансе; остальные подхватывают их не
+/// позже чем через `CRON_SYNC_SECS`, а после рестарта они действуют
+/// поверх конфига.
+pub struct ProjectCron {
+    runner: Arc<Runner>,
+    scheduler: JobScheduler,
+    states: CronJobStateRepository,
+    jobs: Mutex<BTreeMap<String, Entry>>,
+    configs: Vec<CronJobConfig>,
+    lock_ttl_secs: u64,
+    sync_every: Duration,
+}
+
+#[derive(Clone)]
+struct Entry {
+    /// Задача как в `CRON_JOBS_FILE`
+    base: CronJobConfig,
+    job: Arc<CronJob>,
+    /// Id в планировщике, пока задача не на паузе
+    scheduled: Option<Uuid>,
+    paused: bool,
+}
+
+impl ProjectCron {
+    pub async fn new(
+        cfg: &Config,
+        mediator: Arc<Mediator>,
+        leases: CronLeaseRepository,
+        runs: CronRunRepository,
+        states: CronJobStateRepository,
+        shutdown: CancellationToken,
+    ) -> Result<Self, JobSchedulerError> {
+        Ok(Self {
+            runner: Arc::new(Runner {
+                mediator,
+                leases,
+                runs,
+                instance_id: cfg.cron_instance_id.clone(),
+                shutdown,
+            }),
+            scheduler: JobScheduler::new().await?,
+            states,
+            jobs: Mutex::new(BTreeMap::new()),
+            configs: cfg.cron_jobs.clone(),
+            lock_ttl_secs: cfg.cron_lock_ttl_secs,
+            sync_every: Duration::from_secs(cfg.cron_sync_secs.max(1)),
+        })
+    }
+
+    /// Планирует задачи и работает до отмены `shutdown`.
+    pub async fn run(&self) -> Result<(), JobSchedulerError> {
+        match self.runner.runs.abandon_stale().await {
+            Ok(0) => {}
+            Ok(n) => warn!("⚠️ {n} cron runs were interrupted by a stop"),
+            Err(e) => warn!("⚠️ Cannot check interrupted cron runs: {e}"),
+        }
+        let states = self.states.all().await.unwrap_or_else(|e| {
+            warn!("⚠️ Cannot read cron job state, using the config: {e}");
+            Vec::new()
+        });
+
+        let started_at = Utc::now();
+        let mut jobs = self.jobs.lock().await;
+        for config in self.configs.iter().cloned() {
+            if !config.enabled {
+                info!("⏸ Cron job {} is disabled", config.name);
+                continue;
+            }
+            // Опечатка в имени команды видна сразу, а не в момент запуска
+            if !self.runner.mediator.is_named(&config.command).await {
+                error!(
+                    "❌ Cron job {} skipped: unknown command {}",
+                    config.name, config.command
+                );
+                continue;
+            }
+            let job = match self.build(config.clone(), Default::default()) {
+                Ok(job) => Arc::new(job),
+                Err(e) => {
+                    error!(
+                        "❌ Cron job {} skipped: invalid schedule {:?}: {e}",
+                        config.name, config.schedule
+                    );
+                    continue;
+                }
+            };
+            let mut entry =
+                Entry { base: config, job, scheduled: None, paused: false };
+            let state = states.iter().find(|s| s.job_name == entry.base.name);
+            self.apply(&mut entry, state).await?;
+            if !entry.paused {
+                info!(
+                    "⏰ Cron job {} scheduled: {:?} ({}) -> {}",
+                    entry.job.config.name,
+                    entry.job.config.schedule,
+                    entry.job.config.timezone,
+                    entry.job.config.command
+                );
+                let (runner, job) = (self.runner.clone(), entry.job.clone());
+                spawn(async move { runner.catch_up(&job, started_at).await });
+            }
+            jobs.insert(entry.base.name.clone(), entry);
+        }
+        let count = jobs.len();
+        drop(jobs);
+
+        self.scheduler.start().await?;
+        info!(
+            "✅ Cron scheduler started with {count} jobs as {}",
+            self.runner.instance_id
+        );
+
+        let mut sync = interval(self.sync_every);
+        sync.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        sync.tick().await;
+        loop {
+            tokio::select! {
+                _ = self.runner.shutdown.cancelled() => break,
+                _ = sync.tick() => self.sync().await,
+            }
+        }
+
+        info!("🛑 Cron scheduler shutting down");
+        self.scheduler.clone().shutdown().await?;
+        for entry in self.jobs.lock().await.values() {
+            info!("📊 Cron job {}: {}", entry.base.name, entry.job.stats);
+        }
+
+        Ok(())
+    }
+
+    pub async fn jobs(&self) -> Result<Vec<CronJobResult>, DomainError> {
+        // Копия, чтобы не держать задачи заблокированными, пока идут
+        // запросы к истории запусков
+        let entries: Vec<Entry> =
+            self.jobs.lock().await.values().cloned().collect();
+        let mut result = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            result.push(self.describe(entry).await?);
+        }
+        Ok(result)
+    }
+
+    pub async fn pause(
+        &self,
+        name: &str,
+    ) -> Result<CronJobResult, DomainError> {
+        self.set_paused(name, true).await
+    }
+
+    pub async fn resume(
+        &self,
+        name: &str,
+    ) -> Result<CronJobResult, DomainError> {
+        self.set_paused(name, false).await
+    }
+
+    /// Запускает задачу сейчас, вне расписания. Пауза не мешает.
+    pub async fn trigger(
+        &self,
+        name: &str,
+    ) -> Result<CronTriggerResult, DomainError> {
+        let job = self
+            .jobs
+            .lock()
+            .await
+            .get(name)
+            .map(|entry| entry.job.clone())
+            .ok_or_else(|| not_found(name))?;
+        let scheduled_at = self.runner.trigger(job).await?;
+        Ok(CronTriggerResult {
+            name: name.to_string(),
+            scheduled_at: scheduled_at.to_rfc3339(),
+        })
+    }
+
+    /// Меняет расписание без рестарта. Без `timezone` зона остаётся
+    /// прежней. Идущий запуск доработает по старому расписанию.
+    pub async fn reschedule(
+        &self,
+        name: &str,
+        schedule: String,
+        timezone: Option<String>,
+    ) -> Result<CronJobResult, DomainError> {
+        let mut jobs = self.jobs.lock().await;
+        let entry = jobs.get_mut(name).ok_or_else(|| not_found(name))?;
+        let timezone = match timezone {
+            Some(tz) => tz.parse::<Tz>().map_err(|_| {
+                invalid("timezone", &format!("unknown time zone {tz:?}"))
+            })?,
+            None => entry.job.config.timezone,
+        };
+        Schedule::parse(&schedule, timezone).map_err(|_| {
+            invalid(
+                "schedule",
+                "must be a cron expression with seconds or an English schedule",
+            )
+        })?;
+
+        self.states.set_schedule(name, &schedule, timezone.name()).await?;
+        let state = CronJobState {
+            job_name: name.to_string(),
+            paused: entry.paused,
+            schedule: Some(schedule),
+            timezone: Some(timezone.name().to_string()),
+        };
+        self.apply(entry, Some(&state)).await.map_err(scheduler_error)?;
+        let entry = entry.clone();
+        drop(jobs);
+        self.describe(&entry).await
+    }
+
+    async fn set_paused(
+        &self,
+        name: &str,
+        paused: bool,
+    ) -> Result<CronJobResult, DomainError> {
+        let mut jobs = self.jobs.lock().await;
+        let entry = jobs.get_mut(name).ok_or_else(|| not_found(name))?;
+        self.states.set_paused(name, paused).await?;
+        let state = CronJobState {
+            job_name: name.to_string(),
+            paused,
+            schedule: Some(entry.job.config.schedule.clone()),
+            timezone: Some(entry.job.config.timezone.name().to_string()),
+        };
+        self.apply(entry, Some(&state)).await.map_err(scheduler_error)?;
+        let entry = entry.clone();
+        drop(jobs);
+        self.describe(&entry).await
+    }
+
+    /// Подтягивает изменения, сделанные через API на других инстансах.
+    async fn sync(&self) {
+        let states = match self.states.all().await {
+            Ok(states) => states,
+            Err(e) => {
+                warn!("⚠️ Cannot read cron job state: {e}");
+                return;
+            }
+        };
+        let mut jobs = self.jobs.lock().await;
+        for entry in jobs.values_mut() {
+            let state = states.iter().find(|s| s.job_name == entry.base.name);
+            if let Err(e) = self.apply(entry, state).await {
+                error!(
+                    "❌ Cron job {}: cannot apply state: {e}",
+                    entry.base.name
+                );
+            }
+        }
+    }
+
+    /// Приводит задачу в планировщике к `state`; без строки — к конфигу.
+    async fn apply(
+        &self,
+        entry: &mut Entry,
+        state: Option<&CronJobState>,
+    ) -> Result<(), JobSchedulerError> {
+        let name = entry.base.name.clone();
+        let mut config = entry.base.clone();
+        if let Some(state) = state {
+            if let Some(schedule) = &state.schedule {
+                config.schedule = schedule.clone();
+            }
+            if let Some(tz) = &state.timezone {
+                match tz.parse() {
+                    Ok(tz) => config.timezone = tz,
+                    Err(_) => warn!(
+                        "⚠️ Cron job {name}: unknown stored time zone {tz:?}"
+                    ),
+                }
+            }
+        }
+        let paused = state.is_some_and(|s| s.paused);
+
+        let current = &entry.job.config;
+        if config.schedule != current.schedule
+            || config.timezone != current.timezone
+        {
+            match self.build(config, entry.job.stats.clone()) {
+                Ok(job) => {
+                    if let Some(id) = entry.scheduled.take() {
+                        self.scheduler.remove(&id).await?;
+                    }
+                    entry.job = Arc::new(job);
+                    info!(
+                        "🔄 Cron job {name} rescheduled: {:?} ({})",
+                        entry.job.config.schedule, entry.job.config.timezone
+                    );
+                }
+                Err(e) => warn!(
+                    "⚠️ Cron job {name}: keeping the current schedule, stored one is invalid: {e}"
+                ),
+            }
+        }
+
+        if paused {
+            if let Some(id) = entry.scheduled.take() {
+                self.scheduler.remove(&id).await?;
+            }
+            if !entry.paused {
+                info!("⏸ Cron job {name} is paused");
+            }
+        } else if entry.scheduled.is_none() {
+            let job = Self::job(entry.job.clone(), self.runner.clone())?;
+            entry.scheduled = Some(self.scheduler.add(job).await?);
+            if entry.paused {
+                info!("▶️ Cron job {name} resumed");
+            }
+        }
+        entry.paused = paused;
+        Ok(())
+    }
+
+    fn build(
+        &self,
+        config: CronJobConfig,
+        stats: Arc<JobStats>,
+    ) -> Result<CronJob, JobSchedulerError> {
+        Ok(CronJob {
+            schedule: Schedule::parse(&config.schedule, config.timezone)?,
+            lock_ttl: Duration::from_secs(
+                config.lock_ttl_secs.unwrap_or(self.lock_ttl_secs).max(1),
+            ),
+            config,
+            stats,
+        })
+    }
+
+    async fn describe(
+        &self,
+        entry: &Entry,
+    ) -> Result<CronJobResult, DomainError> {
+        let next_run_at = match entry.scheduled {
+            Some(id) => self
+                .scheduler
+                .clone()
+                .next_tick_for_job(id)
+                .await
+                .map_err(scheduler_error)?,
+            None => None,
+        };
+        let last_run = self.runner.runs.last(&entry.base.name).await?;
+        let config = &entry.job.config;
+        Ok(CronJobResult {
+            name: config.name.clone(),
+            command: config.command.clone(),
+            schedule: config.schedule.clone(),
+            timezone: config.timezone.name().to_string(),
+            paused: entry.paused,
+            next_run_at: next_run_at.map(|at| at.to_rfc3339()),
+            last_run: last_run.map(Into::into),
+        })
+    }
+
+    fn job(
+        job: Arc<CronJob>,
+        runner: Arc<Runner>,
+    ) -> Result<Job, JobSchedulerError> {
+        Job::new_async_tz(
+            job.config.schedule.clone(),
+            job.config.timezone,
+            move |_uuid, _l| {
+                let job = job.clone();
+                let runner = runner.clone();
+                Box::pin(async move { runner.fire(&job).await })
+            },
+        )
+    }
+}
+
+fn not_found(name: &str) -> DomainError {
+    DomainError::NotFound(format!("Cron job {name} not found"))
+}
+
+fn invalid(field: &str, message: &str) -> DomainError {
+    DomainError::Validation(vec![FieldViolation::new(field, message)])
+}
+
+fn scheduler_error(e: JobSchedulerError) -> DomainError {
+    DomainError::Unavailable(format!("Cron scheduler error: {e}"))
+}
@@ -0,0 +1,407 @@
+use crate::configs::{CronCatchUp, CronJobConfig, CronOverlap};
+use crate::core::errors::DomainError;
+use crate::core::handlers::base::CommandContext;
+use crate::cron::schedule::Schedule;
+use crate::cron::stats::{JobStats, Skip};
+use crate::infra::storage::cron_leases::{Claim, CronLeaseRepository};
+use crate::infra::storage::cron_runs::{CronRunRepository, RunOutcome};
+use crate::mediator::errors::DispatchError;
+use crate::mediator::mediator::Mediator;
+use chrono::{DateTime, SubsecRound, Utc};
+use serde_json::Value;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::spawn;
+use tokio::time::{MissedTickBehavior, interval, sleep, timeout};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+/// Как часто срабатывание с `overlap: queue` проверяет, освободилась
+/// ли аренда.
+const QUEUE_POLL: Duration = Duration::from_secs(1);
+/// Самая долгая пауза между повторами.
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+/// Сколько пропущенных срабатываний догонять при `catch_up: all`.
+const MAX_CATCH_UP: usize = 100;
+
+#[derive(Debug, Error)]
+enum AttemptError {
+    #[error("Timed out after {0:?}")]
+    TimedOut(Duration),
+
+    #[error(transparent)]
+    Dispatch(#[from] DispatchError),
+}
+
+impl AttemptError {
+    /// Повторять имеет смысл только то, что может пройти само: таймаут,
+    /// недоступная зависимость, сбой базы. Неверный payload или
+    /// запрет повтор не исправит.
+    fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            AttemptError::TimedOut(_)
+                | AttemptError::Dispatch(DispatchError::Domain(
+                    DomainError::Unavailable(_)
+                        | DomainError::Storage(_)
+                        | DomainError::Blob(_)
+                ))
+        )
+    }
+
+    fn outcome(&self) -> RunOutcome {
+        match self {
+            AttemptError::TimedOut(_) => RunOutcome::TimedOut,
+            AttemptError::Dispatch(_) => RunOutcome::Failed,
+        }
+    }
+}
+
+/// Задача с разобранным расписанием. При смене расписания создаётся
+/// заново, счётчики переходят к новой.
+pub struct CronJob {
+    pub config: CronJobConfig,
+    pub schedule: Schedule,
+    pub lock_ttl: Duration,
+    pub stats: Arc<JobStats>,
+}
+
+/// Выполнение срабатываний, общее для всех задач инстанса.
+pub struct Runner {
+    pub mediator: Arc<Mediator>,
+    pub leases: CronLeaseRepository,
+    pub runs: CronRunRepository,
+    pub instance_id: String,
+    pub shutdown: CancellationToken,
+}
+
+impl Runner {
+    /// Срабатывание от планировщика.
+    pub async fn fire(&self, job: &CronJob) {
+        match job.schedule.occurrence(Utc::now()) {
+            Some(scheduled_at) => self.execute(job, scheduled_at).await,
+            None => warn!(
+                "⚠️ Cron job {} fired outside of its schedule",
+                job.config.name
+            ),
+        }
+    }
+
+    /// Догоняет срабатывания, пропущенные с последнего запуска в
+    /// `cron_runs` до `before`. Задача, которая ещё ни разу не
+    /// запускалась, ничего не пропускала.
+    pub async fn catch_up(&self, job: &CronJob, before: DateTime<Utc>) {
+        let name = &job.config.name;
+        let Some(limit) = catch_up_limit(job.config.catch_up) else {
+            return;
+        };
+        let last = match self.runs.last_scheduled(name).await {
+            Ok(Some(last)) => last,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("⚠️ Cron job {name}: cannot read run history: {e}");
+                return;
+            }
+        };
+        let missed = job.schedule.between(last, before, limit);
+        if missed.is_empty() {
+            return;
+        }
+        info!(
+            "⏪ Cron job {name}: catching up {} missed runs since {last}",
+            missed.len()
+        );
+        for scheduled_at in missed {
+            if self.shutdown.is_cancelled() {
+                break;
+            }
+            self.execute(job, scheduled_at).await;
+        }
+    }
+
+    /// Внеочередной запуск на текущую секунду. Аренда берётся сразу и
+    /// без очереди: если прошлый запуск ещё идёт, запуска не будет.
+    /// Сам запуск идёт в фоне и пишется в `cron_runs`, как обычный.
+    pub async fn trigger(
+        self: &Arc<Self>,
+        job: Arc<CronJob>,
+    ) -> Result<DateTime<Utc>, DomainError> {
+        let name = &job.config.name;
+        let scheduled_at = Utc::now().trunc_subsecs(0);
+        let claim = self
+            .leases
+            .acquire(name, &self.instance_id, scheduled_at, job.lock_ttl)
+            .await?;
+        match claim {
+            Claim::Acquired => {}
+            Claim::Taken { holder } => {
+                return Err(DomainError::Conflict(format!(
+                    "Cron job {name} has just started on {holder}"
+                )));
+            }
+            Claim::Busy { holder, scheduled_at: running } => {
+                return Err(DomainError::Conflict(format!(
+                    "Cron job {name} is still running the {running} run on {holder}"
+                )));
+            }
+        }
+        info!("👆 Cron job {name} triggered manually");
+        let runner = self.clone();
+        spawn(async move { runner.run(&job, scheduled_at).await });
+        Ok(scheduled_at)
+    }
+
+    async fn execute(&self, job: &CronJob, scheduled_at: DateTime<Utc>) {
+        if self.claim(job, scheduled_at).await {
+            self.run(job, scheduled_at).await;
+        }
+    }
+
+    /// Запуск срабатывания, аренда которого уже взята.
+    async fn run(&self, job: &CronJob, scheduled_at: DateTime<Utc>) {
+        let name = &job.config.name;
+        job.stats.record_run();
+        info!("⏰ Cron job {name} started for {scheduled_at}");
+        let run_id = match self
+            .runs
+            .start(name, scheduled_at, &self.instance_id)
+            .await
+        {
+            Ok(id) => Some(id),
+            Err(e) => {
+                warn!("⚠️ Cron job {name}: cannot record the run: {e}");
+                None
+            }
+        };
+
+        let (outcome, attempts, error) =
+            self.holding_lease(job, scheduled_at, self.attempts(job)).await;
+
+        if let Some(id) = run_id
+            && let Err(e) = self.runs.finish(id, outcome, attempts, error).await
+        {
+            warn!("⚠️ Cron job {name}: cannot record the outcome: {e}");
+        }
+        if let Err(e) =
+            self.leases.release(name, &self.instance_id, scheduled_at).await
+        {
+            // Аренда всё равно истечёт по TTL
+            warn!("⚠️ Cron job {name}: cannot release the lease: {e}");
+        }
+    }
+
+    /// Берёт аренду срабатывания. С `overlap: queue` ждёт, пока прошлый
+    /// запуск её отпустит.
+    async fn claim(&self, job: &CronJob, scheduled_at: DateTime<Utc>) -> bool {
+        let name = &job.config.name;
+        let mut queued = false;
+        loop {
+            let claim = self
+                .leases
+                .acquire(name, &self.instance_id, scheduled_at, job.lock_ttl)
+                .await;
+            match claim {
+                Ok(Claim::Acquired) => return true,
+                Ok(Claim::Taken { holder }) if queued => {
+                    job.stats.record_skip(Skip::Busy);
+                    info!(
+                        "⏭ Cron job {name} at {scheduled_at} left the queue: a later run started on {holder}"
+                    );
+                    return false;
+                }
+                Ok(Claim::Taken { holder }) => {
+                    job.stats.record_skip(Skip::Elsewhere);
+                    debug!(
+                        "⏭ Cron job {name} at {scheduled_at} runs on {holder}"
+                    );
+                    return false;
+                }
+                Ok(Claim::Busy { holder, scheduled_at: running }) => {
+                    if job.config.overlap == CronOverlap::Skip {
+                        job.stats.record_skip(Skip::Busy);
+                        warn!(
+                            "⏭ Cron job {name} at {scheduled_at} skipped: run of {running} on {holder} still holds the lease"
+                        );
+                        return false;
+                    }
+                    if !queued {
+                        info!(
+                            "⏳ Cron job {name} at {scheduled_at} waits for run of {running} on {holder}"
+                        );
+                        queued = true;
+                    }
+                }
+                Err(e) => {
+                    job.stats.record_skip(Skip::LockError);
+                    error!(
+                        "❌ Cron job {name} at {scheduled_at} skipped: cannot take the lease: {e}"
+                    );
+                    return false;
+                }
+            }
+            tokio::select! {
+                _ = self.shutdown.cancelled() => return false,
+                _ = sleep(QUEUE_POLL) => {}
+            }
+        }
+    }
+
+    /// Попытки с повторами. Возвращает итог, число попыток и текст
+    /// последней ошибки.
+    async fn attempts(
+        &self,
+        job: &CronJob,
+    ) -> (RunOutcome, u32, Option<String>) {
+        let name = &job.config.name;
+        let max_attempts = job.config.retries.saturating_add(1);
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let error = match self.attempt(job).await {
+                Ok(result) => {
+                    info!("✅ Cron job {name} finished: {result}");
+                    return (RunOutcome::Succeeded, attempt, None);
+                }
+                Err(e) => e,
+            };
+            if attempt >= max_attempts || !error.is_transient() {
+                error!(
+                    "❌ Cron job {name} failed after {attempt} attempts: {error}"
+                );
+                return (error.outcome(), attempt, Some(error.to_string()));
+            }
+            let delay = backoff(job.config.retry_backoff_secs, attempt);
+            warn!(
+                "🔁 Cron job {name} attempt {attempt} failed: {error}; retrying in {delay:?}"
+            );
+            tokio::select! {
+                _ = self.shutdown.cancelled() => {
+                    return (error.outcome(), attempt, Some(error.to_string()));
+                }
+                _ = sleep(delay) => {}
+            }
+        }
+    }
+
+    /// Одна попытка. По таймауту обработчик прерывается на ближайшем
+    /// `await`.
+    async fn attempt(&self, job: &CronJob) -> Result<Value, AttemptError> {
+        let run = self.mediator.dispatch(
+            &job.config.command,
+            job.config.payload.clone(),
+            CommandContext::default(),
+        );
+        match job.config.timeout_secs.map(Duration::from_secs) {
+            Some(limit) => timeout(limit, run)
+                .await
+                .map_err(|_| AttemptError::TimedOut(limit))?
+                .map_err(AttemptError::from),
+            None => run.await.map_err(AttemptError::from),
+        }
+    }
+
+    /// Выполняет `work` и, пока она идёт, продлевает аренду за треть
+    /// TTL до истечения.
+    async fn holding_lease<T>(
+        &self,
+        job: &CronJob,
+        scheduled_at: DateTime<Utc>,
+        work: impl Future<Output = T>,
+    ) -> T {
+        let name = &job.config.name;
+        tokio::pin!(work);
+        let mut renew = interval(job.lock_ttl / 3);
+        renew.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        renew.tick().await;
+        loop {
+            tokio::select! {
+                result = &mut work => return result,
+                _ = renew.tick() => {
+                    match self
+                        .leases
+                        .renew(name, &self.instance_id, scheduled_at, job.lock_ttl)
+                        .await
+                    {
+                        Ok(true) => {}
+                        Ok(false) => warn!(
+                            "⚠️ Cron job {name}: lease for {scheduled_at} was lost, another instance may run the next occurrence"
+                        ),
+                        Err(e) => warn!("⚠️ Cron job {name}: cannot renew the lease: {e}"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Сколько пропущенных срабатываний догонять; `None` — ни одного.
+fn catch_up_limit(catch_up: CronCatchUp) -> Option<usize> {
+    match catch_up {
+        CronCatchUp::Skip => None,
+        CronCatchUp::Latest => Some(1),
+        CronCatchUp::All => Some(MAX_CATCH_UP),
+    }
+}
+
+/// `base`, `2 * base`, `4 * base`… но не больше `MAX_BACKOFF`.
+fn backoff(base_secs: u64, attempt: u32) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(16);
+    Duration::from_secs(base_secs.max(1))
+        .saturating_mul(factor)
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::DbErr;
+
+    #[test]
+    fn backoff_doubles_up_to_cap() {
+        assert_eq!(backoff(5, 1), Duration::from_secs(5));
+        assert_eq!(backoff(5, 2), Duration::from_secs(10));
+        assert_eq!(backoff(5, 4), Duration::from_secs(40));
+        assert_eq!(backoff(5, 10), MAX_BACKOFF);
+        assert_eq!(backoff(5, u32::MAX), MAX_BACKOFF);
+        assert_eq!(backoff(u64::MAX, 3), MAX_BACKOFF);
+        // Нулевая база не превращает повторы в цикл без пауз
+        assert_eq!(backoff(0, 1), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        let domain = |e| AttemptError::Dispatch(DispatchError::Domain(e));
+        assert!(AttemptError::TimedOut(Duration::from_secs(1)).is_transient());
+        assert!(domain(DomainError::Unavailable("down".into())).is_transient());
+        assert!(
+            domain(DomainError::Storage(DbErr::Custom("db".into())))
+                .is_transient()
+        );
+
+        assert!(!domain(DomainError::NotFound("gone".into())).is_transient());
+        assert!(!domain(DomainError::Forbidden("no".into())).is_transient());
+        assert!(!domain(DomainError::Validation(Vec::new())).is_transient());
+        assert!(
+            !AttemptError::Dispatch(DispatchError::UnknownCommand(
+                "posts.nope".into()
+            ))
+            .is_transient()
+        );
+        assert!(
+            !AttemptError::Dispatch(DispatchError::InvalidPayload {
+                command: "posts.create",
+                message: "bad".into(),
+            })
+            .is_transient()
+        );
+    }
+
+    #[test]
+    fn catch_up_limits() {
+        assert_eq!(catch_up_limit(CronCatchUp::Skip), None);
+        assert_eq!(catch_up_limit(CronCatchUp::Latest), Some(1));
+        assert_eq!(catch_up_limit(CronCatchUp::All), Some(MAX_CATCH_UP));
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, SubsecRound, TimeDelta, Utc};
+use chrono_tz::Tz;
+use croner::Cron;
+use croner::parser::{CronParser, Seconds};
+use tokio_cron_scheduler::{Job, JobSchedulerError};
+
+/// Расписание задачи в той же записи, что понимает планировщик: cron
+/// с секундами или английский текст.
+///
+/// Планировщик вызывает задачу чуть позже назначенного времени, и на
+/// разных инстансах по-разному. Само назначенное время одинаково
+/// везде, по нему инстансы договариваются, кто выполняет срабатывание.
+#[derive(Clone)]
+pub struct Schedule {
+    cron: Cron,
+    timezone: Tz,
+}
+
+impl Schedule {
+    pub fn parse(
+        schedule: &str,
+        timezone: Tz,
+    ) -> Result<Self, JobSchedulerError> {
+        let expression = Job::schedule_to_cron(schedule)?;
+        let cron = CronParser::builder()
+            .seconds(Seconds::Required)
+            .dom_and_dow(true)
+            .build()
+            .parse(&expression)
+            .map_err(|_| JobSchedulerError::ParseSchedule)?;
+        Ok(Self { cron, timezone })
+    }
+
+    /// Последнее назначенное время не позже `now`. Расписание точное
+    /// до секунды, доли отбрасываются.
+    pub fn occurrence(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let now = now.trunc_subsecs(0).with_timezone(&self.timezone);
+        self.cron
+            .find_previous_occurrence(&now, true)
+            .ok()
+            .map(|at| at.with_timezone(&Utc))
+    }
+
+    /// Назначенные времена строго между `after` и `before`, не больше
+    /// `limit` самых поздних, по возрастанию.
+    pub fn between(
+        &self,
+        after: DateTime<Utc>,
+        before: DateTime<Utc>,
+        limit: usize,
+    ) -> Vec<DateTime<Utc>> {
+        // Срабатывание в ту же секунду, что `before`, тоже пропущено
+        let before = before.trunc_subsecs(0) + TimeDelta::seconds(1);
+        let mut missed: Vec<DateTime<Utc>> = self
+            .cron
+            .iter_before(before.with_timezone(&self.timezone))
+            .map(|at| at.with_timezone(&Utc))
+            .take_while(|at| *at > after)
+            .take(limit)
+            .collect();
+        missed.reverse();
+        missed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 19, h, m, s).unwrap()
+    }
+
+    fn every_minute() -> Schedule {
+        Schedule::parse("0 * * * * *", Tz::UTC).unwrap()
+    }
+
+    #[test]
+    fn between_lists_missed_runs_in_order() {
+        let missed = every_minute().between(at(10, 0, 0), at(10, 3, 30), 100);
+        assert_eq!(missed, [at(10, 1, 0), at(10, 2, 0), at(10, 3, 0)]);
+    }
+
+    #[test]
+    fn between_with_limit_keeps_latest() {
+        let schedule = every_minute();
+        // catch_up: latest
+        assert_eq!(
+            schedule.between(at(10, 0, 0), at(10, 3, 30), 1),
+            [at(10, 3, 0)]
+        );
+        assert_eq!(
+            schedule.between(at(10, 0, 0), at(10, 3, 30), 2),
+            [at(10, 2, 0), at(10, 3, 0)]
+        );
+        assert!(schedule.between(at(10, 0, 0), at(10, 3, 30), 0).is_empty());
+    }
+
+    #[test]
+    fn between_excludes_after_and_includes_before_second() {
+        let schedule = every_minute();
+        // Срабатывание в `after` уже было
+        assert!(schedule.between(at(10, 1, 0), at(10, 1, 59), 10).is_empty());
+        // В ту же секунду, что `before`, — пропущено
+        let before = at(10, 2, 0) + TimeDelta::milliseconds(400);
+        assert_eq!(schedule.between(at(10, 1, 0), before, 10), [at(10, 2, 0)]);
+    }
+
+    #[test]
+    fn occurrence_uses_timezone() {
+        let schedule =
+            Schedule::parse("0 0 9 * * *", "Europe/Moscow".parse().unwrap())
+                .unwrap();
+        assert_eq!(schedule.occurrence(at(7, 0, 0)), Some(at(6, 0, 0)));
+    }
+}
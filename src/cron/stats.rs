@@ -0,0 +1,50 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Почему срабатывание не выполнено на этом инстансе.
+#[derive(Debug, Clone, Copy)]
+pub enum Skip {
+    /// Срабатывание выполнил другой инстанс
+    Elsewhere,
+    /// Предыдущий запуск ещё держит аренду
+    Busy,
+    /// Аренду не удалось проверить; без неё задача не запускается
+    LockError,
+}
+
+/// Счётчики задачи с момента запуска сервиса.
+#[derive(Debug, Default)]
+pub struct JobStats {
+    runs: AtomicU64,
+    elsewhere: AtomicU64,
+    busy: AtomicU64,
+    lock_errors: AtomicU64,
+}
+
+impl JobStats {
+    pub fn record_run(&self) {
+        self.runs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_skip(&self, skip: Skip) {
+        let counter = match skip {
+            Skip::Elsewhere => &self.elsewhere,
+            Skip::Busy => &self.busy,
+            Skip::LockError => &self.lock_errors,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Display for JobStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} runs, {} ran elsewhere, {} skipped as busy, {} lock errors",
+            self.runs.load(Ordering::Relaxed),
+            self.elsewhere.load(Ordering::Relaxed),
+            self.busy.load(Ordering::Relaxed),
+            self.lock_errors.load(Ordering::Relaxed)
+        )
+    }
+}
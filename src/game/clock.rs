@@ -0,0 +1,25 @@
+use std::thread;
+use std::time::Instant;
+
+/// Источник времени игрового цикла.
+///
+/// Время влияет только на расписание тиков, но не на симуляцию: системы
+/// его не видят, поэтому реплей не зависит от скорости машины.
+pub trait Clock: Send {
+    fn now(&self) -> Instant;
+
+    fn sleep_until(&self, deadline: Instant);
+}
+
+/// Обычные часы.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) {
+        thread::sleep(deadline.saturating_duration_since(Instant::now()));
+    }
+}
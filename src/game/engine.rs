@@ -0,0 +1,240 @@
+use crate::configs::{CatchUpPolicy, Config};
+use crate::game::clock::Clock;
+use crate::game::input::PlayerInput;
+use crate::game::metrics::TickStats;
+use crate::game::replay::{ReplayHeader, ReplayRecorder, TickRecord};
+use crate::game::rng::tick_seed;
+use crate::game::simulation::Simulation;
+use crate::game::snapshot::Snapshot;
+use crate::game::world::World;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// Как часто писать сводку по тикам.
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+/// Не чаще одного предупреждения о медленном тике за этот интервал.
+const OVERRUN_WARN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Симуляция с фиксированным шагом.
+///
+/// Тики идут строго через `1 / tick_rate` по расписанию, а не «после
+/// предыдущего»: если тик затянулся, следующий начнётся сразу. Если
+/// цикл отстал на несколько тиков, `CatchUpPolicy` решает, догонять
+/// их подряд или пропустить.
+///
+/// Каждые `snapshot_every` тиков копия мира публикуется в `snapshots`.
+/// Если задан `GAME_REPLAY_DIR`, ввод и зерно каждого тика пишутся
+/// в реплей, который можно проверить командой `replay`.
+pub struct GameLoop {
+    simulation: Simulation,
+    clock: Box<dyn Clock>,
+    seed: u64,
+    inputs: mpsc::Receiver<PlayerInput>,
+    snapshots: watch::Sender<Arc<Snapshot>>,
+    snapshot_every: u64,
+    snapshot_seq: u64,
+    tick_rate: u32,
+    period: Duration,
+    catch_up: CatchUpPolicy,
+    replay_dir: Option<PathBuf>,
+    recorder: Option<ReplayRecorder>,
+    stats: TickStats,
+    last_overrun_warn: Option<Instant>,
+}
+
+impl GameLoop {
+    pub fn new(
+        cfg: &Config,
+        simulation: Simulation,
+        clock: impl Clock + 'static,
+        inputs: mpsc::Receiver<PlayerInput>,
+        snapshots: watch::Sender<Arc<Snapshot>>,
+    ) -> Self {
+        let tick_rate = cfg.game_tick_rate.max(1);
+        Self {
+            simulation,
+            clock: Box::new(clock),
+            // Без GAME_SEED каждый запуск свой; зерно всё равно попадает
+            // в реплей и в лог
+            seed: cfg
+                .game_seed
+                .unwrap_or_else(|| uuid::Uuid::new_v4().as_u64_pair().0),
+            inputs,
+            snapshots,
+            snapshot_every: cfg.game_snapshot_every.max(1),
+            snapshot_seq: 0,
+            tick_rate,
+            period: Duration::from_secs(1) / tick_rate,
+            catch_up: cfg.game_catch_up,
+            replay_dir: cfg.game_replay_dir.as_ref().map(PathBuf::from),
+            recorder: None,
+            stats: TickStats::default(),
+            last_overrun_warn: None,
+        }
+    }
+
+    /// Зерно цикла; по нему матч связывается с файлом реплея.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Блокирующий цикл до отмены `shutdown`; запускать в отдельном
+    /// потоке (`spawn_blocking`). Возвращает состояние мира на момент
+    /// остановки.
+    pub fn run(mut self, shutdown: CancellationToken) -> World {
+        info!(
+            "🎮 Game loop started: tick {:?}, seed {:016x}, systems {:?}",
+            self.period,
+            self.seed,
+            self.simulation.system_names()
+        );
+        self.start_recording();
+        let mut inputs = Vec::new();
+        let mut next_tick = self.clock.now();
+        let mut next_report = next_tick + REPORT_INTERVAL;
+
+        while !shutdown.is_cancelled() {
+            let now = self.clock.now();
+            if now < next_tick {
+                // Спим не дольше одного тика, поэтому остановка
+                // замечается быстро
+                self.clock.sleep_until(next_tick);
+                continue;
+            }
+
+            // Сколько тиков уже должно было случиться, включая текущий
+            let due = u64::try_from(
+                (now - next_tick).as_nanos() / self.period.as_nanos() + 1,
+            )
+            .unwrap_or(u64::MAX);
+            let run = match self.catch_up {
+                CatchUpPolicy::CatchUp { max_ticks } => due.min(max_ticks),
+                CatchUpPolicy::Skip => 1,
+            };
+            for _ in 0..run {
+                self.step(&mut inputs);
+            }
+            if due > run {
+                self.stats.record_skipped(due - run);
+            }
+            // Расписание сдвигается на все наступившие тики, в том числе
+            // пропущенные, и остаётся кратным периоду
+            next_tick += self.period * u32::try_from(due).unwrap_or(u32::MAX);
+
+            if now >= next_report {
+                self.report();
+                next_report = now + REPORT_INTERVAL;
+            }
+        }
+
+        self.flush_recording();
+        info!("🛑 Game loop stopped after {} ticks", self.stats.total_ticks);
+        self.simulation.into_world()
+    }
+
+    fn step(&mut self, inputs: &mut Vec<PlayerInput>) {
+        let started = self.clock.now();
+        inputs.clear();
+        while let Ok(input) = self.inputs.try_recv() {
+            inputs.push(input);
+        }
+
+        let tick = self.simulation.world().tick + 1;
+        let seed = tick_seed(self.seed, tick);
+        self.simulation.step(inputs, seed);
+        self.record(tick, seed, inputs);
+        if tick.is_multiple_of(self.snapshot_every) {
+            self.publish_snapshot();
+        }
+
+        let finished = self.clock.now();
+        let elapsed = finished - started;
+        self.stats.record(elapsed, self.period);
+        if elapsed > self.period
+            && self
+                .last_overrun_warn
+                .is_none_or(|at| finished - at >= OVERRUN_WARN_INTERVAL)
+        {
+            warn!(
+                "🐢 Tick {tick} took {elapsed:?}, budget is {:?}",
+                self.period
+            );
+            self.last_overrun_warn = Some(finished);
+        }
+    }
+
+    fn start_recording(&mut self) {
+        let Some(dir) = &self.replay_dir else {
+            return;
+        };
+        let header = ReplayHeader::new(
+            self.seed,
+            self.tick_rate,
+            self.simulation.world().clone(),
+        );
+        match ReplayRecorder::create(dir, &header) {
+            Ok(recorder) => {
+                info!("📼 Recording replay to {}", recorder.path().display());
+                self.recorder = Some(recorder);
+            }
+            Err(e) => {
+                error!("❌ Cannot start replay in {}: {e}", dir.display())
+            }
+        }
+    }
+
+    fn record(&mut self, tick: u64, seed: u64, inputs: &[PlayerInput]) {
+        let Some(recorder) = &mut self.recorder else {
+            return;
+        };
+        let record = TickRecord {
+            tick,
+            seed,
+            inputs: inputs.to_vec(),
+            checksum: self.simulation.world().checksum(),
+        };
+        // Сломанная запись не должна останавливать игру
+        if let Err(e) = recorder.record(&record) {
+            error!("❌ Replay recording stopped at tick {tick}: {e}");
+            self.recorder = None;
+        }
+    }
+
+    fn flush_recording(&mut self) {
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = recorder.flush()
+        {
+            error!("❌ Replay recording stopped: {e}");
+            self.recorder = None;
+        }
+    }
+
+    fn publish_snapshot(&mut self) {
+        self.snapshot_seq += 1;
+        // send_replace не падает без читателей: снимок просто ждёт первого
+        self.snapshots.send_replace(Arc::new(Snapshot {
+            seq: self.snapshot_seq,
+            world: self.simulation.world().clone(),
+        }));
+    }
+
+    fn report(&mut self) {
+        let stats = &self.stats;
+        info!(
+            "🎮 Tick stats: {} ticks, avg {:?}, max {:?}, {} overruns, {} skipped, {} players",
+            stats.ticks,
+            stats.avg_duration(),
+            stats.max_duration,
+            stats.overruns,
+            stats.skipped,
+            self.simulation.world().players.len()
+        );
+        self.stats.reset_window();
+        // Раз в отчёт запись гарантированно доходит до диска
+        self.flush_recording();
+    }
+}
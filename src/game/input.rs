@@ -0,0 +1,56 @@
+use crate::game::world::PlayerId;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+/// Действие игрока; применяется в начале ближайшего тика.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InputKind {
+    Join,
+    Leave,
+    /// Направление движения, каждая ось от -1 до 1
+    Move {
+        dx: i32,
+        dy: i32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerInput {
+    pub player_id: PlayerId,
+    #[serde(flatten)]
+    pub kind: InputKind,
+}
+
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("Game input queue is full")]
+    QueueFull,
+
+    #[error("Game loop is not running")]
+    Stopped,
+}
+
+/// Отправка ввода в игровой цикл из async-кода. Никогда не ждёт:
+/// при полной очереди ввод отклоняется, а не тормозит вызывающего.
+#[derive(Clone)]
+pub struct InputSender {
+    sender: mpsc::Sender<PlayerInput>,
+}
+
+impl InputSender {
+    pub fn send(&self, input: PlayerInput) -> Result<(), InputError> {
+        self.sender.try_send(input).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => InputError::QueueFull,
+            mpsc::error::TrySendError::Closed(_) => InputError::Stopped,
+        })
+    }
+}
+
+pub fn input_queue(
+    capacity: usize,
+) -> (InputSender, mpsc::Receiver<PlayerInput>) {
+    let (sender, receiver) = mpsc::channel(capacity.max(1));
+    (InputSender { sender }, receiver)
+}
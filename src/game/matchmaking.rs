@@ -0,0 +1,148 @@
+use crate::configs::Config;
+use crate::game::world::PlayerId;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Игрок в очереди.
+#[derive(Debug, Clone)]
+pub struct Ticket {
+    pub user_id: PlayerId,
+    pub rating: i32,
+    pub joined_at: Instant,
+}
+
+/// Состояние очереди для клиента.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueStatus {
+    pub rating: i32,
+    pub waited_secs: u64,
+    /// Допустимая разница рейтинга с соперниками сейчас
+    pub rating_window: i32,
+}
+
+/// Очередь подбора.
+///
+/// Рейтинги делятся на корзины по `bucket_width`. Сначала игрок
+/// подбирается только в своей корзине, затем каждые `widen_every`
+/// ожидания допускается ещё одна соседняя, но не дальше `max_spread`
+/// корзин. Двое совместимы, если разница корзин не больше допуска
+/// каждого из них: долгое ожидание одного не навязывает ему соперника,
+/// который ждёт только что.
+pub struct Matchmaker {
+    match_size: usize,
+    bucket_width: i32,
+    widen_every: Duration,
+    max_spread: i32,
+    queue: Mutex<Vec<Ticket>>,
+}
+
+impl Matchmaker {
+    pub fn new(cfg: &Config) -> Self {
+        Self {
+            match_size: cfg.match_size.max(1),
+            bucket_width: cfg.match_bucket_width.max(1),
+            widen_every: Duration::from_secs(cfg.match_widen_secs.max(1)),
+            max_spread: cfg.match_max_spread,
+            queue: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn queue(&self) -> std::sync::MutexGuard<'_, Vec<Ticket>> {
+        self.queue.lock().expect("matchmaking queue poisoned")
+    }
+
+    /// `false`, если игрок уже в очереди.
+    pub fn enqueue(&self, user_id: PlayerId, rating: i32) -> bool {
+        let mut queue = self.queue();
+        if queue.iter().any(|t| t.user_id == user_id) {
+            return false;
+        }
+        queue.push(Ticket { user_id, rating, joined_at: Instant::now() });
+        true
+    }
+
+    /// `false`, если игрока в очереди не было.
+    pub fn dequeue(&self, user_id: PlayerId) -> bool {
+        let mut queue = self.queue();
+        let before = queue.len();
+        queue.retain(|t| t.user_id != user_id);
+        queue.len() != before
+    }
+
+    /// Убирает из очереди тех, для кого `keep` вернул `false`.
+    pub fn retain(&self, keep: impl Fn(&Ticket) -> bool) -> Vec<PlayerId> {
+        let mut dropped = Vec::new();
+        self.queue().retain(|t| {
+            keep(t) || {
+                dropped.push(t.user_id);
+                false
+            }
+        });
+        dropped
+    }
+
+    pub fn status(&self, user_id: PlayerId) -> Option<QueueStatus> {
+        let now = Instant::now();
+        self.queue().iter().find(|t| t.user_id == user_id).map(|t| {
+            QueueStatus {
+                rating: t.rating,
+                waited_secs: now.duration_since(t.joined_at).as_secs(),
+                rating_window: (self.spread(t, now) + 1) * self.bucket_width,
+            }
+        })
+    }
+
+    /// Собирает из очереди до `limit` групп и убирает их игроков из
+    /// очереди. Дольше ждущие подбираются первыми.
+    pub fn form_matches(&self, now: Instant, limit: usize) -> Vec<Vec<Ticket>> {
+        let mut queue = self.queue();
+        queue.sort_by_key(|t| t.joined_at);
+        let mut matches = Vec::new();
+        let mut anchor = 0;
+        while anchor < queue.len() && matches.len() < limit {
+            let mut group = vec![anchor];
+            // Ближайшие по рейтингу кандидаты первыми
+            let mut candidates: Vec<usize> =
+                (anchor + 1..queue.len()).collect();
+            candidates.sort_by_key(|&i| {
+                (queue[i].rating - queue[anchor].rating).abs()
+            });
+            for candidate in candidates {
+                if group.len() == self.match_size {
+                    break;
+                }
+                if group.iter().all(|&member| {
+                    self.compatible(&queue[member], &queue[candidate], now)
+                }) {
+                    group.push(candidate);
+                }
+            }
+            if group.len() < self.match_size {
+                anchor += 1;
+                continue;
+            }
+            group.sort_unstable_by(|a, b| b.cmp(a));
+            let tickets: Vec<Ticket> =
+                group.into_iter().map(|i| queue.remove(i)).collect();
+            matches.push(tickets);
+        }
+        matches
+    }
+
+    fn bucket(&self, ticket: &Ticket) -> i32 {
+        ticket.rating.div_euclid(self.bucket_width)
+    }
+
+    /// Сколько соседних корзин игрок уже готов принять.
+    fn spread(&self, ticket: &Ticket, now: Instant) -> i32 {
+        let waited = now.duration_since(ticket.joined_at);
+        let steps = waited.as_secs() / self.widen_every.as_secs();
+        i32::try_from(steps).unwrap_or(i32::MAX).min(self.max_spread)
+    }
+
+    fn compatible(&self, a: &Ticket, b: &Ticket, now: Instant) -> bool {
+        let distance = (self.bucket(a) - self.bucket(b)).abs();
+        distance <= self.spread(a, now).min(self.spread(b, now))
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+/// Статистика тиков с последнего отчёта.
+#[derive(Debug, Clone, Default)]
+pub struct TickStats {
+    /// Всего выполнено тиков с запуска
+    pub total_ticks: u64,
+    pub ticks: u64,
+    /// Тики, не уложившиеся в бюджет
+    pub overruns: u64,
+    /// Тики, пропущенные из-за отставания
+    pub skipped: u64,
+    pub max_duration: Duration,
+    total_duration: Duration,
+}
+
+impl TickStats {
+    pub fn record(&mut self, duration: Duration, budget: Duration) {
+        self.total_ticks += 1;
+        self.ticks += 1;
+        self.total_duration += duration;
+        self.max_duration = self.max_duration.max(duration);
+        if duration > budget {
+            self.overruns += 1;
+        }
+    }
+
+    pub fn record_skipped(&mut self, ticks: u64) {
+        self.skipped += ticks;
+    }
+
+    pub fn avg_duration(&self) -> Duration {
+        match u32::try_from(self.ticks) {
+            Ok(0) | Err(_) => Duration::ZERO,
+            Ok(ticks) => self.total_duration / ticks,
+        }
+    }
+
+    /// Начинает новый интервал отчёта, сохраняя общий счётчик.
+    pub fn reset_window(&mut self) {
+        *self = Self { total_ticks: self.total_ticks, ..Self::default() };
+    }
+}
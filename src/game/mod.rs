@@ -0,0 +1,15 @@
+pub mod clock;
+pub mod engine;
+pub mod input;
+pub mod matchmaking;
+pub mod metrics;
+pub mod persistence;
+pub mod replay;
+pub mod replication;
+pub mod results;
+pub mod rng;
+pub mod rooms;
+pub mod simulation;
+pub mod snapshot;
+pub mod systems;
+pub mod world;
@@ -0,0 +1,222 @@
+use crate::configs::Config;
+use crate::game::snapshot::SnapshotReceiver;
+use crate::game::world::World;
+use crate::infra::storage::entities::world_snapshot;
+use crate::infra::storage::world_snapshots::{
+    NewWorldSnapshot, WorldSnapshotRepository,
+};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use sea_orm::DbErr;
+use std::io::{self, Write};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::task::spawn_blocking;
+use tokio::time::{MissedTickBehavior, interval};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+/// Формат поля `data` снимка.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    JsonGzip,
+}
+
+impl Encoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Json => "json",
+            Encoding::JsonGzip => "json+gzip",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "json" => Some(Encoding::Json),
+            "json+gzip" => Some(Encoding::JsonGzip),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PersistError {
+    #[error("World snapshot storage error: {0}")]
+    Db(#[from] DbErr),
+
+    #[error("World snapshot encoding error: {0}")]
+    Codec(#[from] io::Error),
+
+    #[error("Unknown world snapshot encoding {0:?}")]
+    UnknownEncoding(String),
+
+    #[error("World snapshot checksum {stored} does not match {actual}")]
+    Checksum { stored: String, actual: String },
+}
+
+pub fn encode(world: &World, encoding: Encoding) -> io::Result<Vec<u8>> {
+    let json = serde_json::to_vec(world)?;
+    match encoding {
+        Encoding::Json => Ok(json),
+        Encoding::JsonGzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+            encoder.write_all(&json)?;
+            encoder.finish()
+        }
+    }
+}
+
+pub fn decode(data: &[u8], encoding: Encoding) -> io::Result<World> {
+    Ok(match encoding {
+        Encoding::Json => serde_json::from_slice(data)?,
+        Encoding::JsonGzip => serde_json::from_reader(GzDecoder::new(data))?,
+    })
+}
+
+/// Сохранение общего мира в Postgres и восстановление после рестарта.
+///
+/// Игровой цикл об этом не знает: он и так публикует копию мира в
+/// `snapshots`, а сохранитель раз в `every` берёт последнюю, кодирует
+/// её в `spawn_blocking` и пишет в базу. Тик не ждёт ни сериализации,
+/// ни базы; медленная запись лишь пропускает промежуточные состояния.
+///
+/// Вместе с данными пишется `World::checksum()`. При восстановлении
+/// снимок, который не раскодировался или не сошёлся с отпечатком,
+/// пропускается в пользу предыдущего. Миры матчей не сохраняются:
+/// прерванный матч засчитывается как `interrupted`.
+#[derive(Clone)]
+pub struct WorldPersister {
+    repo: WorldSnapshotRepository,
+    /// `None` — сохранение выключено
+    every: Option<Duration>,
+    encoding: Encoding,
+    keep: u64,
+}
+
+impl WorldPersister {
+    pub fn new(cfg: &Config, repo: WorldSnapshotRepository) -> Self {
+        Self {
+            repo,
+            every: (cfg.game_persist_secs > 0)
+                .then(|| Duration::from_secs(cfg.game_persist_secs)),
+            encoding: if cfg.game_persist_compress {
+                Encoding::JsonGzip
+            } else {
+                Encoding::Json
+            },
+            keep: cfg.game_persist_keep.max(1),
+        }
+    }
+
+    /// Последний целый снимок или пустой мир, если восстанавливать
+    /// нечего. Вызывается до запуска игрового цикла и HTTP-сервера.
+    pub async fn restore(&self) -> Result<World, DbErr> {
+        if self.every.is_none() {
+            return Ok(World::default());
+        }
+        for row in self.repo.latest(self.keep).await? {
+            let id = row.id;
+            let loaded = match spawn_blocking(move || load(row)).await {
+                Ok(loaded) => loaded,
+                Err(e) => Err(io::Error::other(e).into()),
+            };
+            match loaded {
+                Ok(world) => {
+                    info!(
+                        "♻️ World restored from snapshot {id}: tick {}, {} players",
+                        world.tick,
+                        world.players.len()
+                    );
+                    return Ok(world);
+                }
+                Err(e) => warn!("⚠️ Skipping world snapshot {id}: {e}"),
+            }
+        }
+        info!("🌱 No saved world, starting from scratch");
+        Ok(World::default())
+    }
+
+    /// Сохраняет мир и удаляет снимки старше `keep` последних.
+    pub async fn save(&self, world: World) -> Result<i32, PersistError> {
+        let encoding = self.encoding;
+        let snapshot = spawn_blocking(move || -> io::Result<_> {
+            Ok(NewWorldSnapshot {
+                tick: world.tick,
+                players: world.players.len(),
+                encoding: encoding.as_str(),
+                checksum: world.checksum(),
+                data: encode(&world, encoding)?,
+            })
+        })
+        .await
+        .map_err(io::Error::other)??;
+        let saved = self.repo.create(snapshot).await?;
+        self.repo.prune(self.keep).await?;
+        Ok(saved.id)
+    }
+
+    /// Периодическое сохранение до отмены `shutdown`. Снимок, который
+    /// уже сохранён, повторно не пишется.
+    pub async fn run(
+        self,
+        snapshots: SnapshotReceiver,
+        shutdown: CancellationToken,
+    ) {
+        let Some(every) = self.every else {
+            info!("💾 World persistence is disabled");
+            return;
+        };
+        let mut ticker = interval(every);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // Первый тик interval срабатывает сразу, а до первого снимка
+        // цикла в канале только пустой мир
+        ticker.tick().await;
+        let mut saved_seq = 0;
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            let snapshot = snapshots.borrow().clone();
+            if snapshot.seq == saved_seq {
+                continue;
+            }
+            saved_seq = snapshot.seq;
+            match self.save(snapshot.world.clone()).await {
+                Ok(id) => debug!(
+                    "💾 World saved as snapshot {id} at tick {}",
+                    snapshot.world.tick
+                ),
+                Err(e) => error!("❌ World snapshot failed: {e}"),
+            }
+        }
+        info!("🛑 World persistence stopped");
+    }
+
+    /// Сохраняет мир, который вернул остановленный игровой цикл, чтобы
+    /// после рестарта игра продолжилась с того же тика.
+    pub async fn save_on_shutdown(&self, world: World) {
+        if self.every.is_none() {
+            return;
+        }
+        let tick = world.tick;
+        match self.save(world).await {
+            Ok(id) => info!("💾 World saved as snapshot {id} at tick {tick}"),
+            Err(e) => error!("❌ Final world snapshot failed: {e}"),
+        }
+    }
+}
+
+fn load(row: world_snapshot::Model) -> Result<World, PersistError> {
+    let encoding = Encoding::parse(&row.encoding)
+        .ok_or_else(|| PersistError::UnknownEncoding(row.encoding.clone()))?;
+    let world = decode(&row.data, encoding)?;
+    let actual = world.checksum();
+    if actual != row.checksum {
+        return Err(PersistError::Checksum { stored: row.checksum, actual });
+    }
+    Ok(world)
+}
@@ -0,0 +1,331 @@
+use crate::game::input::PlayerInput;
+use crate::game::simulation::Simulation;
+use crate::game::world::World;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Версия формата; меняется, когда старые записи нельзя проиграть.
+const FORMAT_VERSION: u32 = 1;
+
+/// Первая строка файла реплея.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub version: u32,
+    /// Зерно цикла; зёрна тиков выводятся из него
+    pub seed: u64,
+    pub tick_rate: u32,
+    /// Мир в момент начала записи
+    pub world: World,
+}
+
+impl ReplayHeader {
+    pub fn new(seed: u64, tick_rate: u32, world: World) -> Self {
+        Self { version: FORMAT_VERSION, seed, tick_rate, world }
+    }
+}
+
+/// Строка файла на каждый тик.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickRecord {
+    pub tick: u64,
+    pub seed: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<PlayerInput>,
+    /// `World::checksum` после тика
+    pub checksum: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Replay I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Invalid replay at line {line}: {message}")]
+    Format { line: usize, message: String },
+}
+
+/// Запись реплея: JSON Lines, только дописывается.
+///
+/// Пишется через буфер, на диск попадает при `flush` — после падения
+/// процесса последние тики могут потеряться, но файл остаётся
+/// пригодным для проверки до последней целой строки.
+pub struct ReplayRecorder {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl ReplayRecorder {
+    /// Создаёт новый файл в `dir` и пишет в него заголовок.
+    pub fn create(dir: &Path, header: &ReplayHeader) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "replay-{}-{:016x}.jsonl",
+            Utc::now().format("%Y%m%dT%H%M%S"),
+            header.seed
+        ));
+        let file =
+            OpenOptions::new().append(true).create_new(true).open(&path)?;
+        let mut recorder = Self { path, writer: BufWriter::new(file) };
+        recorder.write_line(header)?;
+        Ok(recorder)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn record(&mut self, record: &TickRecord) -> io::Result<()> {
+        self.write_line(record)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn write_line(&mut self, value: &impl Serialize) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, value)?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+/// Первый тик, на котором проигрывание разошлось с записью.
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub tick: u64,
+    pub expected: String,
+    pub actual: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    pub seed: u64,
+    pub start_tick: u64,
+    pub ticks: u64,
+    pub divergence: Option<Divergence>,
+    /// Мир после последнего проигранного тика
+    pub world: World,
+}
+
+/// Проигрывает файл через те же системы, что и игровой цикл, сверяя
+/// контрольную сумму после каждого тика. Останавливается на первом
+/// расхождении.
+pub fn verify(path: &Path) -> Result<ReplayReport, ReplayError> {
+    let mut lines = BufReader::new(File::open(path)?).lines().peekable();
+    let header: ReplayHeader = match lines.next() {
+        Some(line) => parse(1, &line?)?,
+        None => {
+            return Err(ReplayError::Format {
+                line: 1,
+                message: "missing header".to_string(),
+            });
+        }
+    };
+    if header.version != FORMAT_VERSION {
+        return Err(ReplayError::Format {
+            line: 1,
+            message: format!("unsupported version {}", header.version),
+        });
+    }
+
+    let start_tick = header.world.tick;
+    let mut simulation = Simulation::new(header.world.clone());
+    let mut ticks = 0;
+    let mut divergence = None;
+    let mut line_no = 1;
+    while let Some(line) = lines.next() {
+        line_no += 1;
+        let record: TickRecord = match parse(line_no, &line?) {
+            Ok(record) => record,
+            // Оборванная последняя строка — процесс упал во время записи
+            Err(_) if lines.peek().is_none() => break,
+            Err(e) => return Err(e),
+        };
+        let expected_tick = simulation.world().tick + 1;
+        if record.tick != expected_tick {
+            return Err(ReplayError::Format {
+                line: line_no,
+                message: format!(
+                    "expected tick {expected_tick}, found {}",
+                    record.tick
+                ),
+            });
+        }
+        simulation.step(&record.inputs, record.seed);
+        ticks += 1;
+        let actual = simulation.world().checksum();
+        if actual != record.checksum {
+            divergence = Some(Divergence {
+                tick: record.tick,
+                expected: record.checksum,
+                actual,
+            });
+            break;
+        }
+    }
+    Ok(ReplayReport {
+        seed: header.seed,
+        start_tick,
+        ticks,
+        divergence,
+        world: simulation.into_world(),
+    })
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(
+    line: usize,
+    text: &str,
+) -> Result<T, ReplayError> {
+    serde_json::from_str(text)
+        .map_err(|e| ReplayError::Format { line, message: e.to_string() })
+}
+
+/// `rust-service replay <file>`: код возврата 0, если реплей сошёлся,
+/// 1 при расхождении, 2 если файл не прочитать.
+pub fn run_cli(path: &Path) -> i32 {
+    match verify(path) {
+        Ok(report) => {
+            println!(
+                "Replay {}: seed {:016x}, {} ticks from tick {}",
+                path.display(),
+                report.seed,
+                report.ticks,
+                report.start_tick
+            );
+            match report.divergence {
+                None => {
+                    println!(
+                        "✅ All checksums match, {} players at the end",
+                        report.world.players.len()
+                    );
+                    0
+                }
+                Some(d) => {
+                    println!(
+                        "❌ Diverged at tick {}: expected {}, got {}",
+                        d.tick, d.expected, d.actual
+                    );
+                    println!("World after tick {}: {:?}", d.tick, report.world);
+                    1
+                }
+            }
+        }
+        Err(e) => {
+            eprintln!("❌ {e}");
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::input::InputKind;
+    use crate::game::rng::tick_seed;
+
+    const SEED: u64 = 0x5EED;
+    const TICKS: u64 = 40;
+
+    /// Ввод тика: двое входят, ходят в разные стороны, один выходит.
+    fn inputs(tick: u64) -> Vec<PlayerInput> {
+        let input = |player_id, kind| PlayerInput { player_id, kind };
+        match tick {
+            1 => vec![input(1, InputKind::Join), input(2, InputKind::Join)],
+            3 => vec![
+                input(1, InputKind::Move { dx: 1, dy: -1 }),
+                input(2, InputKind::Move { dx: -1, dy: 0 }),
+            ],
+            20 => vec![input(3, InputKind::Join)],
+            30 => vec![input(2, InputKind::Leave)],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Прогоняет цикл с записью и возвращает файл и мир в конце.
+    fn record(dir: &Path) -> (PathBuf, World) {
+        let mut simulation = Simulation::new(World::default());
+        let header = ReplayHeader::new(SEED, 20, simulation.world().clone());
+        let mut recorder = ReplayRecorder::create(dir, &header).unwrap();
+        for tick in 1..=TICKS {
+            let inputs = inputs(tick);
+            let seed = tick_seed(SEED, tick);
+            simulation.step(&inputs, seed);
+            recorder
+                .record(&TickRecord {
+                    tick,
+                    seed,
+                    inputs,
+                    checksum: simulation.world().checksum(),
+                })
+                .unwrap();
+        }
+        recorder.flush().unwrap();
+        (recorder.path().to_path_buf(), simulation.into_world())
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("replay-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn replay_reproduces_recorded_world() {
+        let dir = temp_dir();
+        let (path, world) = record(&dir);
+        let report = verify(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(report.divergence.is_none(), "{:?}", report.divergence);
+        assert_eq!(report.seed, SEED);
+        assert_eq!(report.ticks, TICKS);
+        assert_eq!(report.world.checksum(), world.checksum());
+        assert_eq!(report.world, world);
+        assert_eq!(
+            report.world.players.keys().copied().collect::<Vec<_>>(),
+            [1, 3]
+        );
+    }
+
+    #[test]
+    fn replay_reports_first_divergent_tick() {
+        let dir = temp_dir();
+        let (path, _) = record(&dir);
+        // Подменяем ввод тика 3, как если бы цикл применил не то, что
+        // записал
+        let text = fs::read_to_string(&path).unwrap();
+        let tampered: Vec<String> = text
+            .lines()
+            .map(|line| match serde_json::from_str::<TickRecord>(line) {
+                Ok(mut record) if record.tick == 3 => {
+                    record.inputs[0].kind = InputKind::Move { dx: 0, dy: 1 };
+                    serde_json::to_string(&record).unwrap()
+                }
+                _ => line.to_string(),
+            })
+            .collect();
+        fs::write(&path, tampered.join("\n") + "\n").unwrap();
+        let report = verify(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let divergence = report.divergence.expect("divergence detected");
+        assert_eq!(divergence.tick, 3);
+        assert_ne!(divergence.expected, divergence.actual);
+        assert_eq!(report.ticks, 3);
+    }
+
+    #[test]
+    fn replay_ignores_truncated_last_line() {
+        let dir = temp_dir();
+        let (path, _) = record(&dir);
+        let mut text = fs::read_to_string(&path).unwrap();
+        text.truncate(text.len() - 10);
+        fs::write(&path, text).unwrap();
+        let report = verify(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(report.divergence.is_none());
+        assert_eq!(report.ticks, TICKS - 1);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::game::snapshot::Snapshot;
+use crate::game::world::{Player, PlayerId};
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+
+/// Сколько неподтверждённых снимков помнить на клиента. Подтверждения
+/// более старых снимков игнорируются: дельты тогда считаются от
+/// последнего подтверждённого.
+const MAX_PENDING: usize = 64;
+
+/// Часть мира, которую видит клиент.
+type View = BTreeMap<PlayerId, Player>;
+
+/// Обновление состояния для клиента.
+///
+/// Без `base` — полный снимок видимой части мира. С `base` — дельта
+/// относительно снимка `base`, который клиент подтвердил: изменённые
+/// и появившиеся игроки в `players`, исчезнувшие из видимости в `removed`.
+#[derive(Debug, Clone, Serialize)]
+pub struct StateUpdate {
+    pub seq: u64,
+    pub tick: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<u64>,
+    pub players: Vec<Player>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<PlayerId>,
+}
+
+impl StateUpdate {
+    /// Тип кадра WebSocket.
+    pub fn kind(&self) -> &'static str {
+        match self.base {
+            Some(_) => "game.delta",
+            None => "game.snapshot",
+        }
+    }
+}
+
+/// Состояние репликации одного клиента.
+///
+/// Клиент видит только игроков в квадрате `radius` вокруг своего
+/// игрока. Дельты считаются от последнего подтверждённого снимка, а не
+/// от последнего отправленного: потерянный или пропущенный кадр не
+/// ломает состояние клиента.
+pub struct Replica {
+    player_id: PlayerId,
+    radius: i32,
+    acked: Option<(u64, View)>,
+    /// Отправленные, но ещё не подтверждённые снимки по возрастанию seq
+    pending: VecDeque<(u64, View)>,
+}
+
+impl Replica {
+    pub fn new(player_id: PlayerId, radius: i32) -> Self {
+        Self { player_id, radius, acked: None, pending: VecDeque::new() }
+    }
+
+    /// Обновление для клиента или `None`, если его игрока нет в мире.
+    pub fn update(&mut self, snapshot: &Snapshot) -> Option<StateUpdate> {
+        let Some(me) = snapshot.world.players.get(&self.player_id) else {
+            // Игрок вышел: при повторном входе клиент получит полный снимок
+            self.acked = None;
+            self.pending.clear();
+            return None;
+        };
+        let view: View = snapshot
+            .world
+            .players
+            .values()
+            .filter(|p| {
+                (p.x - me.x).abs() <= self.radius
+                    && (p.y - me.y).abs() <= self.radius
+            })
+            .map(|p| (p.id, p.clone()))
+            .collect();
+
+        let update = match &self.acked {
+            None => StateUpdate {
+                seq: snapshot.seq,
+                tick: snapshot.world.tick,
+                base: None,
+                players: view.values().cloned().collect(),
+                removed: Vec::new(),
+            },
+            Some((base, acked)) => StateUpdate {
+                seq: snapshot.seq,
+                tick: snapshot.world.tick,
+                base: Some(*base),
+                players: view
+                    .values()
+                    .filter(|p| acked.get(&p.id) != Some(p))
+                    .cloned()
+                    .collect(),
+                removed: acked
+                    .keys()
+                    .filter(|id| !view.contains_key(id))
+                    .copied()
+                    .collect(),
+            },
+        };
+
+        if self.pending.len() == MAX_PENDING {
+            self.pending.pop_front();
+        }
+        self.pending.push_back((snapshot.seq, view));
+        Some(update)
+    }
+
+    /// Клиент применил снимок `seq`; следующие дельты строятся от него.
+    /// Возвращает `false`, если такого снимка уже или ещё нет.
+    pub fn ack(&mut self, seq: u64) -> bool {
+        let Some(index) = self.pending.iter().position(|(s, _)| *s == seq)
+        else {
+            return false;
+        };
+        // Более ранние снимки клиенту уже не нужны
+        self.acked = self.pending.drain(..=index).next_back();
+        true
+    }
+}
@@ -0,0 +1,131 @@
+use crate::game::world::PlayerId;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Рейтинг игрока, ещё не сыгравшего ни одного матча.
+pub const DEFAULT_RATING: i32 = 1500;
+/// Максимальное изменение рейтинга за матч один на один (Эло).
+const K_FACTOR: f64 = 32.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Won,
+    Lost,
+    Draw,
+    /// Не вернулся за отведённое на переподключение время
+    Abandoned,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Won => "won",
+            Outcome::Lost => "lost",
+            Outcome::Draw => "draw",
+            Outcome::Abandoned => "abandoned",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchStatus {
+    Completed,
+    /// Сервер остановился или игровой цикл упал посреди матча; рейтинг
+    /// не меняется, в сыгранные матч не идёт
+    Interrupted,
+}
+
+impl MatchStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchStatus::Completed => "completed",
+            MatchStatus::Interrupted => "interrupted",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerResult {
+    pub user_id: PlayerId,
+    pub score: u64,
+    pub outcome: Outcome,
+    pub rating_before: i32,
+    pub rating_after: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchResult {
+    pub room_id: u64,
+    pub status: MatchStatus,
+    /// Зерно игрового цикла в hex, как в имени файла реплея
+    pub seed: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub players: Vec<PlayerResult>,
+}
+
+/// Участник на момент окончания матча.
+pub struct Standing {
+    pub user_id: PlayerId,
+    pub rating: i32,
+    pub score: u64,
+    pub abandoned: bool,
+}
+
+/// Итоги по очкам: больше очков — выше место, ушедшие ниже всех.
+/// Рейтинг меняется попарно по Эло, каждая пара весит `1 / (n - 1)`,
+/// чтобы матч на четверых двигал рейтинг не сильнее дуэли.
+pub fn settle(
+    standings: &[Standing],
+    status: MatchStatus,
+) -> Vec<PlayerResult> {
+    let rank = |s: &Standing| (!s.abandoned, s.score);
+    let best = standings.iter().filter(|s| !s.abandoned).map(rank).max();
+    let leaders = standings.iter().filter(|s| Some(rank(s)) == best).count();
+    let pairs = standings.len().saturating_sub(1).max(1) as f64;
+
+    standings
+        .iter()
+        .map(|me| {
+            let outcome = if me.abandoned {
+                Outcome::Abandoned
+            } else if Some(rank(me)) != best {
+                Outcome::Lost
+            } else if leaders > 1 {
+                Outcome::Draw
+            } else {
+                Outcome::Won
+            };
+            let delta: f64 = standings
+                .iter()
+                .filter(|other| other.user_id != me.user_id)
+                .map(|other| {
+                    let actual = match rank(me).cmp(&rank(other)) {
+                        std::cmp::Ordering::Greater => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.0,
+                    };
+                    let expected = 1.0
+                        / (1.0
+                            + 10f64.powf(
+                                f64::from(other.rating - me.rating) / 400.0,
+                            ));
+                    K_FACTOR * (actual - expected) / pairs
+                })
+                .sum();
+            let rating_after = match status {
+                MatchStatus::Completed => me.rating + delta.round() as i32,
+                MatchStatus::Interrupted => me.rating,
+            };
+            PlayerResult {
+                user_id: me.user_id,
+                score: me.score,
+                outcome,
+                rating_before: me.rating,
+                rating_after,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,500 @@
+use crate::configs::Config;
+use crate::core::errors::DomainError;
+use crate::core::events::{DomainEvent, EventBus};
+use crate::game::clock::SystemClock;
+use crate::game::engine::GameLoop;
+use crate::game::input::{InputKind, InputSender, PlayerInput, input_queue};
+use crate::game::matchmaking::{Matchmaker, Ticket};
+use crate::game::results::{MatchResult, MatchStatus, Standing, settle};
+use crate::game::simulation::Simulation;
+use crate::game::snapshot::{SnapshotReceiver, snapshot_channel};
+use crate::game::world::{PlayerId, World};
+use crate::infra::storage::matches::MatchRepository;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::{JoinSet, spawn_blocking};
+use tokio::time::{Instant, MissedTickBehavior, interval};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+pub type RoomId = u64;
+
+/// Как часто проверять очередь и присутствие игроков в комнатах.
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Есть ли у игрока живое соединение. Реализует хаб WebSocket.
+pub trait Presence: Send + Sync {
+    fn is_online(&self, user_id: PlayerId) -> bool;
+}
+
+/// `Waiting` → `Running` → `Finished`; из `Waiting` можно сразу
+/// в `Finished`, если игроки не подключились.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomState {
+    /// Матч собран, ждём, пока все игроки будут на связи
+    Waiting,
+    Running,
+    Finished,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoomInfo {
+    pub id: RoomId,
+    pub state: RoomState,
+    pub players: Vec<PlayerId>,
+    /// Потерявшие связь игроки, которых ещё ждут
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disconnected: Vec<PlayerId>,
+    /// Итоги; нет у отменённого матча
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<MatchResult>,
+}
+
+struct Room {
+    info: RoomInfo,
+    /// Есть, только пока идёт матч
+    game: Option<(InputSender, SnapshotReceiver)>,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: RoomId,
+    rooms: HashMap<RoomId, Room>,
+    by_player: HashMap<PlayerId, RoomId>,
+}
+
+/// Комнаты матчей и общий мир (лобби).
+///
+/// У каждого матча свой игровой цикл, свой мир и свой реплей. Игрок,
+/// который не в матче, находится в лобби; ввод и снимки идут туда,
+/// где игрок сейчас, — об изменении сообщает `subscribe`.
+pub struct Rooms {
+    cfg: Config,
+    presence: Arc<dyn Presence>,
+    repo: MatchRepository,
+    events: EventBus,
+    lobby_inputs: InputSender,
+    lobby_snapshots: SnapshotReceiver,
+    registry: Mutex<Registry>,
+    changes: watch::Sender<()>,
+}
+
+impl Rooms {
+    pub fn new(
+        cfg: Config,
+        presence: Arc<dyn Presence>,
+        repo: MatchRepository,
+        events: EventBus,
+        lobby_inputs: InputSender,
+        lobby_snapshots: SnapshotReceiver,
+    ) -> Self {
+        Self {
+            cfg,
+            presence,
+            repo,
+            events,
+            lobby_inputs,
+            lobby_snapshots,
+            registry: Mutex::new(Registry::default()),
+            changes: watch::channel(()).0,
+        }
+    }
+
+    fn registry(&self) -> MutexGuard<'_, Registry> {
+        self.registry.lock().expect("room registry poisoned")
+    }
+
+    pub fn is_online(&self, player: PlayerId) -> bool {
+        self.presence.is_online(player)
+    }
+
+    pub fn room_of(&self, player: PlayerId) -> Option<RoomInfo> {
+        let registry = self.registry();
+        let id = registry.by_player.get(&player)?;
+        registry.rooms.get(id).map(|room| room.info.clone())
+    }
+
+    /// Куда отправлять ввод игрока.
+    pub fn input_for(
+        &self,
+        player: PlayerId,
+    ) -> Result<InputSender, DomainError> {
+        let registry = self.registry();
+        let Some(room) = registry
+            .by_player
+            .get(&player)
+            .and_then(|id| registry.rooms.get(id))
+        else {
+            return Ok(self.lobby_inputs.clone());
+        };
+        match &room.game {
+            Some((inputs, _)) => Ok(inputs.clone()),
+            None => Err(DomainError::Conflict(format!(
+                "Match in room {} has not started yet",
+                room.info.id
+            ))),
+        }
+    }
+
+    /// Снимки мира, в котором сейчас игрок.
+    pub fn snapshots_for(&self, player: PlayerId) -> SnapshotReceiver {
+        let registry = self.registry();
+        registry
+            .by_player
+            .get(&player)
+            .and_then(|id| registry.rooms.get(id))
+            .and_then(|room| room.game.as_ref())
+            .map_or_else(
+                || self.lobby_snapshots.clone(),
+                |(_, snapshots)| snapshots.clone(),
+            )
+    }
+
+    /// Закрылось соединение игрока. Если других нет и он в лобби, он
+    /// оттуда уходит; в матче его ждёт `play`, там можно переподключиться.
+    pub fn disconnected(&self, player_id: PlayerId) {
+        if self.presence.is_online(player_id)
+            || self.registry().by_player.contains_key(&player_id)
+        {
+            return;
+        }
+        let _ = self
+            .lobby_inputs
+            .send(PlayerInput { player_id, kind: InputKind::Leave });
+    }
+
+    /// Срабатывает, когда кто-то вошёл в матч или вышел из него.
+    pub fn subscribe(&self) -> watch::Receiver<()> {
+        self.changes.subscribe()
+    }
+
+    /// Подбирает матчи из очереди и ведёт их до конца. При остановке
+    /// дожидается, пока идущие матчи сохранятся как прерванные.
+    pub async fn run(
+        self: Arc<Self>,
+        matchmaker: Arc<Matchmaker>,
+        shutdown: CancellationToken,
+    ) {
+        let mut matches = JoinSet::new();
+        let mut lobby_offline = HashMap::new();
+        let mut ticker = interval(SUPERVISE_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            // Ушедшие из сети матча не ждут
+            for user_id in
+                matchmaker.retain(|t| self.presence.is_online(t.user_id))
+            {
+                info!("🎯 Player {user_id} left the queue: offline");
+            }
+            self.sweep_lobby(&mut lobby_offline);
+            // Остальные подождут в очереди, пока не освободится комната
+            let free = self
+                .cfg
+                .match_max_rooms
+                .saturating_sub(self.registry().rooms.len());
+            for tickets in
+                matchmaker.form_matches(std::time::Instant::now(), free)
+            {
+                let id = self.open(&tickets);
+                matches.spawn(self.clone().supervise(
+                    id,
+                    tickets,
+                    shutdown.clone(),
+                ));
+            }
+            while matches.try_join_next().is_some() {}
+        }
+        while matches.join_next().await.is_some() {}
+        info!("🛑 Matchmaking stopped");
+    }
+
+    /// Выводит из лобби игроков без соединения, которых не вывел
+    /// `disconnected`: например, восстановленных из снимка после
+    /// перезапуска и не вернувшихся за `match_reconnect_secs`.
+    fn sweep_lobby(&self, offline_since: &mut HashMap<PlayerId, Instant>) {
+        let now = Instant::now();
+        let grace = Duration::from_secs(self.cfg.match_reconnect_secs);
+        let players: Vec<PlayerId> = self
+            .lobby_snapshots
+            .borrow()
+            .world
+            .players
+            .keys()
+            .copied()
+            .collect();
+        offline_since.retain(|player, _| players.contains(player));
+        for player_id in players {
+            if self.presence.is_online(player_id) {
+                offline_since.remove(&player_id);
+                continue;
+            }
+            let since = *offline_since.entry(player_id).or_insert(now);
+            if now - since < grace {
+                continue;
+            }
+            offline_since.remove(&player_id);
+            let _ = self
+                .lobby_inputs
+                .send(PlayerInput { player_id, kind: InputKind::Leave });
+            info!("👻 Player {player_id} left the lobby: offline");
+        }
+    }
+
+    fn open(&self, tickets: &[Ticket]) -> RoomId {
+        let players: Vec<PlayerId> =
+            tickets.iter().map(|t| t.user_id).collect();
+        let info = {
+            let mut registry = self.registry();
+            registry.next_id += 1;
+            let id = registry.next_id;
+            for &player in &players {
+                registry.by_player.insert(player, id);
+            }
+            let info = RoomInfo {
+                id,
+                state: RoomState::Waiting,
+                players: players.clone(),
+                disconnected: Vec::new(),
+                result: None,
+            };
+            registry.rooms.insert(id, Room { info: info.clone(), game: None });
+            info
+        };
+        // Из лобби игроки уходят сразу, чтобы не висеть там во время матча
+        for &player_id in &players {
+            let _ = self
+                .lobby_inputs
+                .send(PlayerInput { player_id, kind: InputKind::Leave });
+        }
+        info!("🎯 Room {} opened for players {players:?}", info.id);
+        self.announce(&info);
+        info.id
+    }
+
+    async fn supervise(
+        self: Arc<Self>,
+        id: RoomId,
+        tickets: Vec<Ticket>,
+        shutdown: CancellationToken,
+    ) {
+        let players: Vec<PlayerId> =
+            tickets.iter().map(|t| t.user_id).collect();
+        if !self.wait_for_players(&players, &shutdown).await {
+            info!("🚪 Room {id} cancelled: not all players connected");
+            self.close(id, None);
+            return;
+        }
+
+        let started_at = Utc::now();
+        let (inputs, input_rx) = input_queue(self.cfg.game_input_queue);
+        let (snapshots, snapshot_rx) = snapshot_channel();
+        let game = GameLoop::new(
+            &self.cfg,
+            Simulation::new(World::default()),
+            SystemClock,
+            input_rx,
+            snapshots,
+        );
+        let seed = game.seed();
+        // Свой токен: матч заканчивается раньше приложения
+        let stop = shutdown.child_token();
+        let game_stop = stop.clone();
+        let handle = spawn_blocking(move || game.run(game_stop));
+        for &player_id in &players {
+            let _ =
+                inputs.send(PlayerInput { player_id, kind: InputKind::Join });
+        }
+        self.update(id, |room| {
+            room.info.state = RoomState::Running;
+            room.game = Some((inputs.clone(), snapshot_rx.clone()));
+        });
+        info!("🎮 Room {id} started");
+
+        let abandoned =
+            self.play(id, &players, &inputs, &snapshot_rx, &shutdown).await;
+        stop.cancel();
+        let world = match handle.await {
+            Ok(world) => Some(world),
+            Err(e) => {
+                error!("❌ Game loop of room {id} failed: {e}");
+                None
+            }
+        };
+
+        // Без мира очки неизвестны: такой матч тоже прерван
+        let status = if shutdown.is_cancelled() || world.is_none() {
+            MatchStatus::Interrupted
+        } else {
+            MatchStatus::Completed
+        };
+        let world = world.unwrap_or_default();
+        let standings: Vec<Standing> = tickets
+            .iter()
+            .map(|t| Standing {
+                user_id: t.user_id,
+                rating: t.rating,
+                score: abandoned.get(&t.user_id).copied().unwrap_or_else(
+                    || world.players.get(&t.user_id).map_or(0, |p| p.score),
+                ),
+                abandoned: abandoned.contains_key(&t.user_id),
+            })
+            .collect();
+        let result = MatchResult {
+            room_id: id,
+            status,
+            seed: format!("{seed:016x}"),
+            started_at,
+            finished_at: Utc::now(),
+            players: settle(&standings, status),
+        };
+        match self.repo.save(&result).await {
+            Ok(match_id) => info!("🏁 Room {id} finished as match {match_id}"),
+            Err(e) => error!("❌ Cannot save result of room {id}: {e}"),
+        }
+        self.close(id, Some(result));
+    }
+
+    /// `false`, если к `match_ready_timeout_secs` не все на связи.
+    async fn wait_for_players(
+        &self,
+        players: &[PlayerId],
+        shutdown: &CancellationToken,
+    ) -> bool {
+        let deadline = Instant::now()
+            + Duration::from_secs(self.cfg.match_ready_timeout_secs);
+        let mut ticker = interval(SUPERVISE_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => return false,
+                _ = ticker.tick() => {}
+            }
+            if players.iter().all(|&p| self.presence.is_online(p)) {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+        }
+    }
+
+    /// Идёт до конца времени матча, остановки приложения или пока
+    /// соперников не станет слишком мало. Возвращает ушедших игроков
+    /// с очками на момент ухода.
+    async fn play(
+        &self,
+        id: RoomId,
+        players: &[PlayerId],
+        inputs: &InputSender,
+        snapshots: &SnapshotReceiver,
+        shutdown: &CancellationToken,
+    ) -> HashMap<PlayerId, u64> {
+        let deadline =
+            Instant::now() + Duration::from_secs(self.cfg.match_duration_secs);
+        let reconnect = Duration::from_secs(self.cfg.match_reconnect_secs);
+        let mut offline_since: HashMap<PlayerId, Instant> = HashMap::new();
+        let mut abandoned = HashMap::new();
+        let mut ticker = interval(SUPERVISE_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+
+            let mut changed = false;
+            for &player_id in players {
+                if abandoned.contains_key(&player_id) {
+                    continue;
+                }
+                if self.presence.is_online(player_id) {
+                    changed |= offline_since.remove(&player_id).is_some();
+                    continue;
+                }
+                let since =
+                    *offline_since.entry(player_id).or_insert_with(|| {
+                        warn!("📵 Player {player_id} dropped from room {id}");
+                        changed = true;
+                        now
+                    });
+                if now - since < reconnect {
+                    continue;
+                }
+                offline_since.remove(&player_id);
+                let score = snapshots
+                    .borrow()
+                    .world
+                    .players
+                    .get(&player_id)
+                    .map_or(0, |p| p.score);
+                abandoned.insert(player_id, score);
+                let _ = inputs
+                    .send(PlayerInput { player_id, kind: InputKind::Leave });
+                self.registry().by_player.remove(&player_id);
+                info!("📵 Player {player_id} abandoned room {id}");
+                changed = true;
+            }
+            if changed {
+                let disconnected = offline_since.keys().copied().collect();
+                self.update(id, |room| room.info.disconnected = disconnected);
+            }
+
+            // Один оставшийся в дуэли побеждает, не дожидаясь конца
+            if players.len() - abandoned.len() < players.len().min(2) {
+                break;
+            }
+        }
+        abandoned
+    }
+
+    fn update(&self, id: RoomId, f: impl FnOnce(&mut Room)) {
+        let info = {
+            let mut registry = self.registry();
+            let Some(room) = registry.rooms.get_mut(&id) else {
+                return;
+            };
+            f(room);
+            room.info.clone()
+        };
+        self.announce(&info);
+    }
+
+    fn close(&self, id: RoomId, result: Option<MatchResult>) {
+        let Some(mut info) = ({
+            let mut registry = self.registry();
+            let room = registry.rooms.remove(&id);
+            registry.by_player.retain(|_, room_id| *room_id != id);
+            room.map(|room| room.info)
+        }) else {
+            return;
+        };
+        info.state = RoomState::Finished;
+        info.disconnected.clear();
+        info.result = result;
+        self.announce(&info);
+    }
+
+    /// Сообщает игрокам комнаты о её новом состоянии, а сессиям — что
+    /// пора проверить, откуда брать снимки.
+    fn announce(&self, info: &RoomInfo) {
+        self.changes.send_replace(());
+        for &user_id in &info.players {
+            self.events.publish(DomainEvent::RoomUpdated {
+                user_id,
+                room: info.clone(),
+            });
+        }
+    }
+}
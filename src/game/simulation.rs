@@ -0,0 +1,47 @@
+use crate::game::input::PlayerInput;
+use crate::game::rng::GameRng;
+use crate::game::systems::{
+    GameSystem, InputSystem, MovementSystem, TickContext,
+};
+use crate::game::world::World;
+
+/// Логика игры без расписания и ввода-вывода: мир и системы.
+///
+/// Результат тика зависит только от мира, ввода и зерна. Этим
+/// пользуются и игровой цикл, и проверка реплеев, поэтому набор систем
+/// задан здесь, а не снаружи.
+pub struct Simulation {
+    world: World,
+    systems: Vec<Box<dyn GameSystem>>,
+}
+
+impl Simulation {
+    pub fn new(world: World) -> Self {
+        Self {
+            world,
+            systems: vec![Box::new(InputSystem), Box::new(MovementSystem)],
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn into_world(self) -> World {
+        self.world
+    }
+
+    pub fn system_names(&self) -> Vec<&'static str> {
+        self.systems.iter().map(|s| s.name()).collect()
+    }
+
+    /// Выполняет следующий тик.
+    pub fn step(&mut self, inputs: &[PlayerInput], seed: u64) {
+        self.world.tick += 1;
+        let mut rng = GameRng::new(seed);
+        let mut ctx = TickContext { inputs, rng: &mut rng };
+        for system in &mut self.systems {
+            system.run(&mut self.world, &mut ctx);
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::game::world::World;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Состояние мира, опубликованное игровым циклом.
+///
+/// `seq` растёт на единицу с каждым снимком, независимо от того,
+/// через сколько тиков они публикуются; по нему клиенты подтверждают
+/// полученное состояние.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub seq: u64,
+    pub world: World,
+}
+
+/// Читатели всегда видят только последний снимок: медленный клиент
+/// пропускает промежуточные, а не копит очередь.
+pub type SnapshotReceiver = watch::Receiver<Arc<Snapshot>>;
+
+pub fn snapshot_channel() -> (watch::Sender<Arc<Snapshot>>, SnapshotReceiver) {
+    watch::channel(Arc::new(Snapshot::default()))
+}
@@ -0,0 +1,82 @@
+use crate::game::input::{InputKind, PlayerInput};
+use crate::game::rng::GameRng;
+use crate::game::world::{MAX_SPEED, Player, WORLD_SIZE, World};
+
+/// Данные текущего тика, общие для всех систем. Номер тика — в
+/// `World::tick`; шаг фиксированный, поэтому скорости задаются
+/// в единицах за тик.
+pub struct TickContext<'a> {
+    /// Ввод, накопившийся с прошлого тика, в порядке поступления
+    pub inputs: &'a [PlayerInput],
+    /// Единственный допустимый источник случайности в системах: его
+    /// зерно пишется в реплей
+    pub rng: &'a mut GameRng,
+}
+
+/// Часть логики игры, выполняемая каждый тик.
+///
+/// Системы выполняются по очереди в порядке регистрации и не должны
+/// блокироваться: тик целиком обязан укладываться в свой бюджет.
+/// Результат должен зависеть только от мира и `TickContext` — никаких
+/// часов, `HashMap` со случайным порядком и внешнего состояния, иначе
+/// реплей разойдётся с записью.
+pub trait GameSystem: Send {
+    fn name(&self) -> &'static str;
+
+    fn run(&mut self, world: &mut World, ctx: &mut TickContext);
+}
+
+/// Применяет ввод игроков: вход в случайной точке поля, выход,
+/// смена направления.
+pub struct InputSystem;
+
+impl GameSystem for InputSystem {
+    fn name(&self) -> &'static str {
+        "input"
+    }
+
+    fn run(&mut self, world: &mut World, ctx: &mut TickContext) {
+        for input in ctx.inputs {
+            let id = input.player_id;
+            match &input.kind {
+                InputKind::Join => {
+                    world.players.entry(id).or_insert_with(|| {
+                        let x = ctx.rng.range(0, WORLD_SIZE + 1);
+                        let y = ctx.rng.range(0, WORLD_SIZE + 1);
+                        Player::spawn(id, x, y)
+                    });
+                }
+                InputKind::Leave => {
+                    world.players.remove(&id);
+                }
+                InputKind::Move { dx, dy } => {
+                    if let Some(player) = world.players.get_mut(&id) {
+                        player.vx = dx.signum() * MAX_SPEED;
+                        player.vy = dy.signum() * MAX_SPEED;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Двигает игроков по скорости, не выпуская за границы поля, и
+/// начисляет очки за пройденное расстояние.
+pub struct MovementSystem;
+
+impl GameSystem for MovementSystem {
+    fn name(&self) -> &'static str {
+        "movement"
+    }
+
+    fn run(&mut self, world: &mut World, _ctx: &mut TickContext) {
+        for player in world.players.values_mut() {
+            let x = (player.x + player.vx).clamp(0, WORLD_SIZE);
+            let y = (player.y + player.vy).clamp(0, WORLD_SIZE);
+            player.score +=
+                u64::from(x.abs_diff(player.x) + y.abs_diff(player.y));
+            player.x = x;
+            player.y = y;
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// Игрок — это пользователь сервиса.
+pub type PlayerId = i32;
+
+/// Размер поля в игровых единицах; координаты целые, чтобы симуляция
+/// давала одинаковый результат на любой машине.
+pub const WORLD_SIZE: i32 = 10_000;
+/// Максимальная скорость по каждой оси, единиц за тик.
+pub const MAX_SPEED: i32 = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Player {
+    pub id: PlayerId,
+    pub x: i32,
+    pub y: i32,
+    pub vx: i32,
+    pub vy: i32,
+    /// Очки в матче: пройденное расстояние
+    #[serde(default)]
+    pub score: u64,
+}
+
+impl Player {
+    /// Новый игрок стоит на месте.
+    pub fn spawn(id: PlayerId, x: i32, y: i32) -> Self {
+        Self { id, x, y, vx: 0, vy: 0, score: 0 }
+    }
+}
+
+/// Состояние симуляции. Меняется только системами внутри тика.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct World {
+    /// Номер последнего выполненного тика
+    pub tick: u64,
+    /// BTreeMap, а не HashMap: порядок обхода должен быть одинаковым
+    pub players: BTreeMap<PlayerId, Player>,
+}
+
+impl World {
+    /// Короткий отпечаток состояния для сверки реплеев. Сериализация
+    /// детерминирована: поля в порядке объявления, игроки по id.
+    pub fn checksum(&self) -> String {
+        let bytes = serde_json::to_vec(self).unwrap_or_default();
+        hex::encode(&Sha256::digest(&bytes)[..8])
+    }
+}
@@ -0,0 +1,342 @@
+use reqwest::RequestBuilder;
+use reqwest::header::HeaderValue;
+
+/// Как клиент внешнего API подписывает запросы.
+// В сборке сервиса клиентов с auth пока нет, все варианты проверяются
+// тестами ниже
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Debug, Clone, Copy)]
+pub enum Auth {
+    None,
+    /// `Authorization: Bearer <secret>`
+    Bearer,
+    /// Секрет как есть в заголовке, например `X-Api-Key`
+    Header(&'static str),
+}
+
+impl Auth {
+    pub fn apply(
+        self,
+        request: RequestBuilder,
+        secret: Option<&str>,
+    ) -> RequestBuilder {
+        let Some(secret) = secret else { return request };
+        match self {
+            Auth::None => request,
+            Auth::Bearer => request.bearer_auth(secret),
+            Auth::Header(name) => match HeaderValue::from_str(secret) {
+                Ok(mut value) => {
+                    value.set_sensitive(true);
+                    request.header(name, value)
+                }
+                // Пусть reqwest вернёт ошибку сборки запроса
+                Err(_) => request.header(name, secret),
+            },
+        }
+    }
+}
+
+/// Значение для подстановки в путь: всё, кроме unreserved из RFC 3986,
+/// кодируется, чтобы `/` или `?` в id не меняли адрес.
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn path_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Объявление клиента внешнего API.
+///
+/// По списку endpoint-ов генерирует трейт, клиента поверх общего
+/// `HttpClient` (повторы, таймауты, автомат хоста) и, в тестовой
+/// сборке, мок с тем же трейтом. Код, который ходит во внешний API,
+/// зависит от трейта, поэтому в тестах ему отдаётся мок и сеть не
+/// нужна.
+///
+/// ```ignore
+/// api_client! {
+///     /// Посты с jsonplaceholder.
+///     pub trait PostsApi;
+///     client PostClient;
+///     mock MockPostsApi;
+///     auth bearer;
+///
+///     /// Посты автора
+///     fn user_posts(query user_id: u32) -> Vec<Post> = GET "/posts";
+///     /// Пост по id
+///     fn post(path id: u32) -> Post = GET "/posts/{id}";
+///     fn create_post(body post: NewPost) -> Post = POST "/posts";
+/// }
+/// ```
+///
+/// Аргумент `path` подставляется в `{имя}` пути, `query` уходит в
+/// строку запроса (`None` пропускается), `body` — JSON-телом. `auth`
+/// необязателен: `bearer` или `header "X-Api-Key"`, секрет задаётся
+/// через `with_credentials`. Ответ с не-2xx статусом — ошибка
+/// `HttpError::Status`.
+///
+/// Мок хранит по полю на endpoint с тем же именем: ответ задаётся
+/// через `returns`, сделанные вызовы читаются через `calls`.
+macro_rules! api_client {
+    (
+        $(#[$meta:meta])*
+        $vis:vis trait $api:ident;
+        client $client:ident;
+        mock $mock:ident;
+        $(auth $auth:ident $($header:literal)?;)?
+        $(
+            $(#[$endpoint_meta:meta])*
+            fn $name:ident(
+                $($kind:ident $arg:ident: $arg_ty:ty),* $(,)?
+            ) -> $ret:ty = $method:ident $path:literal;
+        )*
+    ) => {
+        $(#[$meta])*
+        #[async_trait::async_trait]
+        $vis trait $api: Send + Sync {
+            $(
+                $(#[$endpoint_meta])*
+                async fn $name(
+                    &self,
+                    $($arg: $arg_ty),*
+                ) -> Result<$ret, $crate::infra::clients::http::HttpError>;
+            )*
+        }
+
+        $vis struct $client {
+            http: $crate::infra::clients::http::HttpClient,
+            base_url: String,
+            credentials: Option<String>,
+        }
+
+        impl $client {
+            const AUTH: $crate::infra::clients::api::Auth =
+                $crate::infra::clients::api::api_client!(
+                    @auth $($auth $($header)?)?
+                );
+
+            /// `base_url` без пути endpoint-а, например
+            /// `https://api.example.com/v1`.
+            pub fn new(
+                http: $crate::infra::clients::http::HttpClient,
+                base_url: impl Into<String>,
+            ) -> Self {
+                let base_url = base_url.into().trim_end_matches('/').to_string();
+                Self { http, base_url, credentials: None }
+            }
+
+            /// Секрет для заголовка из `auth`.
+            #[allow(dead_code)]
+            pub fn with_credentials(mut self, secret: impl Into<String>) -> Self {
+                self.credentials = Some(secret.into());
+                self
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $api for $client {
+            $(
+                async fn $name(
+                    &self,
+                    $($arg: $arg_ty),*
+                ) -> Result<$ret, $crate::infra::clients::http::HttpError> {
+                    #[allow(unused_mut)]
+                    let mut path = String::from($path);
+                    $($crate::infra::clients::api::api_client!(
+                        @path $kind path $arg
+                    );)*
+                    #[allow(unused_mut)]
+                    let mut request = self.http.request(
+                        reqwest::Method::$method,
+                        &format!("{}{}", self.base_url, path),
+                    );
+                    $(request = $crate::infra::clients::api::api_client!(
+                        @request $kind request $arg
+                    );)*
+                    let request =
+                        Self::AUTH.apply(request, self.credentials.as_deref());
+                    self.http.json(request).await
+                }
+            )*
+        }
+
+        /// Мок для тестов: ответ на каждый endpoint задаётся отдельно,
+        /// вызов без ответа — паника.
+        #[cfg(test)]
+        #[allow(dead_code)]
+        #[derive(Default)]
+        $vis struct $mock {
+            $(
+                pub $name: $crate::infra::clients::mock::MockEndpoint<
+                    ($($arg_ty,)*),
+                    $ret,
+                >,
+            )*
+        }
+
+        #[cfg(test)]
+        #[async_trait::async_trait]
+        impl $api for $mock {
+            $(
+                async fn $name(
+                    &self,
+                    $($arg: $arg_ty),*
+                ) -> Result<$ret, $crate::infra::clients::http::HttpError> {
+                    self.$name.call(
+                        concat!(stringify!($mock), "::", stringify!($name)),
+                        ($($arg,)*),
+                    )
+                }
+            )*
+        }
+    };
+
+    (@auth) => { $crate::infra::clients::api::Auth::None };
+    (@auth bearer) => { $crate::infra::clients::api::Auth::Bearer };
+    (@auth header $header:literal) => {
+        $crate::infra::clients::api::Auth::Header($header)
+    };
+
+    (@path path $path:ident $arg:ident) => {
+        $path = $path.replace(
+            concat!("{", stringify!($arg), "}"),
+            &$crate::infra::clients::api::path_segment(&$arg.to_string()),
+        )
+    };
+    (@path query $path:ident $arg:ident) => {};
+    (@path body $path:ident $arg:ident) => {};
+
+    (@request path $request:ident $arg:ident) => { $request };
+    (@request query $request:ident $arg:ident) => {
+        $request.query(&[(stringify!($arg), &$arg)])
+    };
+    (@request body $request:ident $arg:ident) => { $request.json(&$arg) };
+}
+
+pub(crate) use api_client;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::HttpClientConfig;
+    use crate::infra::clients::http::HttpClient;
+    use axum::Json;
+    use axum::http::{HeaderMap, Uri};
+    use serde::Deserialize;
+    use std::time::Duration;
+
+    /// Что тестовый сервер увидел в запросе.
+    #[derive(Debug, Deserialize)]
+    struct Echo {
+        uri: String,
+        authorization: Option<String>,
+        api_key: Option<String>,
+    }
+
+    api_client! {
+        trait EchoApi;
+        client BearerClient;
+        mock MockEchoApi;
+        auth bearer;
+
+        fn item(path id: String, query verbose: Option<bool>) -> Echo =
+            GET "/items/{id}";
+    }
+
+    api_client! {
+        trait KeyedApi;
+        client KeyedClient;
+        mock MockKeyedApi;
+        auth header "X-Api-Key";
+
+        fn search(query q: String) -> Echo = GET "/search";
+    }
+
+    fn http() -> HttpClient {
+        HttpClient::new(&HttpClientConfig {
+            connect_timeout: Duration::from_secs(1),
+            request_timeout: Duration::from_secs(1),
+            retries: 0,
+            retry_backoff: Duration::from_millis(10),
+            retry_max_delay: Duration::from_millis(10),
+            breaker_failures: 3,
+            breaker_open: Duration::from_secs(1),
+        })
+        .unwrap()
+    }
+
+    /// Поднимает сервер, который отвечает путём, строкой запроса и
+    /// заголовками авторизации. Возвращает базовый адрес с путём, чтобы
+    /// проверить, что он сохраняется.
+    async fn echo_server() -> String {
+        async fn echo(uri: Uri, headers: HeaderMap) -> Json<serde_json::Value> {
+            let header = |name: &str| {
+                headers
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(String::from)
+            };
+            Json(serde_json::json!({
+                "uri": uri.to_string(),
+                "authorization": header("authorization"),
+                "api_key": header("x-api-key"),
+            }))
+        }
+
+        let listener =
+            tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = axum::Router::new().fallback(echo);
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{addr}/v1/")
+    }
+
+    #[test]
+    fn path_segment_keeps_unreserved_and_encodes_the_rest() {
+        assert_eq!(path_segment("abc-1.2_~"), "abc-1.2_~");
+        assert_eq!(path_segment("a/b?c#d"), "a%2Fb%3Fc%23d");
+        assert_eq!(path_segment("a b%"), "a%20b%25");
+        assert_eq!(path_segment("ё"), "%D1%91");
+        assert_eq!(path_segment(".."), "..");
+    }
+
+    #[tokio::test]
+    async fn path_and_query_arguments_and_bearer_auth() {
+        let client = BearerClient::new(http(), echo_server().await)
+            .with_credentials("s3cret");
+
+        let echo = client.item("a/b?c".to_string(), Some(true)).await.unwrap();
+        assert_eq!(echo.uri, "/v1/items/a%2Fb%3Fc?verbose=true");
+        assert_eq!(echo.authorization.as_deref(), Some("Bearer s3cret"));
+        assert_eq!(echo.api_key, None);
+
+        // `None` в query пропускается целиком
+        let echo = client.item("42".to_string(), None).await.unwrap();
+        assert_eq!(echo.uri, "/v1/items/42");
+    }
+
+    #[tokio::test]
+    async fn header_auth_and_query_encoding() {
+        let base = echo_server().await;
+
+        let client =
+            KeyedClient::new(http(), base.clone()).with_credentials("key-1");
+        let echo = client.search("a&b=c d".to_string()).await.unwrap();
+        assert_eq!(echo.uri, "/v1/search?q=a%26b%3Dc+d");
+        assert_eq!(echo.api_key.as_deref(), Some("key-1"));
+        assert_eq!(echo.authorization, None);
+
+        // Без секрета запрос уходит без заголовка
+        let echo = KeyedClient::new(http(), base)
+            .search("x".to_string())
+            .await
+            .unwrap();
+        assert_eq!(echo.api_key, None);
+    }
+}
@@ -1,4 +1,4 @@
-use reqwest::{Error, get};
+use crate::infra::clients::api::api_client;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -7,13 +7,11 @@ pub struct Post {
     pub body: String,
 }
 
-pub struct PostClient {
-    pub url: String,
-}
+api_client! {
+    /// Посты с jsonplaceholder.
+    pub trait PostsApi;
+    client PostClient;
+    mock MockPostsApi;
 
-impl PostClient {
-    pub async fn get_posts(&self) -> Result<Vec<Post>, Error> {
-        let posts: Vec<Post> = get(&self.url).await?.json().await?;
-        Ok(posts)
-    }
+    fn list_posts() -> Vec<Post> = GET "/posts";
 }
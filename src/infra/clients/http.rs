@@ -0,0 +1,464 @@
+use crate::configs::HttpClientConfig;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, Request, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use thiserror::Error;
+use tokio::time::sleep;
+use tracing::field::Empty;
+use tracing::{Instrument, Span, debug, info, info_span, warn};
+
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("Invalid request: {0}")]
+    Build(#[source] reqwest::Error),
+
+    #[error("Circuit breaker for {host} is open")]
+    CircuitOpen { host: String },
+
+    #[error("Request to {url} failed: {source}")]
+    Transport {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("{url} responded with {status}")]
+    Status { url: String, status: StatusCode },
+
+    #[error("Cannot decode response from {url}: {source}")]
+    Decode {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+}
+
+/// Общий клиент для исходящих запросов к внешним API.
+///
+/// Один `reqwest::Client` с пулом соединений на весь сервис, с
+/// таймаутами на соединение и на попытку. Идемпотентные запросы
+/// повторяются после сетевой ошибки, 5xx и 429 с паузой со случайным
+/// разбросом; если сервер прислал `Retry-After`, ждём столько, сколько
+/// он просит, но не дольше `retry_max_delay`. Запросы, тело которых
+/// нельзя повторить (поток), отправляются один раз.
+///
+/// На каждый хост свой автомат: после `breaker_failures` сбоев подряд
+/// (сетевая ошибка или 5xx) запросы к хосту сразу получают
+/// `CircuitOpen`, пока через `breaker_open` один пробный запрос не
+/// пройдёт успешно. 429 автомат не открывает: хост жив, просто просит
+/// подождать.
+///
+/// Типизированные клиенты объявляются через `api_client!`: собирают
+/// запрос через `request` и отдают его в `json`.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: Client,
+    cfg: HttpClientConfig,
+    breakers: Arc<Mutex<HashMap<String, Breaker>>>,
+}
+
+impl HttpClient {
+    pub fn new(cfg: &HttpClientConfig) -> Result<Self, reqwest::Error> {
+        let client = Client::builder()
+            .connect_timeout(cfg.connect_timeout)
+            .timeout(cfg.request_timeout)
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .build()?;
+        Ok(Self {
+            client,
+            cfg: cfg.clone(),
+            breakers: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    pub fn request(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+    ) -> RequestBuilder {
+        self.client.request(method, url)
+    }
+
+    /// Отправляет запрос с повторами. Ответ с любым статусом — ошибка,
+    /// только если запрос не удалось отправить; статус проверяет
+    /// вызывающий или `json`.
+    pub async fn send(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<Response, HttpError> {
+        let request = request.build().map_err(HttpError::Build)?;
+        let url = request.url();
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or("")),
+            None => url.host_str().unwrap_or("").to_string(),
+        };
+        let span = info_span!(
+            "http_client",
+            method = %request.method(),
+            host = %host,
+            path = %url.path(),
+            attempts = Empty,
+            status = Empty,
+        );
+        self.send_with_retries(request, host).instrument(span).await
+    }
+
+    /// `send` и разбор JSON из успешного ответа.
+    pub async fn json<T: DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<T, HttpError> {
+        let response = self.send(request).await?;
+        let url = response.url().to_string();
+        let status = response.status();
+        if !status.is_success() {
+            return Err(HttpError::Status { url, status });
+        }
+        response
+            .json()
+            .await
+            .map_err(|source| HttpError::Decode { url, source })
+    }
+
+    async fn send_with_retries(
+        &self,
+        mut request: Request,
+        host: String,
+    ) -> Result<Response, HttpError> {
+        let url = request.url().to_string();
+        let idempotent = request.method().is_idempotent();
+        let started = Instant::now();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            self.before(&host)?;
+            // Копия на случай повтора; потоковое тело не копируется
+            let next = if idempotent && attempt <= self.cfg.retries {
+                request.try_clone()
+            } else {
+                None
+            };
+
+            let result = self.client.execute(request).await;
+            let open = self.record(&host, &result);
+            let retry = if open { None } else { retryable(&result) };
+            let Some((next, (retry_after, reason))) = next.zip(retry) else {
+                record_outcome(attempt, &result);
+                debug!("🌐 {url} done in {:?}", started.elapsed());
+                return result
+                    .map_err(|source| HttpError::Transport { url, source });
+            };
+            let delay = match retry_after {
+                Some(delay) if delay > self.cfg.retry_max_delay => {
+                    warn!(
+                        "⚠️ {url} asks to retry in {delay:?}, longer than allowed"
+                    );
+                    record_outcome(attempt, &result);
+                    return result.map_err(|source| HttpError::Transport {
+                        url,
+                        source,
+                    });
+                }
+                Some(delay) => delay,
+                None => self.backoff(attempt),
+            };
+            warn!(
+                "🔁 {url} attempt {attempt} failed: {reason}; retrying in {delay:?}"
+            );
+            sleep(delay).await;
+            request = next;
+        }
+    }
+
+    /// Случайная пауза от нуля до `retry_backoff * 2^(attempt-1)`, но
+    /// не больше `retry_max_delay`: повторы от разных инстансов не
+    /// приходят на хост разом.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << attempt.saturating_sub(1).min(16);
+        let ceiling = self
+            .cfg
+            .retry_backoff
+            .saturating_mul(factor)
+            .min(self.cfg.retry_max_delay);
+        ceiling.mul_f64(fastrand::f64())
+    }
+
+    /// Пропускает запрос, если автомат хоста закрыт или пора пробовать.
+    fn before(&self, host: &str) -> Result<(), HttpError> {
+        let mut breakers = self.breakers.lock().unwrap();
+        let breaker = breakers.entry(host.to_string()).or_default();
+        match breaker.state {
+            BreakerState::Closed => Ok(()),
+            BreakerState::Open { since } | BreakerState::HalfOpen { since }
+                if since.elapsed() >= self.cfg.breaker_open =>
+            {
+                // Пробный запрос; если он пропал без ответа, через
+                // `breaker_open` пойдёт следующий
+                breaker.state =
+                    BreakerState::HalfOpen { since: Instant::now() };
+                info!("🔌 Circuit breaker for {host} lets a probe through");
+                Ok(())
+            }
+            _ => Err(HttpError::CircuitOpen { host: host.to_string() }),
+        }
+    }
+
+    /// Учитывает итог попытки в автомате хоста. `true` — автомат
+    /// открыт, повторять бесполезно.
+    fn record(
+        &self,
+        host: &str,
+        result: &Result<Response, reqwest::Error>,
+    ) -> bool {
+        let failed = match result {
+            Ok(response) => response.status().is_server_error(),
+            // Любая ошибка без ответа: обрыв тела, редиректы и прочее
+            // во время сбоя хоста тоже сбой, а не повод закрыть автомат
+            Err(_) => true,
+        };
+        let mut breakers = self.breakers.lock().unwrap();
+        let breaker = breakers.entry(host.to_string()).or_default();
+        if !failed {
+            if !matches!(breaker.state, BreakerState::Closed) {
+                info!("🔌 Circuit breaker for {host} closed");
+            }
+            *breaker = Breaker::default();
+            return false;
+        }
+        breaker.failures += 1;
+        match breaker.state {
+            BreakerState::HalfOpen { .. } => {
+                breaker.state = BreakerState::Open { since: Instant::now() };
+                warn!("🔌 Circuit breaker for {host} reopened: probe failed");
+            }
+            BreakerState::Closed
+                if breaker.failures >= self.cfg.breaker_failures =>
+            {
+                breaker.state = BreakerState::Open { since: Instant::now() };
+                warn!(
+                    "🔌 Circuit breaker for {host} opened after {} failures",
+                    breaker.failures
+                );
+            }
+            _ => {}
+        }
+        matches!(breaker.state, BreakerState::Open { .. })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Breaker {
+    /// Сбоев подряд
+    failures: u32,
+    state: BreakerState,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+enum BreakerState {
+    #[default]
+    Closed,
+    /// Запросы к хосту не отправляются
+    Open { since: Instant },
+    /// Пробный запрос в пути, остальные ждут его итога
+    HalfOpen { since: Instant },
+}
+
+/// Итог вызова в полях span-а: сколько было попыток и последний статус.
+fn record_outcome(attempts: u32, result: &Result<Response, reqwest::Error>) {
+    let span = Span::current();
+    span.record("attempts", attempts);
+    if let Ok(response) = result {
+        span.record("status", response.status().as_u16());
+    }
+}
+
+/// Стоит ли повторять: пауза из `Retry-After`, если есть, и причина
+/// для лога.
+fn retryable(
+    result: &Result<Response, reqwest::Error>,
+) -> Option<(Option<Duration>, String)> {
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            (status.is_server_error()
+                || status == StatusCode::TOO_MANY_REQUESTS)
+                .then(|| (retry_after(response), status.to_string()))
+        }
+        Err(e) => (e.is_connect() || e.is_timeout() || e.is_request())
+            .then(|| (None, e.to_string())),
+    }
+}
+
+/// `Retry-After` в секундах или HTTP-датой.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = httpdate::parse_http_date(value).ok()?;
+    Some(at.duration_since(SystemTime::now()).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BREAKER_OPEN: Duration = Duration::from_millis(50);
+
+    fn client() -> HttpClient {
+        HttpClient::new(&HttpClientConfig {
+            connect_timeout: Duration::from_secs(1),
+            request_timeout: Duration::from_secs(1),
+            retries: 2,
+            retry_backoff: Duration::from_millis(200),
+            retry_max_delay: Duration::from_secs(1),
+            breaker_failures: 3,
+            breaker_open: BREAKER_OPEN,
+        })
+        .unwrap()
+    }
+
+    fn response(status: u16, retry_after: Option<&str>) -> Response {
+        let mut builder = axum::http::Response::builder().status(status);
+        if let Some(value) = retry_after {
+            builder = builder.header(RETRY_AFTER, value);
+        }
+        Response::from(builder.body("").unwrap())
+    }
+
+    fn is_open(client: &HttpClient) -> bool {
+        matches!(client.before("api"), Err(HttpError::CircuitOpen { .. }))
+    }
+
+    #[test]
+    fn breaker_opens_after_consecutive_failures() {
+        let client = client();
+        assert!(!client.record("api", &Ok(response(500, None))));
+        // Успех обнуляет счётчик
+        assert!(!client.record("api", &Ok(response(200, None))));
+        assert!(!client.record("api", &Ok(response(502, None))));
+        assert!(!client.record("api", &Ok(response(503, None))));
+        assert!(!is_open(&client));
+        assert!(client.record("api", &Ok(response(500, None))));
+        assert!(is_open(&client));
+        // Автоматы у хостов свои
+        assert!(client.before("other").is_ok());
+    }
+
+    #[test]
+    fn too_many_requests_and_client_errors_keep_breaker_closed() {
+        let client = client();
+        for _ in 0..10 {
+            assert!(!client.record("api", &Ok(response(429, None))));
+            assert!(!client.record("api", &Ok(response(404, None))));
+        }
+        assert!(!is_open(&client));
+    }
+
+    #[test]
+    fn half_open_lets_one_probe_and_reopens_on_failure() {
+        let client = client();
+        for _ in 0..3 {
+            client.record("api", &Ok(response(500, None)));
+        }
+        assert!(is_open(&client));
+
+        std::thread::sleep(BREAKER_OPEN);
+        assert!(client.before("api").is_ok());
+        // Пока проба в пути, остальные не проходят
+        assert!(is_open(&client));
+        assert!(client.record("api", &Ok(response(500, None))));
+        assert!(is_open(&client));
+
+        std::thread::sleep(BREAKER_OPEN);
+        assert!(client.before("api").is_ok());
+        assert!(!client.record("api", &Ok(response(200, None))));
+        assert!(client.before("api").is_ok());
+        // После закрытия снова нужно `breaker_failures` сбоев подряд
+        assert!(!client.record("api", &Ok(response(500, None))));
+        assert!(!is_open(&client));
+    }
+
+    /// Ошибка reqwest, которая не про соединение и не про таймаут.
+    fn other_error() -> reqwest::Error {
+        Client::new().get("http://[::1").build().unwrap_err()
+    }
+
+    #[test]
+    fn every_transport_error_counts_as_failure() {
+        let client = client();
+        let error = other_error();
+        assert!(!error.is_connect() && !error.is_timeout());
+
+        assert!(!client.record("api", &Ok(response(500, None))));
+        assert!(!client.record("api", &Err(error)));
+        // Счётчик не сброшен ошибкой, третий сбой открывает автомат
+        assert!(client.record("api", &Ok(response(500, None))));
+        assert!(is_open(&client));
+
+        // Проба, упавшая без ответа, снова открывает автомат
+        std::thread::sleep(BREAKER_OPEN);
+        assert!(client.before("api").is_ok());
+        assert!(client.record("api", &Err(other_error())));
+        assert!(is_open(&client));
+    }
+
+    #[test]
+    fn retry_after_in_seconds_or_http_date() {
+        assert_eq!(
+            retry_after(&response(429, Some("7"))),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            retry_after(&response(503, Some(" 0 "))),
+            Some(Duration::ZERO)
+        );
+
+        let at = SystemTime::now() + Duration::from_secs(120);
+        let date = httpdate::fmt_http_date(at);
+        let delay = retry_after(&response(503, Some(&date))).unwrap();
+        assert!(delay > Duration::from_secs(110));
+        assert!(delay <= Duration::from_secs(120));
+
+        // Дата в прошлом — повторять можно сразу
+        let past = httpdate::fmt_http_date(SystemTime::UNIX_EPOCH);
+        assert_eq!(
+            retry_after(&response(503, Some(&past))),
+            Some(Duration::ZERO)
+        );
+
+        assert_eq!(retry_after(&response(503, Some("soon"))), None);
+        assert_eq!(retry_after(&response(503, Some("-1"))), None);
+        assert_eq!(retry_after(&response(503, None)), None);
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        assert!(retryable(&Ok(response(500, None))).is_some());
+        assert!(retryable(&Ok(response(429, None))).is_some());
+        assert!(retryable(&Ok(response(404, None))).is_none());
+        assert!(retryable(&Ok(response(200, None))).is_none());
+        let (delay, _) = retryable(&Ok(response(429, Some("3")))).unwrap();
+        assert_eq!(delay, Some(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn backoff_stays_within_doubling_ceiling() {
+        let client = client();
+        for _ in 0..200 {
+            assert!(client.backoff(1) <= Duration::from_millis(200));
+            assert!(client.backoff(2) <= Duration::from_millis(400));
+            assert!(client.backoff(3) <= Duration::from_millis(800));
+            // Дальше упирается в `retry_max_delay`
+            assert!(client.backoff(4) <= Duration::from_secs(1));
+            assert!(client.backoff(u32::MAX) <= Duration::from_secs(1));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Моки клиентов внешних API из `api_client!`. Есть только в тестовой
+//! сборке; тест настраивает лишь те endpoint-ы, которые трогает.
+
+use crate::infra::clients::http::HttpError;
+use std::sync::Mutex;
+
+type Responder<A, R> = Box<dyn Fn(A) -> Result<R, HttpError> + Send + Sync>;
+
+/// Один endpoint мока: заданный ответ и аргументы сделанных вызовов.
+pub struct MockEndpoint<A, R> {
+    responder: Mutex<Option<Responder<A, R>>>,
+    calls: Mutex<Vec<A>>,
+}
+
+impl<A, R> Default for MockEndpoint<A, R> {
+    fn default() -> Self {
+        Self { responder: Mutex::new(None), calls: Mutex::new(Vec::new()) }
+    }
+}
+
+impl<A: Clone, R> MockEndpoint<A, R> {
+    /// Ответ на все следующие вызовы; аргументы приходят кортежем.
+    pub fn returns(
+        &self,
+        responder: impl Fn(A) -> Result<R, HttpError> + Send + Sync + 'static,
+    ) {
+        *self.responder.lock().unwrap() = Some(Box::new(responder));
+    }
+
+    pub fn calls(&self) -> Vec<A> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    pub fn call(&self, name: &str, args: A) -> Result<R, HttpError> {
+        self.calls.lock().unwrap().push(args.clone());
+        let responder = self.responder.lock().unwrap();
+        let responder = responder
+            .as_ref()
+            .unwrap_or_else(|| panic!("{name} called without a response"));
+        responder(args)
+    }
+}
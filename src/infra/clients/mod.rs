@@ -1 +1,5 @@
+pub mod api;
 pub mod client;
+pub mod http;
+#[cfg(test)]
+pub mod mock;
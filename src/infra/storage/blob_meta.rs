@@ -0,0 +1,45 @@
+use crate::infra::storage::entities::blob;
+use crate::infra::storage::tx::Conn;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, Set};
+
+#[derive(Clone)]
+pub struct BlobRepository {
+    db: DatabaseConnection,
+}
+
+impl BlobRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn conn(&self) -> Conn<'_> {
+        Conn::current(&self.db)
+    }
+
+    pub async fn create(
+        &self,
+        owner_id: i32,
+        file_name: String,
+        content_type: String,
+        size: i64,
+        sha256: String,
+    ) -> Result<blob::Model, DbErr> {
+        blob::ActiveModel {
+            owner_id: Set(owner_id),
+            file_name: Set(file_name),
+            content_type: Set(content_type),
+            size: Set(size),
+            sha256: Set(sha256),
+            ..Default::default()
+        }
+        .insert(&self.conn())
+        .await
+    }
+
+    pub async fn get_by_id(
+        &self,
+        id: i32,
+    ) -> Result<Option<blob::Model>, DbErr> {
+        blob::Entity::find_by_id(id).one(&self.conn()).await
+    }
+}
@@ -0,0 +1,84 @@
+use crate::infra::storage::blobs::{
+    BlobError, BlobStore, BlobStream, ByteRange,
+};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+/// Объекты в каталоге на диске: `root/ab/cd/abcd…`, чтобы в одном
+/// каталоге не копились миллионы файлов.
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let mut path = self.root.clone();
+        if key.len() > 4 {
+            path.push(&key[..2]);
+            path.push(&key[2..4]);
+        }
+        path.push(key);
+        path
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put_file(
+        &self,
+        key: &str,
+        source: &Path,
+        _size: u64,
+        _content_type: &str,
+    ) -> Result<(), BlobError> {
+        let target = self.path(key);
+        if fs::try_exists(&target).await? {
+            return Ok(());
+        }
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        // Копируем во временный файл рядом и переименовываем: читатель
+        // никогда не увидит недописанный объект
+        let partial =
+            target.with_extension(format!("partial-{}", Uuid::new_v4()));
+        fs::copy(source, &partial).await?;
+        fs::rename(&partial, &target).await?;
+        Ok(())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, BlobError> {
+        Ok(fs::try_exists(self.path(key)).await?)
+    }
+
+    async fn get(
+        &self,
+        key: &str,
+        range: Option<ByteRange>,
+    ) -> Result<BlobStream, BlobError> {
+        let mut file = match fs::File::open(self.path(key)).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(BlobError::NotFound(key.to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        match range {
+            Some(range) => {
+                file.seek(SeekFrom::Start(range.start)).await?;
+                Ok(ReaderStream::new(file.take(range.len())).boxed())
+            }
+            None => Ok(ReaderStream::new(file).boxed()),
+        }
+    }
+}
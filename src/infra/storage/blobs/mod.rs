@@ -0,0 +1,78 @@
+pub mod local;
+pub mod s3;
+
+use crate::configs::BlobStoreConfig;
+use crate::infra::clients::http::HttpClient;
+use crate::infra::storage::blobs::local::LocalBlobStore;
+use crate::infra::storage::blobs::s3::S3BlobStore;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::stream::BoxStream;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Содержимое объекта, отдаётся по частям.
+pub type BlobStream = BoxStream<'static, io::Result<Bytes>>;
+
+#[derive(Debug, Error)]
+pub enum BlobError {
+    #[error("Blob {0} not found")]
+    NotFound(String),
+
+    #[error("Blob storage I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Blob storage backend error: {0}")]
+    Backend(String),
+}
+
+/// Диапазон байт `[start, end]` включительно, как в `Content-Range`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// Хранилище содержимого файлов. Ключи — hex sha256 содержимого,
+/// поэтому объект под ключом никогда не меняется.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Кладёт файл с диска под `key`; повторная запись того же ключа
+    /// допустима и ничего не меняет.
+    async fn put_file(
+        &self,
+        key: &str,
+        path: &Path,
+        size: u64,
+        content_type: &str,
+    ) -> Result<(), BlobError>;
+
+    async fn exists(&self, key: &str) -> Result<bool, BlobError>;
+
+    /// Содержимое целиком или только `range`.
+    async fn get(
+        &self,
+        key: &str,
+        range: Option<ByteRange>,
+    ) -> Result<BlobStream, BlobError>;
+}
+
+/// S3 ходит через общий `http`: тот же пул соединений, повторы и
+/// автомат хоста, что и у остальных исходящих запросов.
+pub fn from_config(
+    cfg: &BlobStoreConfig,
+    http: HttpClient,
+) -> Arc<dyn BlobStore> {
+    match cfg {
+        BlobStoreConfig::Local { root } => Arc::new(LocalBlobStore::new(root)),
+        BlobStoreConfig::S3(s3) => Arc::new(S3BlobStore::new(s3.clone(), http)),
+    }
+}
@@ -0,0 +1,278 @@
+use crate::configs::S3Config;
+use crate::infra::clients::http::{HttpClient, HttpError};
+use crate::infra::storage::blobs::{
+    BlobError, BlobStore, BlobStream, ByteRange,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use futures_util::{StreamExt, TryStreamExt};
+use hmac::{Hmac, Mac};
+use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE, HOST, RANGE};
+use reqwest::{Body, Method, RequestBuilder, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+use tokio_util::io::ReaderStream;
+
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+/// Таймаут передачи содержимого: таймаут общего клиента рассчитан на
+/// короткие ответы API, а файл может идти долго.
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// S3-совместимое хранилище (AWS S3, MinIO, Ceph RGW): path-style адреса
+/// `{endpoint}/{bucket}/{key}` и подпись запросов AWS Signature V4.
+pub struct S3BlobStore {
+    cfg: S3Config,
+    http: HttpClient,
+}
+
+impl S3BlobStore {
+    pub fn new(cfg: S3Config, http: HttpClient) -> Self {
+        Self { cfg, http }
+    }
+
+    fn url(&self, key: &str) -> Result<Url, BlobError> {
+        let raw = format!(
+            "{}/{}/{}",
+            self.cfg.endpoint.trim_end_matches('/'),
+            uri_encode(&self.cfg.bucket),
+            uri_encode(key)
+        );
+        Url::parse(&raw).map_err(|e| BlobError::Backend(e.to_string()))
+    }
+
+    /// Запрос с заголовками подписи SigV4. Тело не хешируем
+    /// (`UNSIGNED-PAYLOAD`), чтобы отправлять файл потоком.
+    fn signed(&self, method: Method, url: Url) -> RequestBuilder {
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let host = match url.port() {
+            Some(port) => format!("{}:{port}", url.host_str().unwrap_or("")),
+            None => url.host_str().unwrap_or("").to_string(),
+        };
+
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{method}\n{path}\n{query}\nhost:{host}\nx-amz-content-sha256:{UNSIGNED_PAYLOAD}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{UNSIGNED_PAYLOAD}",
+            path = url.path(),
+            query = url.query().unwrap_or(""),
+        );
+        let scope = format!("{date}/{}/s3/aws4_request", self.cfg.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let key =
+            [date.as_str(), self.cfg.region.as_str(), "s3", "aws4_request"]
+                .iter()
+                .fold(
+                    format!("AWS4{}", self.cfg.secret_key).into_bytes(),
+                    |key, part| hmac_sha256(&key, part.as_bytes()),
+                );
+        let signature =
+            hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            self.cfg.access_key
+        );
+
+        self.http
+            .request(method, url.as_str())
+            .header(HOST, host)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", UNSIGNED_PAYLOAD)
+            .header("authorization", authorization)
+    }
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put_file(
+        &self,
+        key: &str,
+        path: &Path,
+        size: u64,
+        content_type: &str,
+    ) -> Result<(), BlobError> {
+        let file = tokio::fs::File::open(path).await?;
+        let request = self
+            .signed(Method::PUT, self.url(key)?)
+            .timeout(TRANSFER_TIMEOUT)
+            .header(CONTENT_TYPE, content_type)
+            .header(CONTENT_LENGTH, size)
+            .body(Body::wrap_stream(ReaderStream::new(file)));
+        let response = self.http.send(request).await.map_err(backend)?;
+        check(response, key).await.map(drop)
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, BlobError> {
+        let response = self
+            .http
+            .send(self.signed(Method::HEAD, self.url(key)?))
+            .await
+            .map_err(backend)?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(false),
+            status if status.is_success() => Ok(true),
+            status => Err(BlobError::Backend(format!("HEAD {key}: {status}"))),
+        }
+    }
+
+    async fn get(
+        &self,
+        key: &str,
+        range: Option<ByteRange>,
+    ) -> Result<BlobStream, BlobError> {
+        let mut request =
+            self.signed(Method::GET, self.url(key)?).timeout(TRANSFER_TIMEOUT);
+        if let Some(range) = range {
+            request = request
+                .header(RANGE, format!("bytes={}-{}", range.start, range.end));
+        }
+        let response =
+            check(self.http.send(request).await.map_err(backend)?, key).await?;
+        Ok(response.bytes_stream().map_err(io::Error::other).boxed())
+    }
+}
+
+async fn check(
+    response: reqwest::Response,
+    key: &str,
+) -> Result<reqwest::Response, BlobError> {
+    match response.status() {
+        status if status.is_success() => Ok(response),
+        StatusCode::NOT_FOUND => Err(BlobError::NotFound(key.to_string())),
+        status => {
+            let body = response.text().await.unwrap_or_default();
+            Err(BlobError::Backend(format!("{key}: {status} {body}")))
+        }
+    }
+}
+
+fn backend(e: HttpError) -> BlobError {
+    BlobError::Backend(e.to_string())
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// URI-encoding сегмента пути по правилам SigV4: всё, кроме
+/// unreserved-символов, кодируется как `%XX`.
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'~' => (b as char).to_string(),
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// Проверка против настоящего S3-совместимого хранилища. По умолчанию
+/// пропускается; запуск с MinIO из `postgresql/postgresql.yml`:
+///
+/// ```sh
+/// docker compose -f postgresql/postgresql.yml --profile s3 up -d minio
+/// S3_TEST_ENDPOINT=http://localhost:9000 cargo test s3 -- --ignored
+/// ```
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::HttpClientConfig;
+    use std::env::var;
+
+    fn store() -> S3BlobStore {
+        let endpoint = var("S3_TEST_ENDPOINT")
+            .expect("S3_TEST_ENDPOINT must be set for S3 tests");
+        let http = HttpClient::new(&HttpClientConfig {
+            connect_timeout: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(30),
+            retries: 1,
+            retry_backoff: Duration::from_millis(100),
+            retry_max_delay: Duration::from_secs(1),
+            breaker_failures: 5,
+            breaker_open: Duration::from_secs(30),
+        })
+        .unwrap();
+        S3BlobStore::new(
+            S3Config {
+                endpoint,
+                bucket: var("S3_TEST_BUCKET")
+                    .unwrap_or("blobs-test".to_string()),
+                region: "us-east-1".to_string(),
+                access_key: var("S3_TEST_ACCESS_KEY")
+                    .unwrap_or("minioadmin".to_string()),
+                secret_key: var("S3_TEST_SECRET_KEY")
+                    .unwrap_or("minioadmin".to_string()),
+            },
+            http,
+        )
+    }
+
+    async fn create_bucket(store: &S3BlobStore) {
+        let url = Url::parse(&format!(
+            "{}/{}",
+            store.cfg.endpoint.trim_end_matches('/'),
+            uri_encode(&store.cfg.bucket)
+        ))
+        .unwrap();
+        let response =
+            store.http.send(store.signed(Method::PUT, url)).await.unwrap();
+        // 409 — бакет остался от прошлого запуска
+        assert!(
+            response.status().is_success()
+                || response.status() == StatusCode::CONFLICT,
+            "create bucket: {}",
+            response.status()
+        );
+    }
+
+    async fn read(stream: BlobStream) -> Vec<u8> {
+        stream.map_ok(|chunk| chunk.to_vec()).try_concat().await.unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs S3_TEST_ENDPOINT with MinIO or another S3"]
+    async fn put_get_and_exists_round_trip() {
+        let store = store();
+        create_bucket(&store).await;
+
+        let content = b"0123456789 s3 round trip".to_vec();
+        let key = format!("test/{} + name.txt", uuid::Uuid::new_v4());
+        let path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        tokio::fs::write(&path, &content).await.unwrap();
+        let put = store
+            .put_file(&key, &path, content.len() as u64, "text/plain")
+            .await;
+        tokio::fs::remove_file(&path).await.unwrap();
+        put.unwrap();
+
+        assert!(store.exists(&key).await.unwrap());
+        assert_eq!(read(store.get(&key, None).await.unwrap()).await, content);
+        let range = ByteRange { start: 2, end: 5 };
+        assert_eq!(
+            read(store.get(&key, Some(range)).await.unwrap()).await,
+            b"2345"
+        );
+
+        let missing = format!("test/{}", uuid::Uuid::new_v4());
+        assert!(!store.exists(&missing).await.unwrap());
+        assert!(matches!(
+            store.get(&missing, None).await,
+            Err(BlobError::NotFound(_))
+        ));
+    }
+}
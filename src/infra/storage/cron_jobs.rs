@@ -0,0 +1,91 @@
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, DbErr, Statement,
+};
+
+/// Изменения задачи, сделанные через API поверх `CRON_JOBS_FILE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronJobState {
+    pub job_name: String,
+    pub paused: bool,
+    /// `None` — расписание из конфига
+    pub schedule: Option<String>,
+    pub timezone: Option<String>,
+}
+
+/// Состояние задач cron в таблице `cron_job_state`, общей для всех
+/// инстансов: пауза и расписание, заданное вместо конфига. Строки нет,
+/// пока задачу ни разу не меняли.
+#[derive(Clone)]
+pub struct CronJobStateRepository {
+    db: DatabaseConnection,
+}
+
+impl CronJobStateRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn all(&self) -> Result<Vec<CronJobState>, DbErr> {
+        let rows = self
+            .db
+            .query_all(Statement::from_string(
+                DbBackend::Postgres,
+                "SELECT job_name, paused, schedule, timezone FROM cron_job_state",
+            ))
+            .await?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(CronJobState {
+                    job_name: row.try_get("", "job_name")?,
+                    paused: row.try_get("", "paused")?,
+                    schedule: row.try_get("", "schedule")?,
+                    timezone: row.try_get("", "timezone")?,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn set_paused(
+        &self,
+        job_name: &str,
+        paused: bool,
+    ) -> Result<(), DbErr> {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                INSERT INTO cron_job_state (job_name, paused, updated_at)
+                VALUES ($1, $2, now())
+                ON CONFLICT (job_name) DO UPDATE
+                SET paused = EXCLUDED.paused, updated_at = EXCLUDED.updated_at
+                "#,
+                [job_name.into(), paused.into()],
+            ))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_schedule(
+        &self,
+        job_name: &str,
+        schedule: &str,
+        timezone: &str,
+    ) -> Result<(), DbErr> {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                INSERT INTO cron_job_state
+                    (job_name, paused, schedule, timezone, updated_at)
+                VALUES ($1, false, $2, $3, now())
+                ON CONFLICT (job_name) DO UPDATE
+                SET schedule = EXCLUDED.schedule,
+                    timezone = EXCLUDED.timezone,
+                    updated_at = EXCLUDED.updated_at
+                "#,
+                [job_name.into(), schedule.into(), timezone.into()],
+            ))
+            .await?;
+        Ok(())
+    }
+}
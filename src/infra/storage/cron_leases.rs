@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, DbErr, Statement,
+};
+use std::time::Duration;
+
+/// Чем закончилась попытка взять срабатывание задачи.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Claim {
+    /// Срабатывание наше, можно выполнять
+    Acquired,
+    /// Это или более позднее срабатывание уже взял `holder`
+    Taken { holder: String },
+    /// Предыдущий запуск ещё держит аренду: идёт или его держатель
+    /// упал, и аренда не истекла
+    Busy { holder: String, scheduled_at: DateTime<Utc> },
+}
+
+/// Аренды задач cron в таблице `cron_lease`, общей для всех инстансов.
+///
+/// На задачу одна строка: кто и какое срабатывание взял и до какого
+/// времени держит аренду. Срабатывание можно взять, только если оно
+/// новее записанного и прошлая аренда отпущена или истекла. Проверка
+/// и захват — один `INSERT ... ON CONFLICT`, поэтому из нескольких
+/// инстансов, сработавших одновременно, выигрывает ровно один. Время
+/// аренды считается по часам базы.
+#[derive(Clone)]
+pub struct CronLeaseRepository {
+    db: DatabaseConnection,
+}
+
+impl CronLeaseRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn acquire(
+        &self,
+        job_name: &str,
+        holder: &str,
+        scheduled_at: DateTime<Utc>,
+        ttl: Duration,
+    ) -> Result<Claim, DbErr> {
+        let acquired = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                INSERT INTO cron_lease
+                    (job_name, holder, scheduled_at, acquired_at, expires_at)
+                VALUES ($1, $2, $3, now(), now() + $4 * interval '1 millisecond')
+                ON CONFLICT (job_name) DO UPDATE
+                SET holder = EXCLUDED.holder,
+                    scheduled_at = EXCLUDED.scheduled_at,
+                    acquired_at = EXCLUDED.acquired_at,
+                    expires_at = EXCLUDED.expires_at
+                WHERE cron_lease.scheduled_at < EXCLUDED.scheduled_at
+                  AND cron_lease.expires_at <= now()
+                RETURNING job_name
+                "#,
+                [
+                    job_name.into(),
+                    holder.into(),
+                    scheduled_at.into(),
+                    millis(ttl).into(),
+                ],
+            ))
+            .await?;
+        if acquired.is_some() {
+            return Ok(Claim::Acquired);
+        }
+
+        let current = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT holder, scheduled_at FROM cron_lease WHERE job_name = $1",
+                [job_name.into()],
+            ))
+            .await?
+            .ok_or_else(|| {
+                DbErr::RecordNotFound(format!("cron lease {job_name}"))
+            })?;
+        let current_holder: String = current.try_get("", "holder")?;
+        let current_at: DateTime<Utc> = current.try_get("", "scheduled_at")?;
+        Ok(if current_at >= scheduled_at {
+            Claim::Taken { holder: current_holder }
+        } else {
+            Claim::Busy { holder: current_holder, scheduled_at: current_at }
+        })
+    }
+
+    /// Продлевает аренду идущего запуска. `false` — аренда уже не наша.
+    pub async fn renew(
+        &self,
+        job_name: &str,
+        holder: &str,
+        scheduled_at: DateTime<Utc>,
+        ttl: Duration,
+    ) -> Result<bool, DbErr> {
+        let renewed = self
+            .db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                UPDATE cron_lease
+                SET expires_at = now() + $4 * interval '1 millisecond'
+                WHERE job_name = $1 AND holder = $2 AND scheduled_at = $3
+                "#,
+                [
+                    job_name.into(),
+                    holder.into(),
+                    scheduled_at.into(),
+                    millis(ttl).into(),
+                ],
+            ))
+            .await?;
+        Ok(renewed.rows_affected() > 0)
+    }
+
+    /// Отпускает аренду после запуска: следующее срабатывание можно
+    /// брать сразу, не дожидаясь TTL.
+    pub async fn release(
+        &self,
+        job_name: &str,
+        holder: &str,
+        scheduled_at: DateTime<Utc>,
+    ) -> Result<(), DbErr> {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                UPDATE cron_lease SET expires_at = now()
+                WHERE job_name = $1 AND holder = $2 AND scheduled_at = $3
+                "#,
+                [job_name.into(), holder.into(), scheduled_at.into()],
+            ))
+            .await?;
+        Ok(())
+    }
+}
+
+fn millis(ttl: Duration) -> i64 {
+    i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX)
+}
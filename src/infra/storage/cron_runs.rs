@@ -0,0 +1,150 @@
+use crate::infra::storage::entities::cron_run;
+use crate::infra::storage::tx::Conn;
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection,
+    DbBackend, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    Statement,
+};
+
+/// Итог запуска в `cron_runs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    Running,
+    Succeeded,
+    Failed,
+    /// Последняя попытка не уложилась в `timeout_secs`
+    TimedOut,
+    /// Инстанс остановился посреди запуска
+    Abandoned,
+}
+
+impl RunOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunOutcome::Running => "running",
+            RunOutcome::Succeeded => "succeeded",
+            RunOutcome::Failed => "failed",
+            RunOutcome::TimedOut => "timed_out",
+            RunOutcome::Abandoned => "abandoned",
+        }
+    }
+}
+
+/// История запусков задач cron в таблице `cron_runs`.
+#[derive(Clone)]
+pub struct CronRunRepository {
+    db: DatabaseConnection,
+}
+
+impl CronRunRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn conn(&self) -> Conn<'_> {
+        Conn::current(&self.db)
+    }
+
+    /// Записывает начало запуска, возвращает id строки.
+    pub async fn start(
+        &self,
+        job_name: &str,
+        scheduled_at: DateTime<Utc>,
+        instance: &str,
+    ) -> Result<i32, DbErr> {
+        let run = cron_run::ActiveModel {
+            job_name: Set(job_name.to_string()),
+            scheduled_at: Set(scheduled_at.into()),
+            instance: Set(instance.to_string()),
+            started_at: Set(Utc::now().into()),
+            finished_at: Set(None),
+            outcome: Set(RunOutcome::Running.as_str().to_string()),
+            attempts: Set(0),
+            error: Set(None),
+            ..Default::default()
+        }
+        .insert(&self.conn())
+        .await?;
+        Ok(run.id)
+    }
+
+    pub async fn finish(
+        &self,
+        id: i32,
+        outcome: RunOutcome,
+        attempts: u32,
+        error: Option<String>,
+    ) -> Result<(), DbErr> {
+        cron_run::ActiveModel {
+            id: Set(id),
+            finished_at: Set(Some(Utc::now().into())),
+            outcome: Set(outcome.as_str().to_string()),
+            attempts: Set(i32::try_from(attempts).unwrap_or(i32::MAX)),
+            error: Set(error),
+            ..Default::default()
+        }
+        .update(&self.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Самое позднее срабатывание задачи, которое хоть раз запускалось,
+    /// с любым итогом.
+    pub async fn last_scheduled(
+        &self,
+        job_name: &str,
+    ) -> Result<Option<DateTime<Utc>>, DbErr> {
+        let last: Option<DateTime<Utc>> = cron_run::Entity::find()
+            .select_only()
+            .column(cron_run::Column::ScheduledAt)
+            .filter(cron_run::Column::JobName.eq(job_name))
+            .order_by_desc(cron_run::Column::ScheduledAt)
+            .limit(1)
+            .into_tuple()
+            .one(&self.conn())
+            .await?;
+        Ok(last)
+    }
+
+    /// Последний начатый запуск задачи.
+    pub async fn last(
+        &self,
+        job_name: &str,
+    ) -> Result<Option<cron_run::Model>, DbErr> {
+        cron_run::Entity::find()
+            .filter(cron_run::Column::JobName.eq(job_name))
+            .order_by_desc(cron_run::Column::StartedAt)
+            .one(&self.conn())
+            .await
+    }
+
+    /// Запуски, которые остались `running`, хотя аренды у их инстанса
+    /// уже нет: инстанс упал посреди запуска.
+    pub async fn abandon_stale(&self) -> Result<u64, DbErr> {
+        let abandoned = self
+            .db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                UPDATE cron_runs r
+                SET outcome = $1, finished_at = now(),
+                    error = 'Instance stopped during the run'
+                WHERE r.outcome = $2
+                  AND NOT EXISTS (
+                      SELECT 1 FROM cron_lease l
+                      WHERE l.job_name = r.job_name
+                        AND l.holder = r.instance
+                        AND l.scheduled_at = r.scheduled_at
+                        AND l.expires_at > now()
+                  )
+                "#,
+                [
+                    RunOutcome::Abandoned.as_str().into(),
+                    RunOutcome::Running.as_str().into(),
+                ],
+            ))
+            .await?;
+        Ok(abandoned.rows_affected())
+    }
+}
@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+
+/// Запуск задачи cron: одна строка на выполненное срабатывание, с
+/// учётом всех повторов.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "cron_runs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub job_name: String,
+    pub scheduled_at: DateTimeWithTimeZone,
+    pub instance: String,
+    pub started_at: DateTimeWithTimeZone,
+    pub finished_at: Option<DateTimeWithTimeZone>,
+    /// `running`, `succeeded`, `failed`, `timed_out` или `abandoned`
+    pub outcome: String,
+    pub attempts: i32,
+    pub error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
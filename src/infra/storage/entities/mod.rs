@@ -0,0 +1,7 @@
+pub mod blob;
+pub mod cron_run;
+pub mod game_match;
+pub mod match_player;
+pub mod player_rating;
+pub mod post;
+pub mod world_snapshot;
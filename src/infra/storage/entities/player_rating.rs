@@ -0,0 +1,17 @@
+use sea_orm::entity::prelude::*;
+
+/// Рейтинг игрока для подбора; строки нет, пока игрок не сыграл матч.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "player_rating")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i32,
+    pub rating: i32,
+    pub matches_played: i32,
+    pub updated_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
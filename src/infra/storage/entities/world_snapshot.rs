@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+
+/// Сохранённое состояние общего мира. `data` — мир в `encoding`,
+/// `checksum` — `World::checksum()` до кодирования.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "world_snapshot")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub tick: i64,
+    pub players: i32,
+    pub encoding: String,
+    pub checksum: String,
+    pub data: Vec<u8>,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,196 @@
+use crate::core::listing::{
+    Cursor, FieldKind, FieldSpec, Filter, FilterOp, FilterValue, ListRequest,
+    Page, Pagination, SortDirection,
+};
+use sea_orm::sea_query::SimpleExpr;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, ModelTrait,
+    Order, QueryFilter, QueryOrder, QuerySelect, Select, Value,
+};
+use std::str::FromStr;
+
+/// Применяет фильтры, сортировку и пагинацию из `ListRequest` к выборке.
+///
+/// В режиме курсора используется keyset-пагинация по полям сортировки
+/// (последнее из них всегда уникальное), поэтому вставки и удаления между
+/// запросами не сдвигают страницы.
+pub async fn fetch_page<E, C>(
+    db: &C,
+    select: Select<E>,
+    request: &ListRequest,
+) -> Result<Page<E::Model>, DbErr>
+where
+    E: EntityTrait,
+    E::Column: FromStr,
+    E::Model: ModelTrait<Entity = E>,
+    C: ConnectionTrait,
+{
+    let mut select = select.filter(filters_condition::<E>(&request.filters)?);
+    let size = request.size;
+
+    let (backward, after) = match &request.pagination {
+        Pagination::Cursor(Some(cursor)) => (cursor.backward, Some(cursor)),
+        _ => (false, None),
+    };
+    if let Some(cursor) = after {
+        select = select.filter(keyset_condition::<E>(request, cursor)?);
+    }
+    for sort in &request.sort {
+        let forward = sort.direction == SortDirection::Asc;
+        let order = if forward != backward { Order::Asc } else { Order::Desc };
+        select = select.order_by(column::<E>(sort.field)?, order);
+    }
+    if let Pagination::Page(page) = request.pagination {
+        select = select.offset((page - 1) * size);
+    }
+
+    let mut items = select.limit(size + 1).all(db).await?;
+    let has_more = items.len() as u64 > size;
+    items.truncate(size as usize);
+    if backward {
+        items.reverse();
+    }
+
+    let mut page = Page {
+        items: Vec::new(),
+        size,
+        page: None,
+        next_cursor: None,
+        prev_cursor: None,
+    };
+    match request.pagination {
+        Pagination::Page(number) => {
+            page.page = Some(number);
+        }
+        Pagination::Cursor(_) => {
+            let first = items.first().map(|m| cursor_for(request, m, true));
+            let last = items.last().map(|m| cursor_for(request, m, false));
+            if backward {
+                page.prev_cursor = if has_more { first } else { None };
+                page.next_cursor = last;
+            } else {
+                page.next_cursor = if has_more { last } else { None };
+                page.prev_cursor = if after.is_some() { first } else { None };
+            }
+        }
+    }
+    page.items = items;
+    Ok(page)
+}
+
+fn column<E>(field: &FieldSpec) -> Result<E::Column, DbErr>
+where
+    E: EntityTrait,
+    E::Column: FromStr,
+{
+    E::Column::from_str(field.name)
+        .map_err(|_| DbErr::Custom(format!("Unknown column {}", field.name)))
+}
+
+fn filters_condition<E>(filters: &[Filter]) -> Result<Condition, DbErr>
+where
+    E: EntityTrait,
+    E::Column: FromStr,
+{
+    let mut condition = Condition::all();
+    for filter in filters {
+        let col = column::<E>(filter.field)?;
+        let values: Vec<Value> =
+            filter.values.iter().map(filter_value).collect();
+        let first = values.first().cloned().unwrap_or(Value::Bool(None));
+        let expr: SimpleExpr = match filter.op {
+            FilterOp::Eq => col.eq(first),
+            FilterOp::Ne => col.ne(first),
+            FilterOp::Gt => col.gt(first),
+            FilterOp::Gte => col.gte(first),
+            FilterOp::Lt => col.lt(first),
+            FilterOp::Lte => col.lte(first),
+            FilterOp::In => col.is_in(values),
+            FilterOp::Like => match &filter.values[..] {
+                [FilterValue::Text(pattern)] => col.like(pattern.as_str()),
+                _ => col.eq(first),
+            },
+        };
+        condition = condition.add(expr);
+    }
+    Ok(condition)
+}
+
+fn filter_value(value: &FilterValue) -> Value {
+    match value {
+        FilterValue::Int(v) => Value::BigInt(Some(*v)),
+        FilterValue::Text(v) => Value::String(Some(Box::new(v.clone()))),
+    }
+}
+
+/// `(a, b, id) > (va, vb, vid)` с учётом направления каждого поля:
+/// `a > va OR (a = va AND b < vb) OR (a = va AND b = vb AND id > vid)`.
+fn keyset_condition<E>(
+    request: &ListRequest,
+    cursor: &Cursor,
+) -> Result<Condition, DbErr>
+where
+    E: EntityTrait,
+    E::Column: FromStr,
+{
+    let mut any = Condition::any();
+    for (i, sort) in request.sort.iter().enumerate() {
+        let mut branch = Condition::all();
+        for (prev, value) in request.sort[..i].iter().zip(&cursor.values) {
+            branch = branch.add(
+                column::<E>(prev.field)?.eq(json_value(prev.field, value)?),
+            );
+        }
+        let col = column::<E>(sort.field)?;
+        let value = json_value(sort.field, &cursor.values[i])?;
+        let ascending =
+            (sort.direction == SortDirection::Asc) != cursor.backward;
+        branch =
+            branch.add(if ascending { col.gt(value) } else { col.lt(value) });
+        any = any.add(branch);
+    }
+    Ok(any)
+}
+
+fn json_value(
+    field: &FieldSpec,
+    value: &serde_json::Value,
+) -> Result<Value, DbErr> {
+    let invalid =
+        || DbErr::Custom(format!("Invalid cursor for {}", field.name));
+    Ok(match field.kind {
+        FieldKind::Int => {
+            Value::BigInt(Some(value.as_i64().ok_or_else(invalid)?))
+        }
+        FieldKind::Text => Value::String(Some(Box::new(
+            value.as_str().ok_or_else(invalid)?.to_string(),
+        ))),
+    })
+}
+
+fn cursor_for<M, E>(request: &ListRequest, model: &M, backward: bool) -> String
+where
+    M: ModelTrait<Entity = E>,
+    E: EntityTrait,
+    E::Column: FromStr,
+{
+    let values = request
+        .sort
+        .iter()
+        .map(|sort| match column::<E>(sort.field) {
+            Ok(col) => sea_value_to_json(model.get(col)),
+            Err(_) => serde_json::Value::Null,
+        })
+        .collect();
+    Cursor { values, backward, scope: request.scope() }.encode()
+}
+
+fn sea_value_to_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::SmallInt(Some(v)) => v.into(),
+        Value::Int(Some(v)) => v.into(),
+        Value::BigInt(Some(v)) => v.into(),
+        Value::String(Some(v)) => (*v).into(),
+        _ => serde_json::Value::Null,
+    }
+}
@@ -0,0 +1,98 @@
+use crate::game::results::{MatchResult, MatchStatus};
+use crate::infra::storage::entities::{
+    game_match, match_player, player_rating,
+};
+use crate::infra::storage::tx::{Conn, transaction};
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{
+    ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, Set,
+    TransactionError,
+};
+
+#[derive(Clone)]
+pub struct MatchRepository {
+    db: DatabaseConnection,
+}
+
+impl MatchRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn conn(&self) -> Conn<'_> {
+        Conn::current(&self.db)
+    }
+
+    /// `None`, если игрок ещё не играл.
+    pub async fn rating(&self, user_id: i32) -> Result<Option<i32>, DbErr> {
+        Ok(player_rating::Entity::find_by_id(user_id)
+            .one(&self.conn())
+            .await?
+            .map(|row| row.rating))
+    }
+
+    /// Сохраняет матч, итоги игроков и новые рейтинги одной транзакцией.
+    /// Прерванный матч рейтинги и счётчик сыгранных не трогает.
+    pub async fn save(&self, result: &MatchResult) -> Result<i32, DbErr> {
+        transaction(&self.db, async {
+            let saved = game_match::ActiveModel {
+                room_id: Set(i64::try_from(result.room_id).unwrap_or(i64::MAX)),
+                status: Set(result.status.as_str().to_string()),
+                seed: Set(result.seed.clone()),
+                started_at: Set(result.started_at.into()),
+                finished_at: Set(result.finished_at.into()),
+                ..Default::default()
+            }
+            .insert(&self.conn())
+            .await?;
+
+            for player in &result.players {
+                match_player::ActiveModel {
+                    match_id: Set(saved.id),
+                    user_id: Set(player.user_id),
+                    score: Set(i64::try_from(player.score).unwrap_or(i64::MAX)),
+                    outcome: Set(player.outcome.as_str().to_string()),
+                    rating_before: Set(player.rating_before),
+                    rating_after: Set(player.rating_after),
+                }
+                .insert(&self.conn())
+                .await?;
+
+                if result.status == MatchStatus::Interrupted {
+                    continue;
+                }
+                player_rating::Entity::insert(player_rating::ActiveModel {
+                    user_id: Set(player.user_id),
+                    rating: Set(player.rating_after),
+                    matches_played: Set(1),
+                    updated_at: Set(Utc::now().into()),
+                })
+                .on_conflict(
+                    OnConflict::column(player_rating::Column::UserId)
+                        .update_columns([
+                            player_rating::Column::Rating,
+                            player_rating::Column::UpdatedAt,
+                        ])
+                        .value(
+                            player_rating::Column::MatchesPlayed,
+                            Expr::col((
+                                player_rating::Entity,
+                                player_rating::Column::MatchesPlayed,
+                            ))
+                            .add(1),
+                        )
+                        .to_owned(),
+                )
+                .exec(&self.conn())
+                .await?;
+            }
+            Ok(saved.id)
+        })
+        .await
+        .map_err(|e| match e {
+            TransactionError::Connection(e)
+            | TransactionError::Transaction(e) => e,
+        })
+    }
+}
@@ -1 +1,13 @@
-
+pub mod blob_meta;
+pub mod blobs;
+pub mod cron_jobs;
+pub mod cron_leases;
+pub mod cron_runs;
+pub mod entities;
+pub mod listing;
+pub mod matches;
+pub mod posts;
+pub mod rate_limit;
+pub mod tx;
+pub mod webhook_deliveries;
+pub mod world_snapshots;
@@ -0,0 +1,115 @@
+use crate::core::listing::{ListRequest, Page};
+use crate::infra::storage::entities::post;
+use crate::infra::storage::listing::fetch_page;
+use crate::infra::storage::tx::Conn;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, Set,
+};
+
+#[derive(Clone)]
+pub struct PostRepository {
+    db: DatabaseConnection,
+}
+
+impl PostRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Транзакция текущей задачи, если она открыта, иначе пул.
+    fn conn(&self) -> Conn<'_> {
+        Conn::current(&self.db)
+    }
+
+    pub async fn create(
+        &self,
+        author_id: i32,
+        title: String,
+        text: String,
+    ) -> Result<post::Model, DbErr> {
+        post::ActiveModel {
+            title: Set(title),
+            text: Set(text),
+            author_id: Set(author_id),
+            ..Default::default()
+        }
+        .insert(&self.conn())
+        .await
+    }
+
+    /// Вставляет пачку постов одним `INSERT`, возвращает число строк.
+    pub async fn insert_many(
+        &self,
+        author_id: i32,
+        posts: Vec<(String, String)>,
+    ) -> Result<u64, DbErr> {
+        if posts.is_empty() {
+            return Ok(0);
+        }
+        let models = posts.into_iter().map(|(title, text)| post::ActiveModel {
+            title: Set(title),
+            text: Set(text),
+            author_id: Set(author_id),
+            ..Default::default()
+        });
+        post::Entity::insert_many(models)
+            .exec_without_returning(&self.conn())
+            .await
+    }
+
+    pub async fn get_by_id(
+        &self,
+        id: i32,
+    ) -> Result<Option<post::Model>, DbErr> {
+        post::Entity::find_by_id(id).one(&self.conn()).await
+    }
+
+    /// Обновляет пост и увеличивает версию. При `expected_version`
+    /// запись меняется, только если версия в базе совпадает; `None`
+    /// в ответе означает, что пост успели изменить или удалить.
+    pub async fn update(
+        &self,
+        id: i32,
+        expected_version: Option<i32>,
+        title: String,
+        text: String,
+    ) -> Result<Option<post::Model>, DbErr> {
+        let mut query = post::Entity::update_many()
+            .col_expr(post::Column::Title, Expr::value(title))
+            .col_expr(post::Column::Text, Expr::value(text))
+            .col_expr(
+                post::Column::Version,
+                Expr::col(post::Column::Version).add(1),
+            )
+            .filter(post::Column::Id.eq(id));
+        if let Some(version) = expected_version {
+            query = query.filter(post::Column::Version.eq(version));
+        }
+        let updated = query.exec_with_returning(&self.conn()).await?;
+        Ok(updated.into_iter().next())
+    }
+
+    /// Удаляет пост; `false`, если версия не совпала или поста уже нет.
+    pub async fn delete(
+        &self,
+        id: i32,
+        expected_version: Option<i32>,
+    ) -> Result<bool, DbErr> {
+        let mut query =
+            post::Entity::delete_many().filter(post::Column::Id.eq(id));
+        if let Some(version) = expected_version {
+            query = query.filter(post::Column::Version.eq(version));
+        }
+        let result = query.exec(&self.conn()).await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    pub async fn list(
+        &self,
+        request: &ListRequest,
+    ) -> Result<Page<post::Model>, DbErr> {
+        fetch_page(&self.conn(), post::Entity::find(), request).await
+    }
+}
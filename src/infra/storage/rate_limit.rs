@@ -0,0 +1,118 @@
+use crate::core::rate_limit::{Decision, Gcra, RateLimitStore};
+use async_trait::async_trait;
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, Statement};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Сколько ключей держим в памяти, прежде чем чистить устаревшие.
+const MEMORY_STORE_PRUNE_AT: usize = 10_000;
+
+/// Общее для всех инстансов хранилище лимитов в таблице `rate_limit`.
+///
+/// Проверка и обновление TAT выполняются одним `INSERT ... ON CONFLICT`,
+/// так что конкурентные запросы с разных инстансов не обгоняют друг друга.
+pub struct PostgresRateLimitStore {
+    db: DatabaseConnection,
+}
+
+impl PostgresRateLimitStore {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for PostgresRateLimitStore {
+    async fn check(
+        &self,
+        key: &str,
+        gcra: Gcra,
+        now_ms: i64,
+    ) -> anyhow::Result<Decision> {
+        let reserved = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                INSERT INTO rate_limit (key, tat) VALUES ($1, $2 + $3)
+                ON CONFLICT (key) DO UPDATE
+                SET tat = GREATEST(rate_limit.tat, $2) + $3
+                WHERE GREATEST(rate_limit.tat, $2) + $3 - $2 <= $4
+                RETURNING tat
+                "#,
+                [
+                    key.into(),
+                    now_ms.into(),
+                    gcra.interval_ms.into(),
+                    gcra.tolerance_ms.into(),
+                ],
+            ))
+            .await?;
+        if let Some(row) = reserved {
+            let tat: i64 = row.try_get("", "tat")?;
+            return Ok(gcra.allowed(tat, now_ms));
+        }
+
+        let current = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "SELECT tat FROM rate_limit WHERE key = $1",
+                [key.into()],
+            ))
+            .await?;
+        let tat = match current {
+            Some(row) => row.try_get("", "tat")?,
+            None => now_ms,
+        };
+        Ok(gcra.denied(tat, now_ms))
+    }
+
+    async fn purge(&self, now_ms: i64) -> anyhow::Result<u64> {
+        let result = self
+            .db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                "DELETE FROM rate_limit WHERE tat <= $1",
+                [now_ms.into()],
+            ))
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Хранилище в памяти процесса: лимиты действуют только в пределах инстанса.
+#[derive(Default)]
+pub struct MemoryRateLimitStore {
+    tats: Mutex<HashMap<String, i64>>,
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryRateLimitStore {
+    async fn check(
+        &self,
+        key: &str,
+        gcra: Gcra,
+        now_ms: i64,
+    ) -> anyhow::Result<Decision> {
+        let mut tats = self.tats.lock().expect("rate limit store poisoned");
+        if tats.len() > MEMORY_STORE_PRUNE_AT {
+            tats.retain(|_, tat| *tat > now_ms);
+        }
+        let tat = tats.get(key).copied();
+        match gcra.next_tat(tat, now_ms) {
+            Some(next) => {
+                tats.insert(key.to_string(), next);
+                Ok(gcra.allowed(next, now_ms))
+            }
+            None => Ok(gcra.denied(tat.unwrap_or(now_ms), now_ms)),
+        }
+    }
+
+    async fn purge(&self, now_ms: i64) -> anyhow::Result<u64> {
+        let mut tats = self.tats.lock().expect("rate limit store poisoned");
+        let before = tats.len();
+        tats.retain(|_, tat| *tat > now_ms);
+        Ok((before - tats.len()) as u64)
+    }
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DatabaseTransaction, DbBackend, DbErr,
+    ExecResult, QueryResult, Statement, TransactionError, TransactionTrait,
+};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+type Deferred = Box<dyn FnOnce() + Send>;
+
+tokio::task_local! {
+    static CURRENT_TX: Arc<DatabaseTransaction>;
+    static AFTER_COMMIT: Arc<Mutex<Vec<Deferred>>>;
+}
+
+/// Выполняет `f` после commit текущей `transaction`, а вне транзакции —
+/// сразу. При rollback отложенные действия отбрасываются.
+pub fn after_commit(f: impl FnOnce() + Send + 'static) {
+    match AFTER_COMMIT.try_with(Arc::clone) {
+        Ok(queue) => {
+            queue.lock().expect("after_commit queue poisoned").push(Box::new(f))
+        }
+        Err(_) => f(),
+    }
+}
+
+/// Выполняет `f` в одной транзакции: все репозитории, вызванные внутри
+/// (в той же задаче tokio), пишут через неё. `Ok` — commit, `Err` — rollback.
+///
+/// Вложенный вызов новую транзакцию не открывает, а присоединяется
+/// к уже открытой.
+pub async fn transaction<F, T, E>(
+    db: &DatabaseConnection,
+    f: F,
+) -> Result<T, TransactionError<E>>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    if CURRENT_TX.try_with(|_| ()).is_ok() {
+        return f.await.map_err(TransactionError::Transaction);
+    }
+
+    let tx = Arc::new(db.begin().await.map_err(TransactionError::Connection)?);
+    let deferred = Arc::new(Mutex::new(Vec::new()));
+    let result = CURRENT_TX
+        .scope(tx.clone(), AFTER_COMMIT.scope(deferred.clone(), f))
+        .await;
+    let tx = Arc::into_inner(tx).ok_or_else(|| {
+        TransactionError::Connection(DbErr::Custom(
+            "transaction is still in use after scope ended".to_string(),
+        ))
+    })?;
+    match result {
+        Ok(value) => {
+            tx.commit().await.map_err(TransactionError::Connection)?;
+            let deferred = std::mem::take(
+                &mut *deferred.lock().expect("after_commit queue poisoned"),
+            );
+            deferred.into_iter().for_each(|f| f());
+            Ok(value)
+        }
+        Err(e) => {
+            tx.rollback().await.map_err(TransactionError::Connection)?;
+            Err(TransactionError::Transaction(e))
+        }
+    }
+}
+
+/// Соединение для запроса: открытая `transaction` текущей задачи или пул.
+pub enum Conn<'a> {
+    Pool(&'a DatabaseConnection),
+    Tx(Arc<DatabaseTransaction>),
+}
+
+impl<'a> Conn<'a> {
+    pub fn current(db: &'a DatabaseConnection) -> Self {
+        CURRENT_TX.try_with(|tx| Conn::Tx(tx.clone())).unwrap_or(Conn::Pool(db))
+    }
+}
+
+#[async_trait]
+impl ConnectionTrait for Conn<'_> {
+    fn get_database_backend(&self) -> DbBackend {
+        match self {
+            Conn::Pool(db) => db.get_database_backend(),
+            Conn::Tx(tx) => tx.get_database_backend(),
+        }
+    }
+
+    async fn execute(&self, stmt: Statement) -> Result<ExecResult, DbErr> {
+        match self {
+            Conn::Pool(db) => db.execute(stmt).await,
+            Conn::Tx(tx) => tx.execute(stmt).await,
+        }
+    }
+
+    async fn execute_unprepared(&self, sql: &str) -> Result<ExecResult, DbErr> {
+        match self {
+            Conn::Pool(db) => db.execute_unprepared(sql).await,
+            Conn::Tx(tx) => tx.execute_unprepared(sql).await,
+        }
+    }
+
+    async fn query_one(
+        &self,
+        stmt: Statement,
+    ) -> Result<Option<QueryResult>, DbErr> {
+        match self {
+            Conn::Pool(db) => db.query_one(stmt).await,
+            Conn::Tx(tx) => tx.query_one(stmt).await,
+        }
+    }
+
+    async fn query_all(
+        &self,
+        stmt: Statement,
+    ) -> Result<Vec<QueryResult>, DbErr> {
+        match self {
+            Conn::Pool(db) => db.query_all(stmt).await,
+            Conn::Tx(tx) => tx.query_all(stmt).await,
+        }
+    }
+}
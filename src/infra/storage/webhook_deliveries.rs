@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ConnectionTrait, DatabaseConnection, DbBackend, DbErr, Statement,
+};
+
+/// Принятые вебхуки в таблице `webhook_delivery`, общей для всех
+/// инстансов.
+///
+/// Строка живёт, пока подпись доставки проходит проверку по времени:
+/// позже повтор и так отклоняется как устаревший. Истёкшие строки
+/// удаляются при записи новых.
+#[derive(Clone)]
+pub struct WebhookDeliveryRepository {
+    db: DatabaseConnection,
+}
+
+impl WebhookDeliveryRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Запоминает доставку. `false` — такую уже принимали и она ещё не
+    /// истекла.
+    pub async fn remember(
+        &self,
+        digest: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, DbErr> {
+        self.db
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                "DELETE FROM webhook_delivery WHERE expires_at <= now()",
+            ))
+            .await?;
+        let inserted = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                INSERT INTO webhook_delivery (digest, expires_at)
+                VALUES ($1, $2)
+                ON CONFLICT (digest) DO UPDATE
+                SET expires_at = EXCLUDED.expires_at
+                WHERE webhook_delivery.expires_at <= now()
+                RETURNING digest
+                "#,
+                [digest.into(), expires_at.into()],
+            ))
+            .await?;
+        Ok(inserted.is_some())
+    }
+}
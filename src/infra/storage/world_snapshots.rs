@@ -0,0 +1,79 @@
+use crate::infra::storage::entities::world_snapshot;
+use crate::infra::storage::tx::Conn;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set,
+};
+
+/// Новая запись снимка мира.
+pub struct NewWorldSnapshot {
+    pub tick: u64,
+    pub players: usize,
+    pub encoding: &'static str,
+    pub checksum: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone)]
+pub struct WorldSnapshotRepository {
+    db: DatabaseConnection,
+}
+
+impl WorldSnapshotRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn conn(&self) -> Conn<'_> {
+        Conn::current(&self.db)
+    }
+
+    pub async fn create(
+        &self,
+        snapshot: NewWorldSnapshot,
+    ) -> Result<world_snapshot::Model, DbErr> {
+        world_snapshot::ActiveModel {
+            tick: Set(i64::try_from(snapshot.tick).unwrap_or(i64::MAX)),
+            players: Set(i32::try_from(snapshot.players).unwrap_or(i32::MAX)),
+            encoding: Set(snapshot.encoding.to_string()),
+            checksum: Set(snapshot.checksum),
+            data: Set(snapshot.data),
+            ..Default::default()
+        }
+        .insert(&self.conn())
+        .await
+    }
+
+    /// Последние `limit` снимков, новые первыми.
+    pub async fn latest(
+        &self,
+        limit: u64,
+    ) -> Result<Vec<world_snapshot::Model>, DbErr> {
+        world_snapshot::Entity::find()
+            .order_by_desc(world_snapshot::Column::Id)
+            .limit(limit)
+            .all(&self.conn())
+            .await
+    }
+
+    /// Удаляет всё, кроме `keep` последних снимков.
+    pub async fn prune(&self, keep: u64) -> Result<u64, DbErr> {
+        let oldest_kept: Option<i32> = world_snapshot::Entity::find()
+            .select_only()
+            .column(world_snapshot::Column::Id)
+            .order_by_desc(world_snapshot::Column::Id)
+            .offset(keep.saturating_sub(1))
+            .limit(1)
+            .into_tuple()
+            .one(&self.conn())
+            .await?;
+        let Some(oldest_kept) = oldest_kept else {
+            return Ok(0);
+        };
+        let deleted = world_snapshot::Entity::delete_many()
+            .filter(world_snapshot::Column::Id.lt(oldest_kept))
+            .exec(&self.conn())
+            .await?;
+        Ok(deleted.rows_affected)
+    }
+}
@@ -4,6 +4,7 @@ mod api;
 mod app;
 mod core;
 mod cron;
+mod game;
 mod infra;
 mod logger;
 mod mediator;
@@ -12,10 +13,21 @@ mod state;
 use crate::app::App;
 use crate::configs::Config;
 use crate::logger::Tracing;
+use std::path::Path;
+use std::{env, process};
 use tokio::runtime::{Builder, Handle};
 use tracing::info;
 
 fn main() {
+    // `rust-service replay <file>` проверяет реплей и выходит, не
+    // поднимая сервер: ни конфиг, ни база для этого не нужны
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let [command, path] = args.as_slice()
+        && command == "replay"
+    {
+        process::exit(game::replay::run_cli(Path::new(path)));
+    }
+
     let cfg = Config::load();
     let app = App::new(cfg.clone());
 
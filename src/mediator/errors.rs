@@ -1,3 +1,4 @@
+use crate::core::errors::DomainError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -14,6 +15,25 @@ pub enum MediatorError {
     #[error("Query type mismatch for {0}")]
     QueryTypeMismatch(String),
 
+    #[error("Command result type mismatch for {0}")]
+    CommandResultMismatch(String),
+
     #[error("Query result type mismatch for {0}")]
     QueryResultMismatch(String),
 }
+
+/// Ошибка вызова команды по имени.
+#[derive(Debug, Error)]
+pub enum DispatchError {
+    #[error("Unknown command {0}")]
+    UnknownCommand(String),
+
+    #[error("Invalid payload for {command}: {message}")]
+    InvalidPayload { command: &'static str, message: String },
+
+    #[error(transparent)]
+    Domain(#[from] DomainError),
+
+    #[error(transparent)]
+    Mediator(#[from] MediatorError),
+}
@@ -1,10 +1,14 @@
+use crate::core::handlers::base::{
+    CommandContext, CommandOutcome, NamedCommand, NamedQuery,
+};
 use crate::core::handlers::hello::{
     Command, CommandHandler, Query, QueryHandler,
 };
-use crate::mediator::errors::MediatorError;
+use crate::mediator::errors::{DispatchError, MediatorError};
 use crate::state::AppState;
 use axum::extract::FromRef;
 use futures_util::future::BoxFuture;
+use serde_json::Value;
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -13,8 +17,12 @@ use tracing::error;
 // Типы замыканий
 
 type CommandFn = Arc<
-    dyn Fn(Box<dyn Any + Send>) -> BoxFuture<'static, Result<(), MediatorError>>
-        + Send
+    dyn Fn(
+            Box<dyn Any + Send>,
+        ) -> BoxFuture<
+            'static,
+            Result<Box<dyn Any + Send + Sync>, MediatorError>,
+        > + Send
         + Sync,
 >;
 
@@ -28,9 +36,16 @@ type QueryFn = Arc<
         + Sync,
 >;
 
+type NamedFn = for<'a> fn(
+    &'a Mediator,
+    Value,
+    CommandContext,
+) -> BoxFuture<'a, Result<Value, DispatchError>>;
+
 pub struct Mediator {
     commands: Mutex<HashMap<TypeId, CommandFn>>,
     queries: Mutex<HashMap<TypeId, QueryFn>>,
+    named: Mutex<HashMap<&'static str, NamedFn>>,
 }
 
 impl FromRef<AppState> for Arc<Mediator> {
@@ -43,13 +58,15 @@ impl Mediator {
         Self {
             commands: Mutex::new(HashMap::new()),
             queries: Mutex::new(HashMap::new()),
+            named: Mutex::new(HashMap::new()),
         }
     }
 
-    pub async fn register_command<C, H>(&self, handler: H)
+    pub async fn register_command<C, R, H>(&self, handler: H)
     where
         C: Command + 'static,
-        H: CommandHandler<C> + Send + Sync + 'static,
+        R: Send + Sync + 'static,
+        H: CommandHandler<C, R> + Send + Sync + 'static,
     {
         let handler = Arc::new(handler);
 
@@ -61,8 +78,8 @@ impl Mediator {
                         std::any::type_name::<C>().to_string(),
                     )
                 })?;
-                handler.execute(*cmd).await;
-                Ok(())
+                let result = handler.execute(*cmd).await;
+                Ok(Box::new(result) as Box<dyn Any + Send + Sync>)
             })
         });
 
@@ -93,12 +110,58 @@ impl Mediator {
         self.queries.lock().await.insert(TypeId::of::<Q>(), f);
     }
 
-    pub async fn send<C: Command + 'static>(
+    /// Делает уже зарегистрированную команду доступной по `C::NAME`.
+    pub async fn register_named<C, R>(&self)
+    where
+        C: NamedCommand,
+        R: CommandOutcome,
+    {
+        self.named.lock().await.insert(C::NAME, dispatch_named::<C, R>);
+    }
+
+    /// То же для запроса: `dispatch` по `Q::NAME` вызовет `query`.
+    pub async fn register_named_query<Q, R>(&self)
+    where
+        Q: NamedQuery,
+        R: CommandOutcome,
+    {
+        self.named.lock().await.insert(Q::NAME, dispatch_named_query::<Q, R>);
+    }
+
+    /// Зарегистрирована ли команда или запрос с таким именем.
+    pub async fn is_named(&self, name: &str) -> bool {
+        self.named.lock().await.contains_key(name)
+    }
+
+    /// Вызывает команду или запрос по имени: JSON-тело разбирается в `C::Payload`,
+    /// результат сериализуется обратно в JSON.
+    pub async fn dispatch(
+        &self,
+        name: &str,
+        payload: Value,
+        ctx: CommandContext,
+    ) -> Result<Value, DispatchError> {
+        let f =
+            self.named.lock().await.get(name).copied().ok_or_else(|| {
+                DispatchError::UnknownCommand(name.to_string())
+            })?;
+        f(self, payload, ctx).await
+    }
+
+    pub async fn send<C: Command + 'static, R: Send + Sync + 'static>(
         &self,
         command: C,
-    ) -> Result<(), MediatorError> {
+    ) -> Result<R, MediatorError> {
         let f = self.get_command::<C>().await?;
-        f(Box::new(command)).await
+
+        f(Box::new(command)).await?.downcast::<R>().map(|b| *b).map_err(|_| {
+            let msg = format!(
+                "Command result type mismatch for {}",
+                std::any::type_name::<C>()
+            );
+            error!("{msg}");
+            MediatorError::CommandResultMismatch(msg)
+        })
     }
 
     pub async fn query<Q: Query + 'static, R: Send + Sync + 'static>(
@@ -152,3 +215,49 @@ impl Mediator {
         )
     }
 }
+
+fn dispatch_named<'a, C, R>(
+    mediator: &'a Mediator,
+    payload: Value,
+    ctx: CommandContext,
+) -> BoxFuture<'a, Result<Value, DispatchError>>
+where
+    C: NamedCommand,
+    R: CommandOutcome,
+{
+    Box::pin(async move {
+        let payload =
+            serde_json::from_value::<C::Payload>(payload).map_err(|e| {
+                DispatchError::InvalidPayload {
+                    command: C::NAME,
+                    message: e.to_string(),
+                }
+            })?;
+        let command = C::from_payload(payload, &ctx)?;
+        let outcome = mediator.send::<C, R>(command).await?;
+        Ok(outcome.into_json()?)
+    })
+}
+
+fn dispatch_named_query<'a, Q, R>(
+    mediator: &'a Mediator,
+    payload: Value,
+    ctx: CommandContext,
+) -> BoxFuture<'a, Result<Value, DispatchError>>
+where
+    Q: NamedQuery,
+    R: CommandOutcome,
+{
+    Box::pin(async move {
+        let payload =
+            serde_json::from_value::<Q::Payload>(payload).map_err(|e| {
+                DispatchError::InvalidPayload {
+                    command: Q::NAME,
+                    message: e.to_string(),
+                }
+            })?;
+        let query = Q::from_payload(payload, &ctx)?;
+        let outcome = mediator.query::<Q, R>(query).await?;
+        Ok(outcome.into_json()?)
+    })
+}
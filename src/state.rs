@@ -1,12 +1,32 @@
+use crate::api::middleware::rate_limit::RateLimiter;
+use crate::api::ws::hub::Hub;
 use crate::configs::Config;
+use crate::core::events::EventBus;
+use crate::cron::ProjectCron;
+use crate::game::rooms::Rooms;
+use crate::infra::storage::blobs::BlobStore;
 use crate::mediator::mediator::Mediator;
 use axum::extract::FromRef;
+use sea_orm::DatabaseConnection;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Clone)]
 pub struct AppState {
     pub cfg: Config,
     pub mediator: Arc<Mediator>,
+    pub db: DatabaseConnection,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub blobs: Arc<dyn BlobStore>,
+    pub events: EventBus,
+    pub ws_hub: Arc<Hub>,
+    /// Лобби и матчи: куда идёт ввод игрока и откуда брать снимки
+    pub rooms: Arc<Rooms>,
+    /// Задачи по расписанию, для админских эндпоинтов
+    pub cron: Arc<ProjectCron>,
+    /// Отменяется при остановке приложения; долгие ответы (SSE)
+    /// завершаются по нему, не дожидаясь клиента
+    pub shutdown: CancellationToken,
 }
 impl FromRef<Arc<AppState>> for AppState {
     fn from_ref(state: &Arc<AppState>) -> Self {
@@ -14,7 +34,30 @@ impl FromRef<Arc<AppState>> for AppState {
     }
 }
 impl AppState {
-    pub async fn setup(cfg: Config, mediator: Arc<Mediator>) -> Self {
-        AppState { cfg, mediator }
+    #[allow(clippy::too_many_arguments)]
+    pub async fn setup(
+        cfg: Config,
+        mediator: Arc<Mediator>,
+        db: DatabaseConnection,
+        blobs: Arc<dyn BlobStore>,
+        events: EventBus,
+        ws_hub: Arc<Hub>,
+        rooms: Arc<Rooms>,
+        cron: Arc<ProjectCron>,
+        shutdown: CancellationToken,
+    ) -> Self {
+        let rate_limiter = Arc::new(RateLimiter::new(&cfg, db.clone()));
+        AppState {
+            cfg,
+            mediator,
+            db,
+            rate_limiter,
+            blobs,
+            events,
+            ws_hub,
+            rooms,
+            cron,
+            shutdown,
+        }
     }
 }